@@ -2,39 +2,48 @@ use editor::Editor;
 use extension_host::ExtensionStore;
 use futures::StreamExt;
 use gpui::{
-    Animation, AnimationExt as _, App, Context, CursorStyle, Entity, EventEmitter,
-    InteractiveElement as _, ParentElement as _, Render, SharedString, StatefulInteractiveElement,
-    Styled, Transformation, Window, actions, percentage,
+    Animation, AnimationExt as _, App, ClipboardItem, Context, CursorStyle, Entity, EventEmitter,
+    InteractiveElement as _, ParentElement as _, PromptLevel, Render, SharedString,
+    StatefulInteractiveElement, Styled, Task, Transformation, Window, actions, percentage,
 };
 use language::{
-    BinaryStatus, LanguageRegistry, LanguageServerId, LanguageServerName,
-    LanguageServerStatusUpdate, ServerHealth,
+    BinaryStatus, LanguageRegistry, LanguageServerName, LanguageServerStatusUpdate, ServerHealth,
 };
 use project::{
-    EnvironmentErrorMessage, LanguageServerProgress, LspStoreEvent, Project,
+    EnvironmentErrorMessage, LanguageServerResources, LspStore, LspStoreEvent, Project,
     ProjectEnvironmentEvent,
-    git_store::{GitStoreEvent, Repository},
+    git_store::{GitStore, GitStoreEvent, Repository},
+    project_settings::ProjectSettings,
 };
+use settings::{Settings as _, update_settings_file};
 use smallvec::SmallVec;
 use std::{
-    cmp::Reverse,
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Write,
     path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
 use ui::{ButtonLike, ContextMenu, PopoverMenu, PopoverMenuHandle, Tooltip, prelude::*};
-use util::truncate_and_trailoff;
-use workspace::{StatusItemView, Workspace, item::ItemHandle};
+use util::{size::format_file_size, truncate_and_trailoff};
+use workspace::{
+    StatusItemView, Toast, Workspace, item::ItemHandle, notifications::NotificationId,
+};
 
-const GIT_OPERATION_DELAY: Duration = Duration::from_millis(0);
+const SERVER_RECOVERED_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+const MAX_STATUS_HISTORY_LEN: usize = 16;
+const RUST_ANALYZER_NAME: LanguageServerName = LanguageServerName::new_static("rust-analyzer");
+/// Parts of the health messages rust-analyzer reports when its proc-macro server can't be
+/// spawned or crashes, leaving proc macros unexpanded.
+const PROC_MACRO_FAILURE_MARKERS: &[&str] = &["proc-macro server", "proc macro server"];
 
 actions!(
     activity_indicator,
     [
         /// Displays error messages from language servers in the status bar.
-        ShowErrorMessage
+        ShowErrorMessage,
+        /// Cancels every cancellable piece of work the language servers are running.
+        CancelAllLanguageServerWork
     ]
 );
 
@@ -43,10 +52,28 @@ pub enum Event {
         server_name: LanguageServerName,
         status: SharedString,
     },
+    CopyStatus {
+        server_name: LanguageServerName,
+        status: SharedString,
+    },
 }
 
 pub struct ActivityIndicator {
     statuses: Vec<ServerStatus>,
+    /// Servers that went back to a healthy state after reporting a warning or an error, shown
+    /// briefly so that the recovery doesn't go unnoticed.
+    recovered_servers: Vec<(LanguageServerName, Instant)>,
+    /// Servers whose last binary status was `Stopped`, so that starting them again counts as
+    /// a restart.
+    stopped_servers: HashSet<LanguageServerName>,
+    /// How many times each server was started again after it had stopped.
+    restart_counts: HashMap<LanguageServerName, usize>,
+    /// The most recent statuses of each server, oldest first.
+    status_history: HashMap<LanguageServerName, VecDeque<LanguageServerStatusUpdate>>,
+    /// The message of the last formatting failure, kept up to date by the project's events.
+    formatting_failure: Option<String>,
+    /// Renders again once the git activity delay has passed since the latest jobs update.
+    git_job_delay_task: Option<Task<()>>,
     project: Entity<Project>,
     context_menu_handle: PopoverMenuHandle<ContextMenu>,
 }
@@ -57,12 +84,6 @@ struct ServerStatus {
     status: LanguageServerStatusUpdate,
 }
 
-struct PendingWork<'a> {
-    language_server_id: LanguageServerId,
-    progress_token: &'a str,
-    progress: &'a LanguageServerProgress,
-}
-
 struct Content {
     icon: Option<gpui::AnyElement>,
     message: String,
@@ -85,11 +106,8 @@ impl ActivityIndicator {
             cx.spawn(async move |this, cx| {
                 while let Some((name, binary_status)) = status_events.next().await {
                     this.update(cx, |this: &mut ActivityIndicator, cx| {
-                        this.statuses.retain(|s| s.name != name);
-                        this.statuses.push(ServerStatus {
-                            name,
-                            status: LanguageServerStatusUpdate::Binary(binary_status),
-                        });
+                        let status = LanguageServerStatusUpdate::Binary(binary_status);
+                        this.set_server_status(name, status);
                         cx.notify();
                     })?;
                 }
@@ -111,146 +129,274 @@ impl ActivityIndicator {
             )
             .detach();
 
-            cx.subscribe(
-                &project.read(cx).lsp_store(),
-                |activity_indicator, _, event, cx| match event {
-                    LspStoreEvent::LanguageServerUpdate { name, message, .. } => {
-                        if let proto::update_language_server::Variant::StatusUpdate(status_update) =
-                            message
-                        {
-                            let Some(name) = name.clone() else {
-                                return;
-                            };
-                            let status = match &status_update.status {
-                                Some(proto::status_update::Status::Binary(binary_status)) => {
-                                    if let Some(binary_status) =
-                                        proto::ServerBinaryStatus::from_i32(*binary_status)
-                                    {
-                                        let binary_status = match binary_status {
-                                            proto::ServerBinaryStatus::None => BinaryStatus::None,
-                                            proto::ServerBinaryStatus::CheckingForUpdate => {
-                                                BinaryStatus::CheckingForUpdate
-                                            }
-                                            proto::ServerBinaryStatus::Downloading => {
-                                                BinaryStatus::Downloading
-                                            }
-                                            proto::ServerBinaryStatus::Starting => {
-                                                BinaryStatus::Starting
-                                            }
-                                            proto::ServerBinaryStatus::Stopping => {
-                                                BinaryStatus::Stopping
-                                            }
-                                            proto::ServerBinaryStatus::Stopped => {
-                                                BinaryStatus::Stopped
-                                            }
-                                            proto::ServerBinaryStatus::Failed => {
-                                                let Some(error) = status_update.message.clone()
-                                                else {
-                                                    return;
-                                                };
-                                                BinaryStatus::Failed { error }
-                                            }
-                                        };
-                                        LanguageServerStatusUpdate::Binary(binary_status)
-                                    } else {
-                                        return;
+            Self::for_project(project.clone(), cx)
+        });
+
+        cx.subscribe_in(
+            &this,
+            window,
+            move |workspace, _, event, window, cx| match event {
+                Event::ShowStatus {
+                    server_name,
+                    status,
+                } => {
+                    let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
+                    let status = status.clone();
+                    let server_name = server_name.clone();
+                    cx.spawn_in(window, async move |workspace, cx| {
+                        let buffer = create_buffer.await?;
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.edit(
+                                [(0..0, format!("Language server {server_name}:\n\n{status}"))],
+                                None,
+                                cx,
+                            );
+                            buffer.set_capability(language::Capability::ReadOnly, cx);
+                        })?;
+                        workspace.update_in(cx, |workspace, window, cx| {
+                            workspace.add_item_to_active_pane(
+                                Box::new(cx.new(|cx| {
+                                    let mut editor = Editor::for_buffer(buffer, None, window, cx);
+                                    editor.set_read_only(true);
+                                    editor
+                                })),
+                                None,
+                                true,
+                                window,
+                                cx,
+                            );
+                        })?;
+
+                        anyhow::Ok(())
+                    })
+                    .detach();
+                }
+                Event::CopyStatus {
+                    server_name,
+                    status,
+                } => {
+                    struct CopiedLanguageServerError;
+
+                    cx.write_to_clipboard(ClipboardItem::new_string(format!(
+                        "Language server {server_name}:\n\n{status}"
+                    )));
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<CopiedLanguageServerError>(),
+                            format!("Copied {server_name} error to clipboard"),
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                }
+            },
+        )
+        .detach();
+        this
+    }
+
+    /// Subscribes to the events of the given project that affect what the indicator displays,
+    /// and creates the indicator's initial state.
+    fn for_project(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        cx.subscribe(&project.read(cx).lsp_store(), Self::handle_lsp_store_event)
+            .detach();
+
+        cx.subscribe(
+            &project.read(cx).environment().clone(),
+            |_, _, event, cx| match event {
+                ProjectEnvironmentEvent::ErrorsUpdated => cx.notify(),
+            },
+        )
+        .detach();
+
+        cx.subscribe(
+            &project.read(cx).git_store().clone(),
+            Self::handle_git_store_event,
+        )
+        .detach();
+
+        cx.subscribe(&project, Self::handle_project_event).detach();
+
+        Self {
+            statuses: Vec::new(),
+            recovered_servers: Vec::new(),
+            stopped_servers: HashSet::default(),
+            restart_counts: HashMap::default(),
+            status_history: HashMap::default(),
+            formatting_failure: project
+                .read(cx)
+                .last_formatting_failure(cx)
+                .map(ToOwned::to_owned),
+            git_job_delay_task: None,
+            project,
+            context_menu_handle: Default::default(),
+        }
+    }
+
+    fn handle_lsp_store_event(
+        &mut self,
+        _: Entity<LspStore>,
+        event: &LspStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            LspStoreEvent::LanguageServerUpdate { name, message, .. } => {
+                if let proto::update_language_server::Variant::StatusUpdate(status_update) = message
+                {
+                    let Some(name) = name.clone() else {
+                        return;
+                    };
+                    let status = match &status_update.status {
+                        Some(proto::status_update::Status::Binary(binary_status)) => {
+                            if let Some(binary_status) =
+                                proto::ServerBinaryStatus::from_i32(*binary_status)
+                            {
+                                let binary_status = match binary_status {
+                                    proto::ServerBinaryStatus::None => BinaryStatus::None,
+                                    proto::ServerBinaryStatus::CheckingForUpdate => {
+                                        BinaryStatus::CheckingForUpdate
                                     }
-                                }
-                                Some(proto::status_update::Status::Health(health_status)) => {
-                                    if let Some(health) =
-                                        proto::ServerHealth::from_i32(*health_status)
-                                    {
-                                        let health = match health {
-                                            proto::ServerHealth::Ok => ServerHealth::Ok,
-                                            proto::ServerHealth::Warning => ServerHealth::Warning,
-                                            proto::ServerHealth::Error => ServerHealth::Error,
+                                    proto::ServerBinaryStatus::Downloading => {
+                                        BinaryStatus::Downloading
+                                    }
+                                    proto::ServerBinaryStatus::Starting => BinaryStatus::Starting,
+                                    proto::ServerBinaryStatus::Stopping => BinaryStatus::Stopping,
+                                    proto::ServerBinaryStatus::Stopped => BinaryStatus::Stopped,
+                                    proto::ServerBinaryStatus::Failed => {
+                                        let Some(error) = status_update.message.clone() else {
+                                            return;
                                         };
-                                        LanguageServerStatusUpdate::Health(
-                                            health,
-                                            status_update.message.clone().map(SharedString::from),
-                                        )
-                                    } else {
-                                        return;
+                                        BinaryStatus::Failed { error }
                                     }
-                                }
-                                None => return,
-                            };
-
-                            activity_indicator.statuses.retain(|s| s.name != name);
-                            activity_indicator
-                                .statuses
-                                .push(ServerStatus { name, status });
+                                };
+                                LanguageServerStatusUpdate::Binary(binary_status)
+                            } else {
+                                return;
+                            }
                         }
-                        cx.notify()
+                        Some(proto::status_update::Status::Health(health_status)) => {
+                            if let Some(health) = proto::ServerHealth::from_i32(*health_status) {
+                                let health = match health {
+                                    proto::ServerHealth::Ok => ServerHealth::Ok,
+                                    proto::ServerHealth::Warning => ServerHealth::Warning,
+                                    proto::ServerHealth::Error => ServerHealth::Error,
+                                };
+                                LanguageServerStatusUpdate::Health(
+                                    health,
+                                    status_update.message.clone().map(SharedString::from),
+                                )
+                            } else {
+                                return;
+                            }
+                        }
+                        None => return,
+                    };
+
+                    let recovered = matches!(
+                        status,
+                        LanguageServerStatusUpdate::Health(ServerHealth::Ok, _)
+                    ) && self.statuses.iter().any(|s| {
+                        s.name == name
+                            && matches!(
+                                s.status,
+                                LanguageServerStatusUpdate::Health(
+                                    ServerHealth::Warning | ServerHealth::Error,
+                                    _
+                                )
+                            )
+                    });
+                    if recovered {
+                        self.show_server_recovered(name.clone(), cx);
                     }
-                    _ => {}
-                },
-            )
-            .detach();
 
-            cx.subscribe(
-                &project.read(cx).environment().clone(),
-                |_, _, event, cx| match event {
-                    ProjectEnvironmentEvent::ErrorsUpdated => cx.notify(),
-                },
-            )
-            .detach();
+                    self.set_server_status(name, status);
+                }
+                cx.notify()
+            }
+            _ => {}
+        }
+    }
 
-            cx.subscribe(
-                &project.read(cx).git_store().clone(),
-                |_, _, event: &GitStoreEvent, cx| match event {
-                    project::git_store::GitStoreEvent::JobsUpdated => cx.notify(),
-                    _ => {}
-                },
-            )
-            .detach();
+    fn handle_project_event(
+        &mut self,
+        _: Entity<Project>,
+        event: &project::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            project::Event::FormattingFailed { message, .. } => {
+                self.formatting_failure = Some(message.clone());
+                cx.notify();
+            }
+            project::Event::FormattingFailureCleared => {
+                self.formatting_failure = None;
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
 
-            Self {
-                statuses: Vec::new(),
-                project: project.clone(),
-                context_menu_handle: Default::default(),
+    fn handle_git_store_event(
+        &mut self,
+        _: Entity<GitStore>,
+        event: &GitStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            GitStoreEvent::JobsUpdated => {
+                cx.notify();
+                // Jobs are only shown once they outlast the delay, so render again when it has
+                // passed.
+                let delay = ProjectSettings::get_global(cx).git.activity_delay();
+                if !delay.is_zero() {
+                    self.git_job_delay_task = Some(cx.spawn(async move |this, cx| {
+                        cx.background_executor().timer(delay).await;
+                        this.update(cx, |_, cx| cx.notify()).ok();
+                    }));
+                }
             }
-        });
+            _ => {}
+        }
+    }
 
-        cx.subscribe_in(&this, window, move |_, _, event, window, cx| match event {
-            Event::ShowStatus {
-                server_name,
-                status,
-            } => {
-                let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
-                let status = status.clone();
-                let server_name = server_name.clone();
-                cx.spawn_in(window, async move |workspace, cx| {
-                    let buffer = create_buffer.await?;
-                    buffer.update(cx, |buffer, cx| {
-                        buffer.edit(
-                            [(0..0, format!("Language server {server_name}:\n\n{status}"))],
-                            None,
-                            cx,
-                        );
-                        buffer.set_capability(language::Capability::ReadOnly, cx);
-                    })?;
-                    workspace.update_in(cx, |workspace, window, cx| {
-                        workspace.add_item_to_active_pane(
-                            Box::new(cx.new(|cx| {
-                                let mut editor = Editor::for_buffer(buffer, None, window, cx);
-                                editor.set_read_only(true);
-                                editor
-                            })),
-                            None,
-                            true,
-                            window,
-                            cx,
-                        );
-                    })?;
+    /// Creates an activity indicator for the given project without a workspace, so that tests can
+    /// feed it statuses directly and inspect what it would display.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_for_test(project: Entity<Project>, cx: &mut App) -> Entity<ActivityIndicator> {
+        cx.new(|cx| Self::for_project(project, cx))
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn push_server_status_for_test(
+        &mut self,
+        name: LanguageServerName,
+        status: LanguageServerStatusUpdate,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_server_status(name, status);
+        cx.notify();
+    }
+
+    /// Returns the message the indicator would currently display, if any.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn message_for_test(&mut self, cx: &mut Context<Self>) -> Option<String> {
+        self.content_to_render(cx).map(|content| content.message)
+    }
 
-                    anyhow::Ok(())
-                })
-                .detach();
+    fn cancel_all_language_server_work(
+        &mut self,
+        _: &CancelAllLanguageServerWork,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.project.update(cx, |project, cx| {
+            for work in project.pending_work_snapshot(cx) {
+                if work.cancellable {
+                    project.cancel_language_server_work(work.server_id, Some(work.token), cx);
+                }
             }
-        })
-        .detach();
-        this
+        });
+        cx.notify();
     }
 
     fn show_error_message(&mut self, _: &ShowErrorMessage, _: &mut Window, cx: &mut Context<Self>) {
@@ -284,32 +430,142 @@ impl ActivityIndicator {
         });
     }
 
-    fn pending_language_server_work<'a>(
+    /// Replaces the current status of the given server, keeping the previous ones in its history.
+    fn set_server_status(&mut self, name: LanguageServerName, status: LanguageServerStatusUpdate) {
+        self.track_restarts(&name, &status);
+        let history = self.status_history.entry(name.clone()).or_default();
+        if history.len() == MAX_STATUS_HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(status.clone());
+        self.statuses.retain(|s| s.name != name);
+        self.statuses.push(ServerStatus { name, status });
+    }
+
+    /// The most recent statuses reported for the given server, oldest first.
+    pub fn status_history(
         &self,
-        cx: &'a App,
-    ) -> impl Iterator<Item = PendingWork<'a>> {
-        self.project
-            .read(cx)
-            .language_server_statuses(cx)
-            .rev()
-            .filter_map(|(server_id, status)| {
-                if status.pending_work.is_empty() {
-                    None
-                } else {
-                    let mut pending_work = status
-                        .pending_work
-                        .iter()
-                        .map(|(token, progress)| PendingWork {
-                            language_server_id: server_id,
-                            progress_token: token.as_str(),
-                            progress,
-                        })
-                        .collect::<SmallVec<[_; 4]>>();
-                    pending_work.sort_by_key(|work| Reverse(work.progress.last_update_at));
-                    Some(pending_work)
+        name: &LanguageServerName,
+    ) -> impl Iterator<Item = &LanguageServerStatusUpdate> {
+        self.status_history.get(name).into_iter().flatten()
+    }
+
+    fn track_restarts(&mut self, name: &LanguageServerName, status: &LanguageServerStatusUpdate) {
+        match status {
+            LanguageServerStatusUpdate::Binary(BinaryStatus::Stopped) => {
+                self.stopped_servers.insert(name.clone());
+            }
+            LanguageServerStatusUpdate::Binary(BinaryStatus::Starting) => {
+                if self.stopped_servers.remove(name) {
+                    *self.restart_counts.entry(name.clone()).or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Servers that were restarted at least once, with their restart counts, sorted by name.
+    fn restarted_servers(&self) -> Vec<(LanguageServerName, usize)> {
+        let mut restarted_servers = self
+            .restart_counts
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect::<Vec<_>>();
+        restarted_servers.sort();
+        restarted_servers
+    }
+
+    fn show_server_recovered(&mut self, name: LanguageServerName, cx: &mut Context<Self>) {
+        self.recovered_servers
+            .retain(|(server_name, _)| *server_name != name);
+        self.recovered_servers
+            .push((name, cx.background_executor().now()));
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(SERVER_RECOVERED_MESSAGE_DURATION)
+                .await;
+            this.update(cx, |this, cx| {
+                let now = cx.background_executor().now();
+                this.recovered_servers.retain(|(_, recovered_at)| {
+                    now.saturating_duration_since(*recovered_at) < SERVER_RECOVERED_MESSAGE_DURATION
+                });
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    /// Servers whose download is in progress or whose binary failed to run, along with
+    /// whether they failed. Each of these can be dismissed on its own from the popover menu.
+    fn dismissible_binary_statuses(&self) -> Vec<(LanguageServerName, bool)> {
+        self.statuses
+            .iter()
+            .filter_map(|status| match &status.status {
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Downloading) => {
+                    Some((status.name.clone(), false))
+                }
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Failed { .. }) => {
+                    Some((status.name.clone(), true))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Health messages reported by language servers, most severe first.
+    fn health_messages(&self) -> Vec<(LanguageServerName, ServerHealth, SharedString)> {
+        let mut health_messages = self
+            .statuses
+            .iter()
+            .filter_map(|status| match &status.status {
+                LanguageServerStatusUpdate::Health(health, Some(message)) => {
+                    Some((status.name.clone(), *health, message.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        health_messages.sort_by_key(|(_, health, _)| std::cmp::Reverse(health_severity(*health)));
+        health_messages
+    }
+
+    fn show_health_message(&mut self, server_name: &LanguageServerName, cx: &mut Context<Self>) {
+        self.statuses.retain(|status| match &status.status {
+            LanguageServerStatusUpdate::Health(_, Some(message)) if status.name == *server_name => {
+                cx.emit(Event::ShowStatus {
+                    server_name: status.name.clone(),
+                    status: message.clone(),
+                });
+                false
+            }
+            _ => true,
+        });
+        cx.notify();
+    }
+
+    /// The full error reported by the given server, either because its binary failed to run or
+    /// because it reported itself as unhealthy.
+    fn error_message(&self, server_name: &LanguageServerName) -> Option<SharedString> {
+        self.statuses
+            .iter()
+            .filter(|status| status.name == *server_name)
+            .find_map(|status| match &status.status {
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Failed { error }) => {
+                    Some(SharedString::from(error.clone()))
                 }
+                LanguageServerStatusUpdate::Health(ServerHealth::Error, Some(message)) => {
+                    Some(message.clone())
+                }
+                _ => None,
             })
-            .flatten()
+    }
+
+    fn copy_error_message(&mut self, server_name: &LanguageServerName, cx: &mut Context<Self>) {
+        if let Some(status) = self.error_message(server_name) {
+            cx.emit(Event::CopyStatus {
+                server_name: server_name.clone(),
+                status,
+            });
+        }
     }
 
     fn pending_environment_errors<'a>(
@@ -341,24 +597,15 @@ impl ActivityIndicator {
         }
         // Show any language server has pending activity.
         {
-            let mut pending_work = self.pending_language_server_work(cx);
-            if let Some(PendingWork {
-                progress_token,
-                progress,
-                ..
-            }) = pending_work.next()
-            {
-                let mut message = progress
-                    .title
-                    .as_deref()
-                    .unwrap_or(progress_token)
-                    .to_string();
+            let mut pending_work = self.project.read(cx).pending_work_snapshot(cx).into_iter();
+            if let Some(work) = pending_work.next() {
+                let mut message = work.title.unwrap_or(work.token);
 
-                if let Some(percentage) = progress.percentage {
+                if let Some(percentage) = work.percentage {
                     write!(&mut message, " ({}%)", percentage).unwrap();
                 }
 
-                if let Some(progress_message) = progress.message.as_ref() {
+                if let Some(progress_message) = work.message.as_ref() {
                     message.push_str(": ");
                     message.push_str(progress_message);
                 }
@@ -388,14 +635,19 @@ impl ActivityIndicator {
             }
         }
 
-        if let Some(session) = self
+        let mut unstarted_sessions = self
             .project
             .read(cx)
             .dap_store()
             .read(cx)
             .sessions()
-            .find(|s| !s.read(cx).is_started())
-        {
+            .filter(|s| !s.read(cx).is_started());
+        if let Some(session) = unstarted_sessions.next() {
+            let mut message = format!("Debug: {}", session.read(cx).adapter());
+            let additional_session_count = unstarted_sessions.count();
+            if additional_session_count > 0 {
+                write!(&mut message, " + {} more", additional_session_count).unwrap();
+            }
             return Some(Content {
                 icon: Some(
                     Icon::new(IconName::ArrowCircle)
@@ -407,7 +659,7 @@ impl ActivityIndicator {
                         )
                         .into_any_element(),
                 ),
-                message: format!("Debug: {}", session.read(cx).adapter()),
+                message,
                 tooltip_message: session.read(cx).label().map(|label| label.to_string()),
                 on_click: None,
             });
@@ -421,7 +673,8 @@ impl ActivityIndicator {
             .and_then(Repository::current_job);
         // Show any long-running git command
         if let Some(job_info) = current_job {
-            if Instant::now() - job_info.start >= GIT_OPERATION_DELAY {
+            let delay = ProjectSettings::get_global(cx).git.activity_delay();
+            if git_job_outlasted_delay(job_info.start, cx.background_executor().now(), delay) {
                 return Some(Content {
                     icon: Some(
                         Icon::new(IconName::ArrowCircle)
@@ -479,11 +732,7 @@ impl ActivityIndicator {
         self.statuses
             .retain(|status| !servers_to_clear_statuses.contains(&status.name));
 
-        health_messages.sort_by_key(|(_, health, _)| match health {
-            ServerHealth::Error => 2,
-            ServerHealth::Warning => 1,
-            ServerHealth::Ok => 0,
-        });
+        health_messages.sort_by_key(|(_, health, _)| health_severity(*health));
 
         if !downloading.is_empty() {
             return Some(Content {
@@ -505,10 +754,7 @@ impl ActivityIndicator {
                         }
                     )
                 ),
-                on_click: Some(Arc::new(move |this, _, _| {
-                    this.statuses
-                        .retain(|status| !downloading.contains(&status.name));
-                })),
+                on_click: Some(Arc::new(Self::toggle_language_server_work_context_menu)),
                 tooltip_message: None,
             });
         }
@@ -549,7 +795,7 @@ impl ActivityIndicator {
                         .into_any_element(),
                 ),
                 message: format!(
-                    "Failed to run {}. Click to show error.",
+                    "Failed to run {}. Click to show details.",
                     failed
                         .iter()
                         .map(|name| name.as_ref())
@@ -561,15 +807,13 @@ impl ActivityIndicator {
                             acc
                         }),
                 ),
-                on_click: Some(Arc::new(|this, window, cx| {
-                    this.show_error_message(&ShowErrorMessage, window, cx)
-                })),
+                on_click: Some(Arc::new(Self::toggle_language_server_work_context_menu)),
                 tooltip_message: None,
             });
         }
 
         // Show any formatting failure
-        if let Some(failure) = self.project.read(cx).last_formatting_failure(cx) {
+        if let Some(failure) = &self.formatting_failure {
             return Some(Content {
                 icon: Some(
                     Icon::new(IconName::Warning)
@@ -587,28 +831,40 @@ impl ActivityIndicator {
             });
         }
 
+        // Point at the setting that controls proc-macro expansion when rust-analyzer can't do it
+        if let Some((_, _, message)) = health_messages
+            .iter()
+            .find(|(name, health, message)| is_proc_macro_failure(name, *health, message))
+        {
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .into_any_element(),
+                ),
+                message: format!("({RUST_ANALYZER_NAME}) Proc macros not expanded"),
+                tooltip_message: Some(message.to_string()),
+                on_click: Some(Arc::new(Self::resolve_proc_macro_failure)),
+            });
+        }
+
         // Show any health messages for the language servers
         if let Some((server_name, health, message)) = health_messages.pop() {
-            let health_str = match health {
-                ServerHealth::Ok => format!("({server_name}) "),
-                ServerHealth::Warning => format!("({server_name}) Warning: "),
-                ServerHealth::Error => format!("({server_name}) Error: "),
+            let health_str = health_message_prefix(&server_name, health);
+            // The rest are listed in the popover menu.
+            let remaining_str = if health_messages.is_empty() {
+                String::new()
+            } else {
+                format!(" +{}", health_messages.len())
             };
-            let single_line_message = message
-                .lines()
-                .filter_map(|line| {
-                    let line = line.trim();
-                    if line.is_empty() { None } else { Some(line) }
-                })
-                .collect::<Vec<_>>()
-                .join(" ");
+            let single_line_message = single_line(&message);
             let mut altered_message = single_line_message != message;
             let truncated_message = truncate_and_trailoff(
                 &single_line_message,
-                MAX_MESSAGE_LEN.saturating_sub(health_str.len()),
+                MAX_MESSAGE_LEN.saturating_sub(health_str.len() + remaining_str.len()),
             );
             altered_message |= truncated_message != single_line_message;
-            let final_message = format!("{health_str}{truncated_message}");
+            let final_message = format!("{health_str}{truncated_message}{remaining_str}");
 
             let tooltip_message = if altered_message {
                 Some(format!("{health_str}{message}"))
@@ -637,6 +893,31 @@ impl ActivityIndicator {
             });
         }
 
+        // Briefly confirm that previously unhealthy language servers have recovered
+        if !self.recovered_servers.is_empty() {
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::Check)
+                        .size(IconSize::Small)
+                        .color(Color::Success)
+                        .into_any_element(),
+                ),
+                message: format!(
+                    "{} recovered",
+                    self.recovered_servers
+                        .iter()
+                        .map(|(name, _)| name.as_ref())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                on_click: Some(Arc::new(|this, _, cx| {
+                    this.recovered_servers.clear();
+                    cx.notify();
+                })),
+                tooltip_message: None,
+            });
+        }
+
         if let Some(extension_store) =
             ExtensionStore::try_global(cx).map(|extension_store| extension_store.read(cx))
         {
@@ -657,6 +938,56 @@ impl ActivityIndicator {
         None
     }
 
+    /// Opens the language server logs, where rust-analyzer explains why its proc-macro server
+    /// failed, and offers to flip `procMacro.enable` in its initialization options.
+    fn resolve_proc_macro_failure(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.statuses.retain(|status| {
+            status.name != RUST_ANALYZER_NAME
+                || !matches!(status.status, LanguageServerStatusUpdate::Health(..))
+        });
+        cx.notify();
+        window.dispatch_action(Box::new(zed_actions::dev::OpenLanguageServerLogs), cx);
+
+        let enabled = proc_macro_expansion_enabled(
+            ProjectSettings::get_global(cx)
+                .lsp
+                .get(&RUST_ANALYZER_NAME)
+                .and_then(|settings| settings.initialization_options.as_ref()),
+        );
+        let (detail, toggle_label) = if enabled {
+            (
+                "Disabling expansion silences the errors caused by unexpanded proc macros.",
+                "Disable Proc-Macro Expansion",
+            )
+        } else {
+            (
+                "Proc-macro expansion is disabled in your settings.",
+                "Enable Proc-Macro Expansion",
+            )
+        };
+        let answer = window.prompt(
+            PromptLevel::Info,
+            "rust-analyzer could not expand proc macros",
+            Some(detail),
+            &[toggle_label, "Cancel"],
+            cx,
+        );
+        let fs = self.project.read(cx).fs().clone();
+        cx.spawn(async move |_, cx| {
+            if answer.await != Ok(0) {
+                return;
+            }
+            cx.update(|cx| {
+                update_settings_file::<ProjectSettings>(fs, cx, move |settings, _| {
+                    let lsp_settings = settings.lsp.entry(RUST_ANALYZER_NAME).or_default();
+                    set_proc_macro_expansion(&mut lsp_settings.initialization_options, !enabled);
+                });
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     fn toggle_language_server_work_context_menu(
         &mut self,
         window: &mut Window,
@@ -669,17 +1000,111 @@ impl ActivityIndicator {
 impl EventEmitter<Event> for ActivityIndicator {}
 
 const MAX_MESSAGE_LEN: usize = 50;
+/// Below this window width, only the icon of the current status is shown, with the message
+/// moved into the tooltip.
+const COMPACT_WINDOW_WIDTH: Pixels = px(800.);
+
+fn health_severity(health: ServerHealth) -> u8 {
+    match health {
+        ServerHealth::Error => 2,
+        ServerHealth::Warning => 1,
+        ServerHealth::Ok => 0,
+    }
+}
+
+fn health_message_prefix(server_name: &LanguageServerName, health: ServerHealth) -> String {
+    match health {
+        ServerHealth::Ok => format!("({server_name}) "),
+        ServerHealth::Warning => format!("({server_name}) Warning: "),
+        ServerHealth::Error => format!("({server_name}) Error: "),
+    }
+}
+
+fn is_proc_macro_failure(
+    server_name: &LanguageServerName,
+    health: ServerHealth,
+    message: &str,
+) -> bool {
+    if *server_name != RUST_ANALYZER_NAME || health == ServerHealth::Ok {
+        return false;
+    }
+    let message = message.to_lowercase();
+    PROC_MACRO_FAILURE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Whether rust-analyzer expands proc macros with the given initialization options, which it does
+/// unless `procMacro.enable` is set to `false`.
+fn proc_macro_expansion_enabled(initialization_options: Option<&serde_json::Value>) -> bool {
+    initialization_options
+        .and_then(|options| options.pointer("/procMacro/enable"))
+        .and_then(|enable| enable.as_bool())
+        .unwrap_or(true)
+}
+
+fn set_proc_macro_expansion(initialization_options: &mut Option<serde_json::Value>, enabled: bool) {
+    let options = initialization_options.get_or_insert_with(|| serde_json::json!({}));
+    if !options.is_object() {
+        *options = serde_json::json!({});
+    }
+    let proc_macro = &mut options["procMacro"];
+    if !proc_macro.is_object() {
+        *proc_macro = serde_json::json!({});
+    }
+    proc_macro["enable"] = serde_json::Value::Bool(enabled);
+}
+
+fn git_job_outlasted_delay(started_at: Instant, now: Instant, delay: Duration) -> bool {
+    now.saturating_duration_since(started_at) >= delay
+}
+
+/// The label of the menu entry cancelling all cancellable work, which is only worth showing when
+/// it would cancel something that a single entry doesn't already.
+fn cancel_all_work_label(cancellable_count: usize, work_count: usize) -> Option<String> {
+    if cancellable_count == 0 || work_count < 2 {
+        None
+    } else if cancellable_count == work_count {
+        Some("Cancel All Language Server Work".to_string())
+    } else {
+        Some(format!(
+            "Cancel All ({cancellable_count} of {work_count} Cancellable)"
+        ))
+    }
+}
+
+/// Describes the usage of a language server process, e.g. `312.4MB · 45% CPU`.
+fn format_server_resources(resources: &LanguageServerResources) -> String {
+    format!(
+        "{} · {:.0}% CPU",
+        format_file_size(resources.memory, true),
+        resources.cpu_usage
+    )
+}
+
+fn single_line(message: &str) -> String {
+    message
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() { None } else { Some(line) }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
 impl Render for ActivityIndicator {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let result = h_flex()
             .id("activity-indicator")
-            .on_action(cx.listener(Self::show_error_message));
+            .on_action(cx.listener(Self::show_error_message))
+            .on_action(cx.listener(Self::cancel_all_language_server_work));
         let Some(content) = self.content_to_render(cx) else {
             return result;
         };
         let this = cx.entity().downgrade();
         let truncate_content = content.message.len() > MAX_MESSAGE_LEN;
+        let compact = content.icon.is_some() && window.viewport_size().width < COMPACT_WINDOW_WIDTH;
         result.gap_2().child(
             PopoverMenu::new("activity-indicator-popover")
                 .trigger(
@@ -689,7 +1114,15 @@ impl Render for ActivityIndicator {
                             .gap_2()
                             .children(content.icon)
                             .map(|button| {
-                                if truncate_content {
+                                if compact {
+                                    let tooltip = match content.tooltip_message {
+                                        Some(tooltip_message) => {
+                                            format!("{}\n{tooltip_message}", content.message)
+                                        }
+                                        None => content.message,
+                                    };
+                                    button.tooltip(Tooltip::text(tooltip))
+                                } else if truncate_content {
                                     button
                                         .child(
                                             Label::new(truncate_and_trailoff(
@@ -721,29 +1154,66 @@ impl Render for ActivityIndicator {
                 .anchor(gpui::Corner::BottomLeft)
                 .menu(move |window, cx| {
                     let strong_this = this.upgrade()?;
-                    let mut has_work = false;
+                    let mut has_entries = false;
                     let menu = ContextMenu::build(window, cx, |mut menu, _, cx| {
-                        for work in strong_this.read(cx).pending_language_server_work(cx) {
-                            has_work = true;
+                        let pending_work = strong_this
+                            .read(cx)
+                            .project
+                            .read(cx)
+                            .pending_work_snapshot(cx);
+                        let cancellable_count =
+                            pending_work.iter().filter(|work| work.cancellable).count();
+                        if let Some(label) =
+                            cancel_all_work_label(cancellable_count, pending_work.len())
+                        {
+                            let this = this.clone();
+                            menu = menu
+                                .entry(
+                                    label,
+                                    Some(Box::new(CancelAllLanguageServerWork)),
+                                    move |window, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.cancel_all_language_server_work(
+                                                &CancelAllLanguageServerWork,
+                                                window,
+                                                cx,
+                                            );
+                                            this.context_menu_handle.hide(cx);
+                                        })
+                                        .ok();
+                                    },
+                                )
+                                .separator();
+                        }
+                        for work in pending_work {
+                            has_entries = true;
                             let this = this.clone();
-                            let mut title = work
-                                .progress
-                                .title
-                                .as_deref()
-                                .unwrap_or(work.progress_token)
-                                .to_owned();
-
-                            if work.progress.is_cancellable {
-                                let language_server_id = work.language_server_id;
-                                let token = work.progress_token.to_string();
+                            let mut title = work.title.unwrap_or_else(|| work.token.clone());
+                            let resources = work.resources.as_ref().map(|resources| {
+                                SharedString::from(format_server_resources(resources))
+                            });
+
+                            if work.cancellable {
+                                let language_server_id = work.server_id;
+                                let token = work.token;
                                 let title = SharedString::from(title);
                                 menu = menu.custom_entry(
                                     move |_, _| {
                                         h_flex()
                                             .w_full()
                                             .justify_between()
+                                            .gap_2()
                                             .child(Label::new(title.clone()))
-                                            .child(Icon::new(IconName::XCircle))
+                                            .child(
+                                                h_flex()
+                                                    .gap_2()
+                                                    .children(resources.clone().map(|resources| {
+                                                        Label::new(resources)
+                                                            .size(LabelSize::Small)
+                                                            .color(Color::Muted)
+                                                    }))
+                                                    .child(Icon::new(IconName::XCircle)),
+                                            )
                                             .into_any_element()
                                     },
                                     move |_, cx| {
@@ -762,17 +1232,142 @@ impl Render for ActivityIndicator {
                                     },
                                 );
                             } else {
-                                if let Some(progress_message) = work.progress.message.as_ref() {
+                                if let Some(progress_message) = work.message.as_ref() {
                                     title.push_str(": ");
                                     title.push_str(progress_message);
                                 }
 
-                                menu = menu.label(title);
+                                if let Some(resources) = resources {
+                                    let title = SharedString::from(title);
+                                    menu = menu.custom_row(move |_, _| {
+                                        h_flex()
+                                            .w_full()
+                                            .justify_between()
+                                            .gap_2()
+                                            .child(Label::new(title.clone()).color(Color::Muted))
+                                            .child(
+                                                Label::new(resources.clone())
+                                                    .size(LabelSize::Small)
+                                                    .color(Color::Muted),
+                                            )
+                                            .into_any_element()
+                                    });
+                                } else {
+                                    menu = menu.label(title);
+                                }
                             }
                         }
+
+                        let binary_statuses = strong_this.read(cx).dismissible_binary_statuses();
+                        if has_entries && !binary_statuses.is_empty() {
+                            menu = menu.separator();
+                        }
+                        let failed_servers = binary_statuses
+                            .iter()
+                            .filter(|(_, failed)| *failed)
+                            .map(|(server_name, _)| server_name.clone())
+                            .collect::<Vec<_>>();
+                        let has_failures = !failed_servers.is_empty();
+                        for (server_name, failed) in binary_statuses {
+                            has_entries = true;
+                            let this = this.clone();
+                            let title = if failed {
+                                SharedString::from(format!("Failed to run {server_name}"))
+                            } else {
+                                SharedString::from(format!("Downloading {server_name}..."))
+                            };
+                            menu = menu.custom_entry(
+                                move |_, _| {
+                                    h_flex()
+                                        .w_full()
+                                        .justify_between()
+                                        .child(Label::new(title.clone()))
+                                        .child(Icon::new(IconName::XCircle))
+                                        .into_any_element()
+                                },
+                                move |_, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.statuses.retain(|status| status.name != server_name);
+                                        cx.notify();
+                                    })
+                                    .ok();
+                                },
+                            );
+                        }
+                        if has_failures {
+                            let this = this.clone();
+                            menu = menu.entry("Show Error", None, move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.show_error_message(&ShowErrorMessage, window, cx);
+                                })
+                                .ok();
+                            });
+                        }
+                        for server_name in failed_servers {
+                            let this = this.clone();
+                            menu = menu.entry(
+                                format!("Copy {server_name} Error"),
+                                None,
+                                move |_, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.copy_error_message(&server_name, cx);
+                                        this.context_menu_handle.hide(cx);
+                                    })
+                                    .ok();
+                                },
+                            );
+                        }
+
+                        let health_messages = strong_this.read(cx).health_messages();
+                        if has_entries && !health_messages.is_empty() {
+                            menu = menu.separator();
+                        }
+                        for (server_name, health, message) in health_messages {
+                            has_entries = true;
+                            let this = this.clone();
+                            let title = format!(
+                                "{}{}",
+                                health_message_prefix(&server_name, health),
+                                truncate_and_trailoff(&single_line(&message), MAX_MESSAGE_LEN)
+                            );
+                            let copy_server_name =
+                                (health == ServerHealth::Error).then(|| server_name.clone());
+                            menu = menu.entry(title, None, {
+                                let this = this.clone();
+                                move |_, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.show_health_message(&server_name, cx);
+                                    })
+                                    .ok();
+                                }
+                            });
+                            if let Some(server_name) = copy_server_name {
+                                menu = menu.entry(
+                                    format!("Copy {server_name} Error"),
+                                    None,
+                                    move |_, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.copy_error_message(&server_name, cx);
+                                            this.context_menu_handle.hide(cx);
+                                        })
+                                        .ok();
+                                    },
+                                );
+                            }
+                        }
+
+                        let restarted_servers = strong_this.read(cx).restarted_servers();
+                        if has_entries && !restarted_servers.is_empty() {
+                            menu = menu.separator();
+                        }
+                        for (server_name, count) in restarted_servers {
+                            has_entries = true;
+                            let times = if count == 1 { "time" } else { "times" };
+                            menu = menu.label(format!("{server_name} restarted {count} {times}"));
+                        }
                         menu
                     });
-                    has_work.then_some(menu)
+                    has_entries.then_some(menu)
                 }),
         )
     }
@@ -787,3 +1382,465 @@ impl StatusItemView for ActivityIndicator {
     ) {
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::LanguageServerId;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{cell::Cell, rc::Rc};
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    async fn build_indicator(
+        cx: &mut TestAppContext,
+    ) -> (Entity<Project>, Entity<ActivityIndicator>) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "main.rs": "" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        let indicator = cx.update(|cx| ActivityIndicator::new_for_test(project.clone(), cx));
+        (project, indicator)
+    }
+
+    #[gpui::test]
+    async fn test_content_priority(cx: &mut TestAppContext) {
+        let (project, indicator) = build_indicator(cx).await;
+
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("rust-analyzer"),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Warning,
+                    Some("failed to load workspace".into()),
+                ),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("(rust-analyzer) Warning: failed to load workspace")
+            );
+
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("clangd"),
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Failed {
+                    error: "not found".to_string(),
+                }),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("Failed to run clangd. Click to show details.")
+            );
+
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("gopls"),
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Downloading),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("Downloading gopls...")
+            );
+        });
+
+        project.update(cx, |project, cx| {
+            project.insert_environment_error(
+                Path::new(path!("/project")).into(),
+                "direnv failed",
+                cx,
+            );
+        });
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("direnv failed")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_formatting_failure(cx: &mut TestAppContext) {
+        let (project, indicator) = build_indicator(cx).await;
+
+        project.update(cx, |_, cx| {
+            cx.emit(project::Event::FormattingFailed {
+                buffer_ids: Vec::new(),
+                message: "prettier exited with 2".to_string(),
+            })
+        });
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("Formatting failed: prettier exited with 2. Click to see logs.")
+            );
+        });
+
+        project.update(cx, |_, cx| {
+            cx.emit(project::Event::FormattingFailureCleared)
+        });
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_multiple_health_messages(cx: &mut TestAppContext) {
+        let (_, indicator) = build_indicator(cx).await;
+
+        indicator.update(cx, |indicator, cx| {
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("rust-analyzer"),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Warning,
+                    Some("failed to load workspace".into()),
+                ),
+                cx,
+            );
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("clangd"),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Error,
+                    Some("no compile commands".into()),
+                ),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("(clangd) Error: no compile commands +1")
+            );
+            assert_eq!(
+                indicator
+                    .health_messages()
+                    .into_iter()
+                    .map(|(name, _, _)| name.0.to_string())
+                    .collect::<Vec<_>>(),
+                ["clangd", "rust-analyzer"]
+            );
+
+            indicator.show_health_message(&LanguageServerName::new_static("clangd"), cx);
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("(rust-analyzer) Warning: failed to load workspace")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_proc_macro_failure(cx: &mut TestAppContext) {
+        let (_, indicator) = build_indicator(cx).await;
+
+        indicator.update(cx, |indicator, cx| {
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("clangd"),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Error,
+                    Some("Failed to spawn one or more proc-macro servers.".into()),
+                ),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("(clangd) Error: Failed to spawn one or more proc-macro servers.")
+            );
+
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("rust-analyzer"),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Warning,
+                    Some("Failed to spawn one or more proc-macro servers.\n\nno sysroot".into()),
+                ),
+                cx,
+            );
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("(rust-analyzer) Proc macros not expanded")
+            );
+        });
+    }
+
+    #[test]
+    fn test_set_proc_macro_expansion() {
+        let mut initialization_options = None;
+        assert!(proc_macro_expansion_enabled(
+            initialization_options.as_ref()
+        ));
+        set_proc_macro_expansion(&mut initialization_options, false);
+        assert_eq!(
+            initialization_options,
+            Some(json!({ "procMacro": { "enable": false } }))
+        );
+        assert!(!proc_macro_expansion_enabled(
+            initialization_options.as_ref()
+        ));
+
+        let mut initialization_options = Some(json!({
+            "check": { "command": "clippy" },
+            "procMacro": { "enable": false, "attributes": { "enable": true } }
+        }));
+        set_proc_macro_expansion(&mut initialization_options, true);
+        assert_eq!(
+            initialization_options,
+            Some(json!({
+                "check": { "command": "clippy" },
+                "procMacro": { "enable": true, "attributes": { "enable": true } }
+            }))
+        );
+    }
+
+    #[test]
+    fn test_git_job_outlasted_delay() {
+        let started_at = Instant::now();
+        let delay = Duration::from_millis(300);
+        assert!(!git_job_outlasted_delay(started_at, started_at, delay));
+        assert!(!git_job_outlasted_delay(
+            started_at,
+            started_at + Duration::from_millis(299),
+            delay
+        ));
+        assert!(git_job_outlasted_delay(
+            started_at,
+            started_at + Duration::from_millis(300),
+            delay
+        ));
+        assert!(git_job_outlasted_delay(
+            started_at,
+            started_at + Duration::from_secs(5),
+            delay
+        ));
+        assert!(git_job_outlasted_delay(
+            started_at,
+            started_at,
+            Duration::ZERO
+        ));
+    }
+
+    #[gpui::test]
+    async fn test_git_job_delay(cx: &mut TestAppContext) {
+        let (project, indicator) = build_indicator(cx).await;
+        let notify_count = Rc::new(Cell::new(0));
+        let _subscription = cx.update(|cx| {
+            let notify_count = notify_count.clone();
+            cx.observe(&indicator, move |_, _| {
+                notify_count.set(notify_count.get() + 1)
+            })
+        });
+        let delay = cx.update(|cx| ProjectSettings::get_global(cx).git.activity_delay());
+        assert_eq!(delay, Duration::from_millis(300));
+
+        let repository = project
+            .read_with(cx, |project, cx| project.active_repository(cx))
+            .unwrap();
+        let (finish_tx, finish_rx) = futures::channel::oneshot::channel::<()>();
+        let job = repository.update(cx, |repository, _| {
+            repository.send_job(Some("git fetch".into()), |_, _| async move {
+                finish_rx.await.ok();
+            })
+        });
+        cx.run_until_parked();
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+        });
+
+        notify_count.set(0);
+        cx.executor()
+            .advance_clock(delay - Duration::from_millis(1));
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 0);
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+        });
+
+        cx.executor().advance_clock(Duration::from_millis(1));
+        cx.run_until_parked();
+        assert!(
+            notify_count.get() > 0,
+            "the indicator should re-render once the job outlasts the delay"
+        );
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx).as_deref(), Some("git fetch"));
+        });
+
+        finish_tx.send(()).unwrap();
+        job.await.unwrap();
+        cx.run_until_parked();
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+        });
+    }
+
+    #[test]
+    fn test_cancel_all_work_label() {
+        assert_eq!(cancel_all_work_label(0, 3), None);
+        assert_eq!(cancel_all_work_label(1, 1), None);
+        assert_eq!(
+            cancel_all_work_label(2, 2).as_deref(),
+            Some("Cancel All Language Server Work")
+        );
+        assert_eq!(
+            cancel_all_work_label(3, 5).as_deref(),
+            Some("Cancel All (3 of 5 Cancellable)")
+        );
+    }
+
+    #[test]
+    fn test_format_server_resources() {
+        assert_eq!(
+            format_server_resources(&LanguageServerResources {
+                memory: 312_400_000,
+                cpu_usage: 45.3,
+            }),
+            "312.4MB · 45% CPU"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_server_recovered(cx: &mut TestAppContext) {
+        let (project, indicator) = build_indicator(cx).await;
+        let rust_analyzer = LanguageServerName::new_static("rust-analyzer");
+
+        indicator.update(cx, |indicator, cx| {
+            indicator.push_server_status_for_test(
+                rust_analyzer.clone(),
+                LanguageServerStatusUpdate::Health(
+                    ServerHealth::Error,
+                    Some("failed to load workspace".into()),
+                ),
+                cx,
+            );
+        });
+
+        let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+        lsp_store.update(cx, |_, cx| {
+            cx.emit(LspStoreEvent::LanguageServerUpdate {
+                language_server_id: LanguageServerId(0),
+                name: Some(rust_analyzer.clone()),
+                message: proto::update_language_server::Variant::StatusUpdate(
+                    proto::StatusUpdate {
+                        message: None,
+                        status: Some(proto::status_update::Status::Health(
+                            proto::ServerHealth::Ok as i32,
+                        )),
+                    },
+                ),
+            })
+        });
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("rust-analyzer recovered")
+            );
+        });
+
+        cx.executor()
+            .advance_clock(SERVER_RECOVERED_MESSAGE_DURATION - Duration::from_millis(1));
+        cx.run_until_parked();
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(
+                indicator.message_for_test(cx).as_deref(),
+                Some("rust-analyzer recovered")
+            );
+        });
+
+        cx.executor().advance_clock(Duration::from_millis(1));
+        cx.run_until_parked();
+        indicator.update(cx, |indicator, cx| {
+            assert_eq!(indicator.message_for_test(cx), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restart_counts(cx: &mut TestAppContext) {
+        let (_, indicator) = build_indicator(cx).await;
+
+        indicator.update(cx, |indicator, cx| {
+            let rust_analyzer = LanguageServerName::new_static("rust-analyzer");
+            for status in [
+                BinaryStatus::Starting,
+                BinaryStatus::Stopped,
+                BinaryStatus::Starting,
+                BinaryStatus::Stopping,
+                BinaryStatus::Stopped,
+                BinaryStatus::Starting,
+                BinaryStatus::Starting,
+            ] {
+                indicator.push_server_status_for_test(
+                    rust_analyzer.clone(),
+                    LanguageServerStatusUpdate::Binary(status),
+                    cx,
+                );
+            }
+            indicator.push_server_status_for_test(
+                LanguageServerName::new_static("clangd"),
+                LanguageServerStatusUpdate::Binary(BinaryStatus::Starting),
+                cx,
+            );
+            assert_eq!(indicator.restarted_servers(), [(rust_analyzer, 2)]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_status_history(cx: &mut TestAppContext) {
+        let (_, indicator) = build_indicator(cx).await;
+
+        indicator.update(cx, |indicator, cx| {
+            let rust_analyzer = LanguageServerName::new_static("rust-analyzer");
+            let statuses = [
+                BinaryStatus::CheckingForUpdate,
+                BinaryStatus::Downloading,
+                BinaryStatus::Starting,
+                BinaryStatus::Failed {
+                    error: "oops".to_string(),
+                },
+            ]
+            .map(LanguageServerStatusUpdate::Binary);
+            for status in statuses.clone() {
+                indicator.push_server_status_for_test(rust_analyzer.clone(), status, cx);
+            }
+            assert_eq!(
+                indicator
+                    .status_history(&rust_analyzer)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                statuses
+            );
+
+            for _ in 0..MAX_STATUS_HISTORY_LEN {
+                indicator.push_server_status_for_test(
+                    rust_analyzer.clone(),
+                    LanguageServerStatusUpdate::Binary(BinaryStatus::Starting),
+                    cx,
+                );
+            }
+            assert_eq!(
+                indicator.status_history(&rust_analyzer).count(),
+                MAX_STATUS_HISTORY_LEN
+            );
+            assert!(indicator.status_history(&rust_analyzer).all(
+                |status| *status == LanguageServerStatusUpdate::Binary(BinaryStatus::Starting)
+            ));
+            assert_eq!(
+                indicator
+                    .status_history(&LanguageServerName::new_static("clangd"))
+                    .count(),
+                0
+            );
+        });
+    }
+}
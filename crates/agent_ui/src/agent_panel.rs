@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use crate::NewExternalAgentThread;
 use crate::agent_diff::AgentDiffThread;
 use crate::message_editor::{MAX_EDITOR_LINES, MIN_EDITOR_LINES};
-use crate::ui::NewThreadButton;
+use crate::ui::{NewThreadButton, usage_color};
 use crate::{
     AddContextServer, AgentDiffPane, ContinueThread, ContinueWithBurnMode,
     DeleteRecentlyOpenThread, ExpandMessageEditor, Follow, InlineAssistant, NewTextThread,
@@ -78,7 +78,7 @@ use zed_actions::{
     agent::{OpenConfiguration, ResetOnboarding, ToggleModelSelector},
     assistant::{OpenRulesLibrary, ToggleFocus},
 };
-use zed_llm_client::{CompletionIntent, UsageLimit};
+use zed_llm_client::CompletionIntent;
 
 const AGENT_PANEL_KEY: &str = "agent_panel";
 
@@ -1974,12 +1974,9 @@ impl AgentPanel {
                                 .header_with_link("Prompt Usage", "Manage", account_url.clone())
                                 .custom_entry(
                                     move |_window, cx| {
-                                        let used_percentage = match usage.limit {
-                                            UsageLimit::Limited(limit) => {
-                                                Some((usage.amount as f32 / limit as f32) * 100.)
-                                            }
-                                            UsageLimit::Unlimited => None,
-                                        };
+                                        let used_percentage = usage.limit_value().map(|limit| {
+                                            (usage.amount as f32 / limit as f32) * 100.
+                                        });
 
                                         h_flex()
                                             .flex_1()
@@ -1988,16 +1985,9 @@ impl AgentPanel {
                                                 ProgressBar::new("usage", percent, 100., cx)
                                             }))
                                             .child(
-                                                Label::new(match usage.limit {
-                                                    UsageLimit::Limited(limit) => {
-                                                        format!("{} / {limit}", usage.amount)
-                                                    }
-                                                    UsageLimit::Unlimited => {
-                                                        format!("{} / ∞", usage.amount)
-                                                    }
-                                                })
-                                                .size(LabelSize::Small)
-                                                .color(Color::Muted),
+                                                Label::new(usage.display())
+                                                    .size(LabelSize::Small)
+                                                    .color(usage_color(&usage)),
                                             )
                                             .into_any_element()
                                     },
@@ -3,8 +3,10 @@ mod burn_mode_tooltip;
 mod context_pill;
 mod new_thread_button;
 pub mod preview;
+mod usage_color;
 
 pub use agent_notification::*;
 pub use burn_mode_tooltip::*;
 pub use context_pill::*;
 pub use new_thread_button::*;
+pub use usage_color::*;
@@ -0,0 +1,11 @@
+use client::RequestUsage;
+use ui::Color;
+
+/// The color to show request usage in, which turns to an error once the limit is reached.
+pub fn usage_color(usage: &RequestUsage) -> Color {
+    if usage.over_limit() {
+        Color::Error
+    } else {
+        Color::Muted
+    }
+}
@@ -4,16 +4,23 @@ use chrono::{DateTime, Utc};
 use collections::{HashMap, HashSet, hash_map::Entry};
 use derive_more::Deref;
 use feature_flags::FeatureFlagAppExt;
-use futures::{Future, StreamExt, channel::mpsc};
+use futures::{AsyncReadExt as _, Future, StreamExt, channel::mpsc};
 use gpui::{
-    App, AsyncApp, Context, Entity, EventEmitter, SharedString, SharedUri, Task, WeakEntity,
+    App, AsyncApp, Context, Entity, EventEmitter, Image, ImageFormat, SharedString, SharedUri,
+    Task, WeakEntity,
+};
+use http_client::{
+    AsyncBody, HttpClient, HttpClientWithUrl,
+    http::{HeaderMap, HeaderValue, header::CONTENT_TYPE},
 };
-use http_client::http::{HeaderMap, HeaderValue};
 use postage::{sink::Sink, watch};
 use rpc::proto::{RequestMessage, UsersResponse};
 use std::{
+    collections::VecDeque,
+    fmt::Write as _,
     str::FromStr as _,
     sync::{Arc, Weak},
+    time::Duration,
 };
 use text::ReplicaId;
 use util::{TryFutureExt as _, maybe};
@@ -24,6 +31,12 @@ use zed_llm_client::{
 
 pub type UserId = u64;
 
+/// The number of model request usage snapshots kept for exporting.
+const MAX_USAGE_HISTORY_LEN: usize = 1024;
+/// How long [`UserStore::fuzzy_search_users`] waits for a newer query before searching, so that
+/// typing a query doesn't search for each of its prefixes.
+pub const FUZZY_SEARCH_USERS_DEBOUNCE: Duration = Duration::from_millis(150);
+
 #[derive(
     Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, serde::Serialize, serde::Deserialize,
 )]
@@ -60,6 +73,14 @@ pub struct User {
     pub name: Option<String>,
 }
 
+/// What to show for a user's avatar once [`UserStore::avatar`] has tried to fetch it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UserAvatar {
+    Image(Arc<Image>),
+    /// The avatar couldn't be fetched, so the user's initials are shown instead.
+    Initials(SharedString),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Collaborator {
     pub peer_id: proto::PeerId,
@@ -108,12 +129,20 @@ pub enum ContactRequestStatus {
 pub struct UserStore {
     users: HashMap<u64, Arc<User>>,
     by_github_login: HashMap<String, u64>,
+    /// Users the server didn't return when asked for them, e.g. because their account was
+    /// deleted. They aren't requested again until the next sign-in.
+    unknown_user_ids: HashSet<u64>,
+    /// The avatar URI each user's avatar was fetched from, and its outcome once the fetch is done.
+    avatars: HashMap<u64, (SharedUri, Option<UserAvatar>)>,
+    /// Incremented by each fuzzy search, so that searches can tell whether they were superseded.
+    latest_fuzzy_search_id: usize,
     participant_indices: HashMap<u64, ParticipantIndex>,
     update_contacts_tx: mpsc::UnboundedSender<UpdateContacts>,
     current_plan: Option<proto::Plan>,
     subscription_period: Option<(DateTime<Utc>, DateTime<Utc>)>,
     trial_started_at: Option<DateTime<Utc>>,
     model_request_usage: Option<ModelRequestUsage>,
+    model_request_usage_history: VecDeque<UsageHistoryEntry>,
     edit_prediction_usage: Option<EditPredictionUsage>,
     is_usage_based_billing_enabled: Option<bool>,
     account_too_young: Option<bool>,
@@ -143,8 +172,19 @@ pub enum Event {
         kind: ContactEventKind,
     },
     ShowContacts,
+    /// An existing contact came online, went offline, or started or stopped being busy.
+    ContactPresenceChanged {
+        user_id: u64,
+        online: bool,
+        busy: bool,
+    },
     ParticipantIndicesChanged,
     PrivateUserInfoUpdated,
+    /// The current user's plan changed to a different one, e.g. after an upgrade.
+    PlanChanged {
+        from: Option<proto::Plan>,
+        to: proto::Plan,
+    },
 }
 
 #[derive(Clone, Copy)]
@@ -174,6 +214,28 @@ pub struct RequestUsage {
     pub amount: i32,
 }
 
+/// The model request usage of the current user at a point in time.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct UsageHistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub amount: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageExportFormat {
+    Json,
+    Csv,
+}
+
+/// A consistent view of the current user's plan and usage, read all at once.
+#[derive(Debug, Clone, Copy)]
+pub struct UsageSnapshot {
+    pub current_plan: Option<proto::Plan>,
+    pub subscription_period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    pub model_request_usage: Option<ModelRequestUsage>,
+    pub edit_prediction_usage: Option<EditPredictionUsage>,
+}
+
 impl UserStore {
     pub fn new(client: Arc<Client>, cx: &Context<Self>) -> Self {
         let (mut current_user_tx, current_user_rx) = watch::channel();
@@ -187,11 +249,15 @@ impl UserStore {
         Self {
             users: Default::default(),
             by_github_login: Default::default(),
+            unknown_user_ids: Default::default(),
+            avatars: Default::default(),
+            latest_fuzzy_search_id: 0,
             current_user: current_user_rx,
             current_plan: None,
             subscription_period: None,
             trial_started_at: None,
             model_request_usage: None,
+            model_request_usage_history: VecDeque::new(),
             edit_prediction_usage: None,
             is_usage_based_billing_enabled: None,
             account_too_young: None,
@@ -228,8 +294,10 @@ impl UserStore {
                         Status::Connected { .. } => {
                             if let Some(user_id) = client.user_id() {
                                 let fetch_user = if let Ok(fetch_user) =
-                                    this.update(cx, |this, cx| this.get_user(user_id, cx).log_err())
-                                {
+                                    this.update(cx, |this, cx| {
+                                        this.unknown_user_ids.clear();
+                                        this.get_user(user_id, cx).log_err()
+                                    }) {
                                     fetch_user
                                 } else {
                                     break;
@@ -303,6 +371,7 @@ impl UserStore {
     pub fn clear_cache(&mut self) {
         self.users.clear();
         self.by_github_login.clear();
+        self.unknown_user_ids.clear();
     }
 
     async fn handle_update_invite_info(
@@ -352,7 +421,8 @@ impl UserStore {
         mut cx: AsyncApp,
     ) -> Result<()> {
         this.update(&mut cx, |this, cx| {
-            this.current_plan = Some(message.payload.plan());
+            let plan = message.payload.plan();
+            let previous_plan = this.current_plan.replace(plan);
             this.subscription_period = maybe!({
                 let period = message.payload.subscription_period?;
                 let started_at = DateTime::from_timestamp(period.started_at as i64, 0)?;
@@ -376,6 +446,9 @@ impl UserStore {
                         RequestUsage::from_proto(usage.model_requests_usage_amount, limit)
                     })
                     .map(ModelRequestUsage);
+                if let Some(model_request_usage) = this.model_request_usage {
+                    this.record_model_request_usage(model_request_usage);
+                }
                 this.edit_prediction_usage = usage
                     .edit_predictions_usage_limit
                     .and_then(|limit| {
@@ -384,6 +457,12 @@ impl UserStore {
                     .map(EditPredictionUsage);
             }
 
+            if previous_plan != Some(plan) {
+                cx.emit(Event::PlanChanged {
+                    from: previous_plan,
+                    to: plan,
+                });
+            }
             cx.notify();
         })?;
         Ok(())
@@ -391,9 +470,21 @@ impl UserStore {
 
     pub fn update_model_request_usage(&mut self, usage: ModelRequestUsage, cx: &mut Context<Self>) {
         self.model_request_usage = Some(usage);
+        self.record_model_request_usage(usage);
         cx.notify();
     }
 
+    fn record_model_request_usage(&mut self, usage: ModelRequestUsage) {
+        if self.model_request_usage_history.len() == MAX_USAGE_HISTORY_LEN {
+            self.model_request_usage_history.pop_front();
+        }
+        self.model_request_usage_history
+            .push_back(UsageHistoryEntry {
+                timestamp: Utc::now(),
+                amount: usage.amount,
+            });
+    }
+
     pub fn update_edit_prediction_usage(
         &mut self,
         usage: EditPredictionUsage,
@@ -470,7 +561,19 @@ impl UserStore {
                                 &&updated_contact.user.github_login,
                                 |contact| &contact.user.github_login,
                             ) {
-                                Ok(ix) => this.contacts[ix] = updated_contact,
+                                Ok(ix) => {
+                                    let previous = &this.contacts[ix];
+                                    if previous.online != updated_contact.online
+                                        || previous.busy != updated_contact.busy
+                                    {
+                                        cx.emit(Event::ContactPresenceChanged {
+                                            user_id: updated_contact.user.id,
+                                            online: updated_contact.online,
+                                            busy: updated_contact.busy,
+                                        });
+                                    }
+                                    this.contacts[ix] = updated_contact;
+                                }
                                 Err(ix) => this.contacts.insert(ix, updated_contact),
                             }
                         }
@@ -682,19 +785,28 @@ impl UserStore {
         cx: &Context<Self>,
     ) -> Task<Result<Vec<Arc<User>>>> {
         let mut user_ids_to_fetch = user_ids.clone();
-        user_ids_to_fetch.retain(|id| !self.users.contains_key(id));
+        user_ids_to_fetch
+            .retain(|id| !self.users.contains_key(id) && !self.unknown_user_ids.contains(id));
 
         cx.spawn(async move |this, cx| {
             if !user_ids_to_fetch.is_empty() {
-                this.update(cx, |this, cx| {
-                    this.load_users(
-                        proto::GetUsers {
-                            user_ids: user_ids_to_fetch,
-                        },
-                        cx,
-                    )
-                })?
-                .await?;
+                let users = this
+                    .update(cx, |this, cx| {
+                        this.load_users(
+                            proto::GetUsers {
+                                user_ids: user_ids_to_fetch.clone(),
+                            },
+                            cx,
+                        )
+                    })?
+                    .await?;
+                this.update(cx, |this, _| {
+                    this.unknown_user_ids.extend(
+                        user_ids_to_fetch
+                            .into_iter()
+                            .filter(|id| !users.iter().any(|user| user.id == *id)),
+                    );
+                })?;
             }
 
             this.read_with(cx, |this, _| {
@@ -711,12 +823,36 @@ impl UserStore {
         })
     }
 
+    /// Searches users matching the query after [`FUZZY_SEARCH_USERS_DEBOUNCE`]. Resolves to `None`
+    /// when a newer search is started before this one's results arrive.
     pub fn fuzzy_search_users(
-        &self,
+        &mut self,
         query: String,
-        cx: &Context<Self>,
-    ) -> Task<Result<Vec<Arc<User>>>> {
-        self.load_users(proto::FuzzySearchUsers { query }, cx)
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<Arc<User>>>>> {
+        self.fuzzy_search_users_with_debounce(query, FUZZY_SEARCH_USERS_DEBOUNCE, cx)
+    }
+
+    pub fn fuzzy_search_users_with_debounce(
+        &mut self,
+        query: String,
+        debounce: Duration,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<Arc<User>>>>> {
+        self.latest_fuzzy_search_id += 1;
+        let search_id = self.latest_fuzzy_search_id;
+        let is_latest = move |this: &Self| this.latest_fuzzy_search_id == search_id;
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            let Some(search) = this.update(cx, |this, cx| {
+                is_latest(this).then(|| this.load_users(proto::FuzzySearchUsers { query }, cx))
+            })?
+            else {
+                return Ok(None);
+            };
+            let users = search.await?;
+            Ok(this.read_with(cx, |this, _| is_latest(this).then_some(users))?)
+        })
     }
 
     pub fn get_cached_user(&self, user_id: u64) -> Option<Arc<User>> {
@@ -749,6 +885,63 @@ impl UserStore {
         })
     }
 
+    /// Fetches a user from the server even if it is already cached, replacing the cached entry.
+    /// Use this when cached details such as the display name or avatar may be stale.
+    pub fn refresh_user(&self, user_id: u64, cx: &Context<Self>) -> Task<Result<Arc<User>>> {
+        let load_users = self.load_users(
+            proto::GetUsers {
+                user_ids: vec![user_id],
+            },
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            let users = load_users.await?;
+            this.update(cx, |_, cx| cx.notify())?;
+            users
+                .into_iter()
+                .find(|user| user.id == user_id)
+                .context("server responded with no users")
+        })
+    }
+
+    /// Returns the avatar of the given user, or `None` while it is being fetched. Fetches are
+    /// only repeated when the user's avatar URI changes, so that a URI that failed once falls
+    /// back to the user's initials without being requested again.
+    pub fn avatar(&mut self, user: &Arc<User>, cx: &mut Context<Self>) -> Option<UserAvatar> {
+        if let Some((uri, avatar)) = self.avatars.get(&user.id) {
+            if *uri == user.avatar_uri {
+                return avatar.clone();
+            }
+        }
+
+        let Some(client) = self.client.upgrade() else {
+            return Some(UserAvatar::Initials(user.initials()));
+        };
+        self.avatars
+            .insert(user.id, (user.avatar_uri.clone(), None));
+        let user = user.clone();
+        let http_client = client.http_client();
+        cx.spawn(async move |this, cx| {
+            let avatar = match fetch_avatar(&http_client, &user.avatar_uri).await {
+                Ok(image) => UserAvatar::Image(Arc::new(image)),
+                Err(error) => {
+                    log::warn!("failed to fetch avatar of {}: {error:#}", user.github_login);
+                    UserAvatar::Initials(user.initials())
+                }
+            };
+            this.update(cx, |this, cx| {
+                if let Some((uri, pending)) = this.avatars.get_mut(&user.id) {
+                    if *uri == user.avatar_uri {
+                        *pending = Some(avatar);
+                        cx.notify();
+                    }
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+        None
+    }
+
     pub fn cached_user_by_github_login(&self, github_login: &str) -> Option<Arc<User>> {
         self.by_github_login
             .get(github_login)
@@ -795,6 +988,36 @@ impl UserStore {
         self.edit_prediction_usage
     }
 
+    /// The model request usage snapshots received during this session, oldest first.
+    pub fn model_request_usage_history(&self) -> impl Iterator<Item = &UsageHistoryEntry> {
+        self.model_request_usage_history.iter()
+    }
+
+    /// Exports the model request usage snapshots received during this session, oldest first.
+    pub fn export_model_request_usage_history(&self, format: UsageExportFormat) -> Result<String> {
+        match format {
+            UsageExportFormat::Json => Ok(serde_json::to_string_pretty(
+                &self.model_request_usage_history,
+            )?),
+            UsageExportFormat::Csv => {
+                let mut csv = String::from("timestamp,amount\n");
+                for entry in &self.model_request_usage_history {
+                    writeln!(csv, "{},{}", entry.timestamp.to_rfc3339(), entry.amount)?;
+                }
+                Ok(csv)
+            }
+        }
+    }
+
+    pub fn usage_snapshot(&self) -> UsageSnapshot {
+        UsageSnapshot {
+            current_plan: self.current_plan(),
+            subscription_period: self.subscription_period,
+            model_request_usage: self.model_request_usage,
+            edit_prediction_usage: self.edit_prediction_usage,
+        }
+    }
+
     pub fn watch_current_user(&self) -> watch::Receiver<Option<Arc<User>>> {
         self.current_user.clone()
     }
@@ -862,6 +1085,7 @@ impl UserStore {
         let mut ret = Vec::with_capacity(users.len());
         for user in users {
             let user = User::new(user);
+            self.unknown_user_ids.remove(&user.id);
             if let Some(old) = self.users.insert(user.id, user.clone()) {
                 if old.github_login != user.github_login {
                     self.by_github_login.remove(&old.github_login);
@@ -924,6 +1148,42 @@ impl User {
             name: message.name,
         })
     }
+
+    /// The first letters of the first and last words of the user's name, or the first letter of
+    /// their GitHub login when they have no name.
+    pub fn initials(&self) -> SharedString {
+        let words = self
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let initials = match words.as_slice() {
+            [] => self.github_login.chars().take(1).collect::<String>(),
+            [word] => word.chars().take(1).collect(),
+            [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+        };
+        initials.to_uppercase().into()
+    }
+}
+
+async fn fetch_avatar(http_client: &HttpClientWithUrl, uri: &str) -> Result<Image> {
+    let mut response = http_client.get(uri, AsyncBody::default(), true).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "avatar request failed with status {}",
+        response.status()
+    );
+    let format = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .and_then(|mime_type| ImageFormat::from_mime_type(mime_type.trim()))
+        .context("avatar is not a supported image")?;
+    let mut bytes = Vec::new();
+    response.body_mut().read_to_end(&mut bytes).await?;
+    Ok(Image::from_bytes(format, bytes))
 }
 
 impl Contact {
@@ -956,9 +1216,29 @@ impl Collaborator {
             committer_email: message.committer_email,
         })
     }
+
+    /// Returns the collaborator's git identity, formatted as `Name <email>`,
+    /// if they've shared both their committer name and email.
+    pub fn git_identity(&self) -> Option<String> {
+        let name = self.committer_name.as_ref()?;
+        let email = self.committer_email.as_ref()?;
+        Some(format!("{name} <{email}>"))
+    }
 }
 
 impl RequestUsage {
+    pub fn is_unlimited(&self) -> bool {
+        matches!(self.limit, UsageLimit::Unlimited)
+    }
+
+    /// Returns the maximum number of requests, or `None` if usage is unlimited.
+    pub fn limit_value(&self) -> Option<i32> {
+        match self.limit {
+            UsageLimit::Limited(limit) => Some(limit),
+            UsageLimit::Unlimited => None,
+        }
+    }
+
     pub fn over_limit(&self) -> bool {
         match self.limit {
             UsageLimit::Limited(limit) => self.amount >= limit,
@@ -966,6 +1246,14 @@ impl RequestUsage {
         }
     }
 
+    /// Formats the usage for display, e.g. `42 / 500`, or `42 (unlimited)`.
+    pub fn display(&self) -> String {
+        match self.limit {
+            UsageLimit::Limited(limit) => format!("{} / {limit}", self.amount),
+            UsageLimit::Unlimited => format!("{} (unlimited)", self.amount),
+        }
+    }
+
     pub fn from_proto(amount: u32, limit: proto::UsageLimit) -> Option<Self> {
         let limit = match limit.variant? {
             proto::usage_limit::Variant::Limited(limited) => {
@@ -1017,3 +1305,400 @@ impl EditPredictionUsage {
         )?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::FakeServer;
+    use gpui::{AppContext as _, TestAppContext};
+    use http_client::FakeHttpClient;
+    use settings::SettingsStore;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_contact_presence_changed(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let user_store = cx.new(|cx| UserStore::new(client, cx));
+        user_store.update(cx, |store, _| {
+            for (id, github_login) in [(1, "nathansobo"), (2, "maxbrunsfeld")] {
+                store.users.insert(
+                    id,
+                    Arc::new(User {
+                        id,
+                        github_login: github_login.into(),
+                        avatar_uri: "".into(),
+                        name: None,
+                    }),
+                );
+            }
+        });
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&user_store, move |_, event, _| {
+                if let Event::ContactPresenceChanged {
+                    user_id,
+                    online,
+                    busy,
+                } = event
+                {
+                    events.borrow_mut().push((*user_id, *online, *busy));
+                }
+            })
+            .detach();
+        });
+
+        let contact = |user_id, online, busy| proto::Contact {
+            user_id,
+            online,
+            busy,
+        };
+        user_store
+            .update(cx, |store, cx| {
+                store.update_contacts(
+                    UpdateContacts::Update(proto::UpdateContacts {
+                        contacts: vec![contact(1, false, false), contact(2, true, false)],
+                        ..Default::default()
+                    }),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        // Contacts that are new don't have a presence to change from.
+        assert_eq!(events.borrow().as_slice(), &[]);
+
+        user_store
+            .update(cx, |store, cx| {
+                store.update_contacts(
+                    UpdateContacts::Update(proto::UpdateContacts {
+                        contacts: vec![contact(1, true, false), contact(2, true, false)],
+                        ..Default::default()
+                    }),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(events.borrow().as_slice(), &[(1, true, false)]);
+    }
+
+    #[gpui::test]
+    async fn test_fuzzy_search_users_debounce(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let server = FakeServer::for_client(5, &client, cx).await;
+        let user_store = server.build_user_store(client, cx).await;
+
+        let first_search =
+            user_store.update(cx, |store, cx| store.fuzzy_search_users("ma".into(), cx));
+        let second_search =
+            user_store.update(cx, |store, cx| store.fuzzy_search_users("max".into(), cx));
+        cx.executor().advance_clock(FUZZY_SEARCH_USERS_DEBOUNCE);
+
+        // Only the latest query is sent to the server.
+        let request = server.receive::<proto::FuzzySearchUsers>().await.unwrap();
+        assert_eq!(request.payload.query, "max");
+        server.respond(
+            request.receipt(),
+            proto::UsersResponse {
+                users: vec![proto::User {
+                    id: 2,
+                    github_login: "maxbrunsfeld".into(),
+                    avatar_url: "".into(),
+                    name: None,
+                }],
+            },
+        );
+
+        assert_eq!(first_search.await.unwrap(), None);
+        let users = second_search.await.unwrap().unwrap();
+        assert_eq!(
+            users
+                .iter()
+                .map(|user| user.github_login.as_str())
+                .collect::<Vec<_>>(),
+            ["maxbrunsfeld"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_unknown_users_are_not_requested_again(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let server = FakeServer::for_client(5, &client, cx).await;
+        let user_store = server.build_user_store(client, cx).await;
+
+        let lookup = user_store.update(cx, |store, cx| store.get_users(vec![10], cx));
+        let request = server.receive::<proto::GetUsers>().await.unwrap();
+        assert_eq!(request.payload.user_ids, [10]);
+        server.respond(
+            request.receipt(),
+            proto::UsersResponse { users: Vec::new() },
+        );
+        assert!(lookup.await.is_err());
+
+        // The unknown user is not requested again, so the next request is for another user.
+        let lookup = user_store.update(cx, |store, cx| store.get_users(vec![10], cx));
+        assert!(lookup.await.is_err());
+        let _lookup = user_store.update(cx, |store, cx| store.get_users(vec![11], cx));
+        let request = server.receive::<proto::GetUsers>().await.unwrap();
+        assert_eq!(request.payload.user_ids, [11]);
+    }
+
+    #[gpui::test]
+    async fn test_refresh_user(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let server = FakeServer::for_client(5, &client, cx).await;
+        let user_store = server.build_user_store(client, cx).await;
+
+        let user = |name: &str| proto::User {
+            id: 10,
+            github_login: "nathansobo".into(),
+            avatar_url: "".into(),
+            name: Some(name.into()),
+        };
+        let lookup = user_store.update(cx, |store, cx| store.get_user(10, cx));
+        let request = server.receive::<proto::GetUsers>().await.unwrap();
+        server.respond(
+            request.receipt(),
+            proto::UsersResponse {
+                users: vec![user("Nathan")],
+            },
+        );
+        assert_eq!(lookup.await.unwrap().name.as_deref(), Some("Nathan"));
+
+        // Cached users are requested again when refreshed, and the cache is updated.
+        let refresh = user_store.update(cx, |store, cx| store.refresh_user(10, cx));
+        let request = server.receive::<proto::GetUsers>().await.unwrap();
+        assert_eq!(request.payload.user_ids, [10]);
+        server.respond(
+            request.receipt(),
+            proto::UsersResponse {
+                users: vec![user("Nathan Sobo")],
+            },
+        );
+        assert_eq!(refresh.await.unwrap().name.as_deref(), Some("Nathan Sobo"));
+        user_store.read_with(cx, |store, _| {
+            assert_eq!(
+                store.get_cached_user(10).unwrap().name.as_deref(),
+                Some("Nathan Sobo")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_plan_changed(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let server = FakeServer::for_client(5, &client, cx).await;
+        let user_store = server.build_user_store(client, cx).await;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&user_store, move |_, event, _| {
+                if let Event::PlanChanged { from, to } = event {
+                    events.borrow_mut().push((*from, *to));
+                }
+            })
+            .detach();
+        });
+
+        let limited = |limit| proto::UsageLimit {
+            variant: Some(proto::usage_limit::Variant::Limited(
+                proto::usage_limit::Limited { limit },
+            )),
+        };
+        let update_plan = |plan: proto::Plan| proto::UpdateUserPlan {
+            plan: plan as i32,
+            usage: Some(proto::SubscriptionUsage {
+                model_requests_usage_amount: 42,
+                model_requests_usage_limit: Some(limited(500)),
+                edit_predictions_usage_amount: 0,
+                edit_predictions_usage_limit: Some(limited(2000)),
+            }),
+            subscription_period: Some(proto::SubscriptionPeriod {
+                started_at: 1_700_000_000,
+                ended_at: 1_702_592_000,
+            }),
+            ..Default::default()
+        };
+
+        server.send(update_plan(proto::Plan::Free));
+        cx.run_until_parked();
+        server.send(update_plan(proto::Plan::Free));
+        cx.run_until_parked();
+        server.send(update_plan(proto::Plan::ZedPro));
+        cx.run_until_parked();
+        // Updates that don't change the plan aren't reported.
+        assert_eq!(
+            events.borrow().as_slice(),
+            &[
+                (None, proto::Plan::Free),
+                (Some(proto::Plan::Free), proto::Plan::ZedPro)
+            ]
+        );
+
+        user_store.read_with(cx, |store, _| {
+            let snapshot = store.usage_snapshot();
+            assert_eq!(snapshot.current_plan, Some(proto::Plan::ZedPro));
+            let (started_at, ended_at) = snapshot.subscription_period.unwrap();
+            assert_eq!(started_at.timestamp(), 1_700_000_000);
+            assert_eq!(ended_at.timestamp(), 1_702_592_000);
+            let model_request_usage = snapshot.model_request_usage.unwrap();
+            assert_eq!(model_request_usage.amount, 42);
+            assert_eq!(model_request_usage.limit_value(), Some(500));
+            assert_eq!(
+                snapshot.edit_prediction_usage.unwrap().limit_value(),
+                Some(2000)
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_export_model_request_usage_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let user_store = cx.new(|cx| UserStore::new(client, cx));
+
+        user_store.update(cx, |store, cx| {
+            for amount in [1, 2] {
+                store.update_model_request_usage(
+                    ModelRequestUsage(RequestUsage {
+                        limit: UsageLimit::Limited(500),
+                        amount,
+                    }),
+                    cx,
+                );
+            }
+        });
+
+        user_store.read_with(cx, |store, _| {
+            let entries = store.model_request_usage_history().collect::<Vec<_>>();
+            assert_eq!(
+                entries.iter().map(|entry| entry.amount).collect::<Vec<_>>(),
+                [1, 2]
+            );
+
+            let csv = store
+                .export_model_request_usage_history(UsageExportFormat::Csv)
+                .unwrap();
+            let expected_csv = format!(
+                "timestamp,amount\n{},1\n{},2\n",
+                entries[0].timestamp.to_rfc3339(),
+                entries[1].timestamp.to_rfc3339()
+            );
+            assert_eq!(csv, expected_csv);
+
+            let json = store
+                .export_model_request_usage_history(UsageExportFormat::Json)
+                .unwrap();
+            let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+            let amounts = json
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| entry["amount"].as_i64().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(amounts, [1, 2]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_avatar_falls_back_to_initials(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| Client::new(FakeHttpClient::with_404_response(), cx));
+        let user_store = cx.new(|cx| UserStore::new(client, cx));
+        let user = Arc::new(User {
+            id: 1,
+            github_login: "nathansobo".into(),
+            avatar_uri: "https://avatars.example.com/1".into(),
+            name: Some("Nathan Sobo".into()),
+        });
+
+        let avatar = user_store.update(cx, |store, cx| store.avatar(&user, cx));
+        assert_eq!(avatar, None);
+        cx.run_until_parked();
+        let avatar = user_store.update(cx, |store, cx| store.avatar(&user, cx));
+        assert_eq!(avatar, Some(UserAvatar::Initials("NS".into())));
+    }
+
+    #[test]
+    fn test_initials() {
+        let user = |github_login: &str, name: Option<&str>| User {
+            id: 1,
+            github_login: github_login.into(),
+            avatar_uri: "".into(),
+            name: name.map(Into::into),
+        };
+        assert_eq!(user("nathansobo", Some("Nathan Sobo")).initials(), "NS");
+        assert_eq!(
+            user("maxbrunsfeld", Some("max de la brunsfeld")).initials(),
+            "MB"
+        );
+        assert_eq!(user("nathansobo", Some("Nathan")).initials(), "N");
+        assert_eq!(user("nathansobo", None).initials(), "N");
+        assert_eq!(user("nathansobo", Some("  ")).initials(), "N");
+    }
+
+    #[test]
+    fn test_git_identity() {
+        let collaborator =
+            |committer_name: Option<&str>, committer_email: Option<&str>| Collaborator {
+                peer_id: proto::PeerId { owner_id: 1, id: 1 },
+                replica_id: 1,
+                user_id: 1,
+                is_host: false,
+                committer_name: committer_name.map(Into::into),
+                committer_email: committer_email.map(Into::into),
+            };
+        assert_eq!(
+            collaborator(Some("Nathan Sobo"), Some("nathan@zed.dev")).git_identity(),
+            Some("Nathan Sobo <nathan@zed.dev>".into())
+        );
+        assert_eq!(collaborator(Some("Nathan Sobo"), None).git_identity(), None);
+        assert_eq!(
+            collaborator(None, Some("nathan@zed.dev")).git_identity(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_request_usage() {
+        let limited = RequestUsage {
+            limit: UsageLimit::Limited(500),
+            amount: 42,
+        };
+        assert!(!limited.is_unlimited());
+        assert_eq!(limited.limit_value(), Some(500));
+        assert!(!limited.over_limit());
+        assert_eq!(limited.display(), "42 / 500");
+
+        let exhausted = RequestUsage {
+            limit: UsageLimit::Limited(500),
+            amount: 500,
+        };
+        assert!(exhausted.over_limit());
+
+        let unlimited = RequestUsage {
+            limit: UsageLimit::Unlimited,
+            amount: 42,
+        };
+        assert!(unlimited.is_unlimited());
+        assert_eq!(unlimited.limit_value(), None);
+        assert!(!unlimited.over_limit());
+        assert_eq!(unlimited.display(), "42 (unlimited)");
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            crate::init_settings(cx);
+        });
+    }
+}
@@ -345,7 +345,9 @@ impl PickerDelegate for ChannelModalDelegate {
                     .update(cx, |store, cx| store.fuzzy_search_users(query, cx));
                 cx.spawn_in(window, async move |picker, cx| {
                     async {
-                        let users = search_users.await?;
+                        let Some(users) = search_users.await? else {
+                            return Ok(());
+                        };
                         picker.update(cx, |picker, cx| {
                             picker.delegate.matching_users = users;
                             cx.notify();
@@ -104,7 +104,9 @@ impl PickerDelegate for ContactFinderDelegate {
 
         cx.spawn_in(window, async move |picker, cx| {
             async {
-                let potential_contacts = search_users.await?;
+                let Some(potential_contacts) = search_users.await? else {
+                    return Ok(());
+                };
                 picker.update(cx, |picker, cx| {
                     picker.delegate.potential_contacts = potential_contacts.into();
                     cx.notify();
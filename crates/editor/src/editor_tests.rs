@@ -15382,6 +15382,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                     "some other init value": false
                 })),
                 enable_lsp_tasks: false,
+                extra_disk_diagnostic_sources: Vec::new(),
             },
         );
     });
@@ -15402,6 +15403,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                     "anotherInitValue": false
                 })),
                 enable_lsp_tasks: false,
+                extra_disk_diagnostic_sources: Vec::new(),
             },
         );
     });
@@ -15422,6 +15424,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                     "anotherInitValue": false
                 })),
                 enable_lsp_tasks: false,
+                extra_disk_diagnostic_sources: Vec::new(),
             },
         );
     });
@@ -15440,6 +15443,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                 settings: None,
                 initialization_options: None,
                 enable_lsp_tasks: false,
+                extra_disk_diagnostic_sources: Vec::new(),
             },
         );
     });
@@ -63,6 +63,11 @@ impl OpenPathDelegate {
         }
     }
 
+    /// Whether the first directory listing is still being fetched.
+    pub fn is_loading(&self) -> bool {
+        matches!(self.directory_state, DirectoryState::None { .. })
+    }
+
     fn get_entry(&self, selected_match_index: usize) -> Option<CandidateInfo> {
         match &self.directory_state {
             DirectoryState::List { entries, .. } => {
@@ -1,3 +1,4 @@
+use agent_ui::usage_color;
 use anyhow::Result;
 use client::{DisableAiSettings, UserStore, zed_urls};
 use editor::{
@@ -27,10 +28,7 @@ use ui::{
     Clickable, ContextMenu, ContextMenuEntry, DocumentationSide, IconButton, IconButtonShape,
     Indicator, PopoverMenu, PopoverMenuHandle, ProgressBar, Tooltip, prelude::*,
 };
-use workspace::{
-    StatusItemView, Workspace, create_and_open_local_file, item::ItemHandle,
-};
-use zed_llm_client::UsageLimit;
+use workspace::{StatusItemView, Workspace, create_and_open_local_file, item::ItemHandle};
 use zeta::RateCompletions;
 
 actions!(
@@ -381,12 +379,9 @@ impl InlineCompletionButton {
                 menu = menu
                     .custom_entry(
                         move |_window, cx| {
-                            let used_percentage = match usage.limit {
-                                UsageLimit::Limited(limit) => {
-                                    Some((usage.amount as f32 / limit as f32) * 100.)
-                                }
-                                UsageLimit::Unlimited => None,
-                            };
+                            let used_percentage = usage
+                                .limit_value()
+                                .map(|limit| (usage.amount as f32 / limit as f32) * 100.);
 
                             h_flex()
                                 .flex_1()
@@ -397,14 +392,9 @@ impl InlineCompletionButton {
                                     }),
                                 )
                                 .child(
-                                    Label::new(match usage.limit {
-                                        UsageLimit::Limited(limit) => {
-                                            format!("{} / {limit}", usage.amount)
-                                        }
-                                        UsageLimit::Unlimited => format!("{} / ∞", usage.amount),
-                                    })
-                                    .size(LabelSize::Small)
-                                    .color(Color::Muted),
+                                    Label::new(usage.display())
+                                        .size(LabelSize::Small)
+                                        .color(usage_color(&usage)),
                                 )
                                 .into_any_element()
                         },
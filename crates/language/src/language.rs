@@ -379,6 +379,17 @@ pub trait LspAdapter: 'static + Send + Sync {
         None
     }
 
+    /// Checks whether a binary configured through the `binary.path` setting is usable.
+    /// When this returns `false`, the configured binary is ignored and the server binary
+    /// is looked up as if no path had been configured.
+    async fn check_configured_binary(
+        &self,
+        _: &LanguageServerBinary,
+        _: &dyn LspAdapterDelegate,
+    ) -> bool {
+        true
+    }
+
     fn will_fetch_server(
         &self,
         _: &Arc<dyn LspAdapterDelegate>,
@@ -3,7 +3,7 @@ use editor::{Editor, EditorEvent, actions::MoveToEnd, scroll::Autoscroll};
 use futures::{StreamExt, channel::mpsc};
 use gpui::{
     AnyView, App, Context, Corner, Entity, EventEmitter, FocusHandle, Focusable, Global,
-    IntoElement, ParentElement, Render, Styled, Subscription, WeakEntity, Window, actions, div,
+    IntoElement, ParentElement, Render, Styled, Subscription, WeakEntity, Window, div,
 };
 use itertools::Itertools;
 use language::{LanguageServerId, language_settings::SoftWrap};
@@ -22,6 +22,8 @@ use workspace::{
 
 use crate::get_or_create_tool;
 
+pub use zed_actions::dev::OpenLanguageServerLogs;
+
 const SEND_LINE: &str = "\n// Send:";
 const RECEIVE_LINE: &str = "\n// Receive:";
 const MAX_STORED_LOG_ENTRIES: usize = 2000;
@@ -198,14 +200,6 @@ pub(crate) struct LogMenuItem {
     pub server_kind: LanguageServerKind,
 }
 
-actions!(
-    dev,
-    [
-        /// Opens the language server protocol logs viewer.
-        OpenLanguageServerLogs
-    ]
-);
-
 pub(super) struct GlobalLogStore(pub WeakEntity<LogStore>);
 
 impl Global for GlobalLogStore {}
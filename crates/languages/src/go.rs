@@ -1,17 +1,19 @@
 use anyhow::{Result};
 use async_trait::async_trait;
 use collections::HashMap;
+use futures::StreamExt;
 
 use gpui::{App, AsyncApp, Task};
 pub use language::*;
 use lsp::{LanguageServerBinary, LanguageServerName};
-use project::Fs;
+use project::{Fs, lsp_store::language_server_settings};
 use regex::Regex;
-use serde_json::json;
+use serde_json::{Value, json};
 use std::{
     borrow::Cow,
     ffi::OsString,
     ops::Range,
+    path::{Path, PathBuf},
     str,
     sync::{
         Arc, LazyLock,
@@ -19,6 +21,7 @@ use std::{
     },
 };
 use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use util::merge_json_value_into;
 
 fn server_binary_arguments() -> Vec<OsString> {
     vec!["-mode=stdio".into()]
@@ -87,18 +90,31 @@ impl super::LspAdapter for GoLspAdapter {
         _: &dyn Fs,
         _: &Arc<dyn LspAdapterDelegate>,
     ) -> Result<Option<serde_json::Value>> {
-        Ok(Some(json!({
-            "usePlaceholders": true,
-            "hints": {
-                "assignVariableTypes": true,
-                "compositeLiteralFields": true,
-                "compositeLiteralTypes": true,
-                "constantValues": true,
-                "functionTypeParameters": true,
-                "parameterNames": true,
-                "rangeVariableTypes": true
-            }
-        })))
+        Ok(Some(gopls_configuration([])))
+    }
+
+    async fn workspace_configuration(
+        self: Arc<Self>,
+        _: &dyn Fs,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+        _: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AsyncApp,
+    ) -> Result<Value> {
+        // gopls applies this on top of the initialization options, so it has to carry the user's
+        // initialization options too, or the defaults would undo them.
+        let overrides = cx.update(|cx| {
+            language_server_settings(delegate.as_ref(), &Self::SERVER_NAME, cx)
+                .map(|settings| {
+                    [
+                        settings.initialization_options.clone(),
+                        settings.settings.clone(),
+                    ]
+                })
+                .unwrap_or_default()
+        })?;
+        Ok(json!({
+            "gopls": gopls_configuration(overrides.into_iter().flatten())
+        }))
     }
 
     async fn label_for_completion(
@@ -315,6 +331,27 @@ impl super::LspAdapter for GoLspAdapter {
     }
 }
 
+/// Zed's default gopls options, which turn on every inlay hint, with the given overrides
+/// merged over them in order.
+fn gopls_configuration(overrides: impl IntoIterator<Item = Value>) -> Value {
+    let mut configuration = json!({
+        "usePlaceholders": true,
+        "hints": {
+            "assignVariableTypes": true,
+            "compositeLiteralFields": true,
+            "compositeLiteralTypes": true,
+            "constantValues": true,
+            "functionTypeParameters": true,
+            "parameterNames": true,
+            "rangeVariableTypes": true
+        }
+    });
+    for overrides in overrides {
+        merge_json_value_into(overrides, &mut configuration);
+    }
+    configuration
+}
+
 fn adjust_runs(
     delta: usize,
     mut runs: Vec<(Range<usize>, HighlightId)>,
@@ -331,8 +368,27 @@ pub(crate) struct GoContextProvider;
 const GO_PACKAGE_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("GO_PACKAGE"));
 const GO_MODULE_ROOT_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("GO_MODULE_ROOT"));
+const GO_MODULE_PATH_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_MODULE_PATH"));
+const GO_IMPORT_PATH_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_IMPORT_PATH"));
+/// The symbol under the cursor as `go doc` names it: `Type.Method` for methods.
+const GO_DOC_SYMBOL_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_DOC_SYMBOL"));
 const GO_SUBTEST_NAME_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("GO_SUBTEST_NAME"));
+const GO_TEST_WITH_SUBTEST_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_TEST_WITH_SUBTEST"));
+/// The name of the `Benchmark*` function a benchmark runnable was found in.
+const GO_BENCH_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_BENCH_NAME"));
+/// The directory of the `go.work` file the current file belongs to, if any.
+const GO_WORK_ROOT_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_WORK_ROOT"));
+/// The `-timeout` to run tests with, e.g. `30s`. Taken from the task variables the context is
+/// built from, or else from the `GO_TEST_TIMEOUT` task variable of the Go language settings.
+const GO_TEST_TIMEOUT_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("GO_TEST_TIMEOUT"));
 
 impl ContextProvider for GoContextProvider {
     fn build_context(
@@ -343,12 +399,23 @@ impl ContextProvider for GoContextProvider {
         _: Arc<dyn LanguageToolchainStore>,
         cx: &mut gpui::App,
     ) -> Task<Result<TaskVariables>> {
-        let local_abs_path = location
-            .file_location
-            .buffer
-            .read(cx)
-            .file()
-            .and_then(|file| Some(file.as_local()?.abs_path(cx)));
+        const TEST_TIMEOUT_SETTING: &str = "GO_TEST_TIMEOUT";
+
+        let file = location.file_location.buffer.read(cx).file();
+        let local_abs_path = file.and_then(|file| Some(file.as_local()?.abs_path(cx)));
+        let language_settings = language_settings::language_settings(Some("Go".into()), file, cx);
+        let go_test_timeout_variable = variables
+            .get(&GO_TEST_TIMEOUT_TASK_VARIABLE)
+            .or_else(|| {
+                language_settings
+                    .tasks
+                    .variables
+                    .get(TEST_TIMEOUT_SETTING)
+                    .map(String::as_str)
+            })
+            .map(|timeout| timeout.trim())
+            .filter(|timeout| !timeout.is_empty())
+            .map(|timeout| (GO_TEST_TIMEOUT_TASK_VARIABLE.clone(), timeout.to_owned()));
 
         let go_package_variable = local_abs_path
             .as_deref()
@@ -372,30 +439,110 @@ impl ContextProvider for GoContextProvider {
                 (GO_PACKAGE_TASK_VARIABLE.clone(), package_name.to_string())
             });
 
-        let go_module_root_variable = local_abs_path
+        let buffer_dir = local_abs_path
             .as_deref()
-            .and_then(|local_abs_path| local_abs_path.parent())
-            .map(|buffer_dir| {
-                // Walk dirtree up until getting the first go.mod file
-                let module_dir = buffer_dir
-                    .ancestors()
-                    .find(|dir| dir.join("go.mod").is_file())
-                    .map(|dir| dir.to_string_lossy().to_string())
-                    .unwrap_or_else(|| ".".to_string());
-
-                (GO_MODULE_ROOT_TASK_VARIABLE.clone(), module_dir)
-            });
+            .and_then(|local_abs_path| local_abs_path.parent());
+        let go_work = buffer_dir.and_then(|buffer_dir| {
+            buffer_dir.ancestors().find_map(|dir| {
+                let go_work = std::fs::read_to_string(dir.join("go.work")).ok()?;
+                Some((dir, go_work))
+            })
+        });
+        let go_work_root_variable = go_work.as_ref().map(|(go_work_dir, _)| {
+            (
+                GO_WORK_ROOT_TASK_VARIABLE.clone(),
+                go_work_dir.to_string_lossy().to_string(),
+            )
+        });
+
+        // In a workspace, the module is the `use` directory closest to the file; otherwise, walk
+        // dirtree up until getting the first go.mod file
+        let module_dir = buffer_dir.and_then(|buffer_dir| {
+            go_work
+                .as_ref()
+                .and_then(|(go_work_dir, go_work)| {
+                    go_work_module_root(go_work_dir, go_work, buffer_dir)
+                })
+                .or_else(|| {
+                    buffer_dir
+                        .ancestors()
+                        .find(|dir| dir.join("go.mod").is_file())
+                        .map(Path::to_path_buf)
+                })
+        });
+
+        let go_module_root_variable = local_abs_path.is_some().then(|| {
+            let module_dir = module_dir
+                .as_deref()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_else(|| ".".to_string());
+            (GO_MODULE_ROOT_TASK_VARIABLE.clone(), module_dir)
+        });
+
+        let module_path = module_dir
+            .as_deref()
+            .and_then(|dir| std::fs::read_to_string(dir.join("go.mod")).ok())
+            .and_then(|go_mod| parse_go_module_path(&go_mod));
+
+        let go_import_path_variable = module_path
+            .as_deref()
+            .zip(module_dir.as_deref())
+            .zip(
+                local_abs_path
+                    .as_deref()
+                    .and_then(|local_abs_path| local_abs_path.parent()),
+            )
+            .and_then(|((module_path, module_dir), package_dir)| {
+                go_import_path(module_path, module_dir, package_dir)
+            })
+            .map(|import_path| (GO_IMPORT_PATH_TASK_VARIABLE.clone(), import_path));
+
+        let go_module_path_variable =
+            module_path.map(|module_path| (GO_MODULE_PATH_TASK_VARIABLE.clone(), module_path));
+
+        let go_doc_symbol_variable = enclosing_method_at(location.file_location, cx)
+            .or_else(|| variables.get(&VariableName::Symbol).map(str::to_owned))
+            .map(|symbol| (GO_DOC_SYMBOL_TASK_VARIABLE.clone(), symbol));
 
         let _subtest_name = variables.get(&VariableName::Custom(Cow::Borrowed("_subtest_name")));
 
         let go_subtest_variable = extract_subtest_name(_subtest_name.unwrap_or(""))
             .map(|subtest_name| (GO_SUBTEST_NAME_TASK_VARIABLE.clone(), subtest_name));
 
+        // Table test cases know their enclosing test from the runnable query, while `t.Run`
+        // subtests rely on the symbol under the cursor.
+        let table_test_case = variables
+            .get(&VariableName::Custom(Cow::Borrowed("_test_name")))
+            .zip(variables.get(&VariableName::Custom(Cow::Borrowed(
+                "_table_test_case_name",
+            ))));
+        let go_test_with_subtest_variable = table_test_case
+            .or_else(|| variables.get(&VariableName::Symbol).zip(_subtest_name))
+            .and_then(|(test_name, subtest_name)| {
+                let subtest_name = extract_subtest_name(subtest_name)?;
+                Some((
+                    GO_TEST_WITH_SUBTEST_TASK_VARIABLE.clone(),
+                    format!("\\^{test_name}\\$/\\^{subtest_name}\\$"),
+                ))
+            });
+
+        let go_bench_name_variable = variables
+            .get(&VariableName::Custom(Cow::Borrowed("_name")))
+            .filter(|bench_name| bench_name.starts_with("Benchmark"))
+            .map(|bench_name| (GO_BENCH_NAME_TASK_VARIABLE.clone(), bench_name.clone()));
+
         Task::ready(Ok(TaskVariables::from_iter(
             [
                 go_package_variable,
+                go_bench_name_variable,
                 go_subtest_variable,
+                go_test_with_subtest_variable,
                 go_module_root_variable,
+                go_work_root_variable,
+                go_module_path_variable,
+                go_import_path_variable,
+                go_doc_symbol_variable,
+                go_test_timeout_variable,
             ]
             .into_iter()
             .flatten(),
@@ -404,10 +551,14 @@ impl ContextProvider for GoContextProvider {
 
     fn associated_tasks(
         &self,
-        _: Arc<dyn Fs>,
-        _: Option<Arc<dyn File>>,
-        _: &App,
+        fs: Arc<dyn Fs>,
+        file: Option<Arc<dyn File>>,
+        cx: &App,
     ) -> Task<Option<TaskTemplates>> {
+        let worktree_root_and_path = project::File::from_dyn(file.as_ref()).and_then(|file| {
+            let worktree_root = file.worktree.read(cx).root_dir()?;
+            Some((worktree_root, file.path().clone()))
+        });
         let package_cwd = if GO_PACKAGE_TASK_VARIABLE.template_value() == "." {
             None
         } else {
@@ -415,7 +566,7 @@ impl ContextProvider for GoContextProvider {
         };
         let module_cwd = Some(GO_MODULE_ROOT_TASK_VARIABLE.template_value());
 
-        Task::ready(Some(TaskTemplates(vec![
+        let mut task_templates = vec![
             TaskTemplate {
                 label: format!(
                     "go test {} -run {}",
@@ -446,23 +597,40 @@ impl ContextProvider for GoContextProvider {
                 cwd: module_cwd.clone(),
                 ..TaskTemplate::default()
             },
+            TaskTemplate {
+                label: "go test -short ./...".into(),
+                command: "go".into(),
+                args: vec!["test".into(), "-short".into(), "./...".into()],
+                cwd: module_cwd.clone(),
+                ..TaskTemplate::default()
+            },
             TaskTemplate {
                 label: format!(
-                    "go test {} -v -run {}/{}",
+                    "go test -timeout {} ./...",
+                    GO_TEST_TIMEOUT_TASK_VARIABLE.template_value(),
+                ),
+                command: "go".into(),
+                args: vec![
+                    "test".into(),
+                    "-timeout".into(),
+                    GO_TEST_TIMEOUT_TASK_VARIABLE.template_value(),
+                    "./...".into(),
+                ],
+                cwd: module_cwd.clone(),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "go test {} -v -run {}",
                     GO_PACKAGE_TASK_VARIABLE.template_value(),
-                    VariableName::Symbol.template_value(),
-                    GO_SUBTEST_NAME_TASK_VARIABLE.template_value(),
+                    GO_TEST_WITH_SUBTEST_TASK_VARIABLE.template_value(),
                 ),
                 command: "go".into(),
                 args: vec![
                     "test".into(),
                     "-v".into(),
                     "-run".into(),
-                    format!(
-                        "\\^{}\\$/\\^{}\\$",
-                        VariableName::Symbol.template_value(),
-                        GO_SUBTEST_NAME_TASK_VARIABLE.template_value(),
-                    ),
+                    GO_TEST_WITH_SUBTEST_TASK_VARIABLE.template_value(),
                 ],
                 cwd: package_cwd.clone(),
                 tags: vec!["go-subtest".to_owned()],
@@ -472,18 +640,12 @@ impl ContextProvider for GoContextProvider {
                 label: format!(
                     "go test {} -bench {}",
                     GO_PACKAGE_TASK_VARIABLE.template_value(),
-                    VariableName::Symbol.template_value()
+                    GO_BENCH_NAME_TASK_VARIABLE.template_value()
                 ),
                 command: "go".into(),
-                args: vec![
-                    "test".into(),
-                    "-benchmem".into(),
-                    "-run='^$'".into(),
-                    "-bench".into(),
-                    format!("\\^{}\\$", VariableName::Symbol.template_value()),
-                ],
+                args: go_bench_args(&GO_BENCH_NAME_TASK_VARIABLE.template_value()),
                 cwd: package_cwd.clone(),
-                tags: vec!["go-benchmark".to_owned()],
+                tags: vec!["go-bench".to_owned()],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
@@ -526,8 +688,260 @@ impl ContextProvider for GoContextProvider {
                 cwd: module_cwd.clone(),
                 ..TaskTemplate::default()
             },
-        ])))
+            TaskTemplate {
+                label: format!(
+                    "go doc {} {}",
+                    GO_IMPORT_PATH_TASK_VARIABLE.template_value(),
+                    GO_DOC_SYMBOL_TASK_VARIABLE.template_value(),
+                ),
+                command: "go".into(),
+                args: vec![
+                    "doc".into(),
+                    GO_IMPORT_PATH_TASK_VARIABLE.template_value(),
+                    GO_DOC_SYMBOL_TASK_VARIABLE.template_value(),
+                ],
+                cwd: module_cwd.clone(),
+                ..TaskTemplate::default()
+            },
+        ];
+
+        let Some((worktree_root, file_relative_path)) = worktree_root_and_path else {
+            return Task::ready(Some(TaskTemplates(task_templates)));
+        };
+        cx.background_spawn(async move {
+            if let Some(module_root) =
+                find_go_module_root(fs.as_ref(), &worktree_root, &file_relative_path).await
+            {
+                for main_package in find_main_packages(fs.as_ref(), &module_root).await {
+                    task_templates.push(TaskTemplate {
+                        label: format!("go run {main_package}"),
+                        command: "go".into(),
+                        args: vec!["run".into(), format!("./{main_package}")],
+                        cwd: module_cwd.clone(),
+                        tags: vec!["go-main-package".to_owned()],
+                        ..TaskTemplate::default()
+                    });
+                }
+            }
+            Some(TaskTemplates(task_templates))
+        })
+    }
+}
+
+/// Runs only the given benchmark: `-run '^$'` matches no test, so that unit tests don't run
+/// along with it.
+fn go_bench_args(bench_name: &str) -> Vec<String> {
+    vec![
+        "test".into(),
+        "-bench".into(),
+        format!("\\^{bench_name}\\$"),
+        "-run".into(),
+        "\\^\\$".into(),
+        "-benchmem".into(),
+    ]
+}
+
+/// The `Type.Method` name of the method declaration enclosing the location, if any.
+fn enclosing_method_at(location: &Location, cx: &App) -> Option<String> {
+    let buffer = location.buffer.read(cx);
+    let offset = location.range.start.to_offset(buffer);
+    let layer = buffer.syntax_layer_at(offset)?;
+    let mut node = layer.node().descendant_for_byte_range(offset, offset);
+    while let Some(current) = node {
+        if current.kind() == "method_declaration" {
+            let name = current.child_by_field_name("name")?;
+            let mut receiver_type = current
+                .child_by_field_name("receiver")?
+                .named_child(0)?
+                .child_by_field_name("type")?;
+            // Receivers may be pointers and have type parameters, e.g. `*Stack[T]`.
+            while receiver_type.kind() != "type_identifier" {
+                receiver_type = match receiver_type.kind() {
+                    "pointer_type" => receiver_type.named_child(0)?,
+                    "generic_type" => receiver_type.child_by_field_name("type")?,
+                    _ => return None,
+                };
+            }
+            return Some(format!(
+                "{}.{}",
+                buffer
+                    .text_for_range(receiver_type.byte_range())
+                    .collect::<String>(),
+                buffer.text_for_range(name.byte_range()).collect::<String>()
+            ));
+        }
+        node = current.parent();
+    }
+    None
+}
+
+/// The import path of the package in `package_dir`, which belongs to the module rooted at
+/// `module_dir` whose `go.mod` declares `module_path`.
+fn go_import_path(module_path: &str, module_dir: &Path, package_dir: &Path) -> Option<String> {
+    let relative_dir = package_dir.strip_prefix(module_dir).ok()?;
+    let mut import_path = module_path.to_string();
+    for component in relative_dir.components() {
+        import_path.push('/');
+        import_path.push_str(component.as_os_str().to_str()?);
+    }
+    Some(import_path)
+}
+
+/// Reads the import path declared by the `module` directive of a `go.mod` file.
+fn parse_go_module_path(go_mod: &str) -> Option<String> {
+    go_mod.lines().find_map(|line| {
+        let line = line.split("//").next()?.trim();
+        let module_path = line.strip_prefix("module")?;
+        if !module_path.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let module_path = module_path.trim().trim_matches(|c| c == '"' || c == '`');
+        (!module_path.is_empty()).then(|| module_path.to_string())
+    })
+}
+
+/// Reads the directories listed by the `use` directives of a `go.work` file, both in their
+/// single-line and block forms.
+fn parse_go_work_uses(go_work: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_use_block = false;
+    for line in go_work.lines() {
+        let Some(line) = line.split("//").next().map(str::trim) else {
+            continue;
+        };
+        let directory = if in_use_block {
+            if line == ")" {
+                in_use_block = false;
+                continue;
+            }
+            line
+        } else {
+            let Some(directive) = line.strip_prefix("use") else {
+                continue;
+            };
+            if !directive.starts_with(|c: char| c.is_whitespace() || c == '(') {
+                continue;
+            }
+            let directive = directive.trim();
+            if let Some(block) = directive.strip_prefix('(') {
+                in_use_block = true;
+                block.trim()
+            } else {
+                directive
+            }
+        };
+        let directory = directory.trim_matches(|c| c == '"' || c == '`');
+        if !directory.is_empty() {
+            uses.push(directory.to_string());
+        }
+    }
+    uses
+}
+
+/// Picks the module of the `go.work` in `go_work_dir` that contains `package_dir`: the `use`
+/// directory that is its nearest ancestor.
+fn go_work_module_root(go_work_dir: &Path, go_work: &str, package_dir: &Path) -> Option<PathBuf> {
+    parse_go_work_uses(go_work)
+        .into_iter()
+        .map(|directory| {
+            let mut module_dir = PathBuf::new();
+            for component in go_work_dir.join(directory).components() {
+                match component {
+                    std::path::Component::CurDir => {}
+                    std::path::Component::ParentDir => {
+                        module_dir.pop();
+                    }
+                    component => module_dir.push(component),
+                }
+            }
+            module_dir
+        })
+        .filter(|module_dir| package_dir.starts_with(module_dir))
+        .max_by_key(|module_dir| module_dir.components().count())
+}
+
+/// Finds the closest directory containing a `go.mod`, starting from the file's directory and
+/// stopping at the worktree root.
+async fn find_go_module_root(
+    fs: &dyn Fs,
+    worktree_root: &Path,
+    file_relative_path: &Path,
+) -> Option<PathBuf> {
+    for directory in file_relative_path
+        .ancestors()
+        .skip(1)
+        .map(|path| worktree_root.join(path))
+    {
+        if fs.is_file(&directory.join("go.mod")).await {
+            return Some(directory);
+        }
+    }
+    None
+}
+
+/// Lists the `cmd/<name>` directories of a module that contain a `package main`, relative to the
+/// module root.
+async fn find_main_packages(fs: &dyn Fs, module_root: &Path) -> Vec<String> {
+    let Ok(mut entries) = fs.read_dir(&module_root.join("cmd")).await else {
+        return Vec::new();
+    };
+    let mut main_packages = Vec::new();
+    while let Some(entry) = entries.next().await {
+        let Ok(directory) = entry else {
+            continue;
+        };
+        if !fs.is_dir(&directory).await {
+            continue;
+        }
+        let Some(name) = directory.file_name() else {
+            continue;
+        };
+        if is_main_package(fs, &directory).await {
+            main_packages.push(format!("cmd/{}", name.to_string_lossy()));
+        }
+    }
+    main_packages.sort();
+    main_packages
+}
+
+async fn is_main_package(fs: &dyn Fs, directory: &Path) -> bool {
+    let Ok(mut entries) = fs.read_dir(directory).await else {
+        return false;
+    };
+    while let Some(entry) = entries.next().await {
+        let Ok(path) = entry else {
+            continue;
+        };
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !file_name.ends_with(".go") || file_name.ends_with("_test.go") {
+            continue;
+        }
+        if let Ok(contents) = fs.load(&path).await
+            && contents.lines().any(is_package_main_clause)
+        {
+            return true;
+        }
     }
+    false
+}
+
+/// Whether the line is a `package main` clause, which may be surrounded by comments.
+fn is_package_main_clause(line: &str) -> bool {
+    let mut line = line.trim_start();
+    while let Some(comment) = line.strip_prefix("/*") {
+        let Some((_, rest)) = comment.split_once("*/") else {
+            return false;
+        };
+        line = rest.trim_start();
+    }
+    line.strip_prefix("package main").is_some_and(|rest| {
+        rest.is_empty()
+            || rest.starts_with(char::is_whitespace)
+            || rest.starts_with("//")
+            || rest.starts_with("/*")
+    })
 }
 
 fn extract_subtest_name(input: &str) -> Option<String> {
@@ -555,8 +969,10 @@ fn extract_subtest_name(input: &str) -> Option<String> {
 mod tests {
     use super::*;
     use crate::language;
-    use gpui::{AppContext, Hsla, TestAppContext};
+    use gpui::{AppContext, BackgroundExecutor, Hsla, TestAppContext};
+    use project::FakeFs;
     use theme::SyntaxTheme;
+    use util::path;
 
     #[gpui::test]
     async fn test_go_label_for_completion() {
@@ -711,6 +1127,55 @@ mod tests {
             "Should find test function and subtest with backticks, found: {}",
             runnables.len()
         );
+
+        let table_test = r#"
+        package main
+
+        import "testing"
+
+        func TestExample(t *testing.T) {
+            tests := []struct {
+                name string
+                want int
+            }{
+                {name: "first case", want: 1},
+                {name: `second case`, want: 2},
+            }
+            for _, tt := range tests {
+                t.Run(tt.name, func(t *testing.T) {})
+            }
+        }
+        "#;
+
+        let buffer =
+            cx.new(|cx| crate::Buffer::local(table_test, cx).with_language(language.clone(), cx));
+        cx.executor().run_until_parked();
+
+        let runnables: Vec<_> = buffer.update(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            snapshot.runnable_ranges(0..table_test.len()).collect()
+        });
+
+        assert!(
+            runnables.len() == 3,
+            "Should find test function and both table test cases, found: {}",
+            runnables.len()
+        );
+        assert_eq!(
+            runnables
+                .iter()
+                .filter(|runnable| {
+                    runnable
+                        .runnable
+                        .tags
+                        .iter()
+                        .any(|tag| tag.0 == "go-subtest")
+                })
+                .filter_map(|runnable| runnable.extra_captures.get("_table_test_case_name"))
+                .collect::<Vec<_>>(),
+            ["\"first case\"", "`second case`"],
+            "Table test cases should run as subtests of their test"
+        );
     }
 
     #[test]
@@ -749,4 +1214,238 @@ mod tests {
         let result = extract_subtest_name(input_with_double_quotes);
         assert_eq!(result, Some(r#"test_with_\"double_quotes\""#.to_string()));
     }
+
+    #[gpui::test]
+    fn test_go_doc_symbol_of_methods(cx: &mut TestAppContext) {
+        let language = language("go", tree_sitter_go::LANGUAGE.into());
+        let text = r#"
+        package stack
+
+        type Stack[T any] struct{ items []T }
+
+        func (s *Stack[T]) Push(item T) {
+            s.items = append(s.items, item)
+        }
+
+        func (s Stack[T]) Len() int { return len(s.items) }
+
+        func New[T any]() *Stack[T] {
+            return &Stack[T]{}
+        }
+        "#;
+        let buffer =
+            cx.new(|cx| crate::Buffer::local(text, cx).with_language(language.clone(), cx));
+        cx.executor().run_until_parked();
+
+        let method_at = |needle: &str, cx: &mut TestAppContext| {
+            let offset = text.find(needle).unwrap();
+            cx.update(|cx| {
+                let anchor = buffer.read(cx).anchor_before(offset);
+                enclosing_method_at(
+                    &Location {
+                        buffer: buffer.clone(),
+                        range: anchor..anchor,
+                    },
+                    cx,
+                )
+            })
+        };
+        assert_eq!(
+            method_at("append", cx).as_deref(),
+            Some("Stack.Push"),
+            "pointer receivers with type parameters"
+        );
+        assert_eq!(method_at("return len", cx).as_deref(), Some("Stack.Len"));
+        assert_eq!(method_at("return &Stack", cx), None);
+    }
+
+    #[gpui::test]
+    async fn test_find_main_packages(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "go.mod": "module example.com/app",
+                "cmd": {
+                    "server": {
+                        "main.go": "// Command server serves.\npackage main\n",
+                        "main_test.go": "package main_test\n",
+                    },
+                    "migrate": {
+                        "migrate.go": "//go:build tools\n\npackage main\n",
+                    },
+                    "tool": {
+                        "tool.go": "/* Command tool. */ package main // entry point\n",
+                    },
+                    "mainly": { "mainly.go": "package mainly\n" },
+                    "worker": {
+                        "doc.go": "// Package worker is documented here.\npackage worker\n",
+                        "worker.go": "package main\n",
+                    },
+                    "shared": { "shared.go": "package shared\n" },
+                    "README.md": "",
+                },
+                "internal": {
+                    "store": { "store.go": "package store\n" },
+                },
+            }),
+        )
+        .await;
+
+        let module_root = find_go_module_root(
+            fs.as_ref(),
+            path!("/root").as_ref(),
+            Path::new("internal/store/store.go"),
+        )
+        .await;
+        assert_eq!(module_root.as_deref(), Some(Path::new(path!("/root"))));
+
+        assert_eq!(
+            find_main_packages(fs.as_ref(), Path::new(path!("/root"))).await,
+            vec![
+                "cmd/migrate".to_string(),
+                "cmd/server".to_string(),
+                "cmd/tool".to_string(),
+                "cmd/worker".to_string(),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_go_module_path() {
+        assert_eq!(
+            parse_go_module_path("module example.com/app\n\ngo 1.22\n"),
+            Some("example.com/app".to_string())
+        );
+        assert_eq!(
+            parse_go_module_path("// Service module\nmodule \"github.com/acme/service\" // v2\n"),
+            Some("github.com/acme/service".to_string())
+        );
+        assert_eq!(parse_go_module_path("modulefoo bar\ngo 1.22\n"), None);
+        assert_eq!(parse_go_module_path("go 1.22\n"), None);
+    }
+
+    #[test]
+    fn test_gopls_configuration() {
+        let defaults = gopls_configuration([]);
+        assert_eq!(defaults["hints"]["parameterNames"], json!(true));
+        assert_eq!(defaults["usePlaceholders"], json!(true));
+
+        let configuration = gopls_configuration([json!({
+            "hints": { "parameterNames": false },
+            "staticcheck": true
+        })]);
+        assert_eq!(configuration["hints"]["parameterNames"], json!(false));
+        assert_eq!(configuration["hints"]["assignVariableTypes"], json!(true));
+        assert_eq!(configuration["hints"]["rangeVariableTypes"], json!(true));
+        assert_eq!(configuration["usePlaceholders"], json!(true));
+        assert_eq!(configuration["staticcheck"], json!(true));
+
+        // Hints disabled through the initialization options stay off, and `settings` win over
+        // both them and the defaults.
+        let configuration = gopls_configuration([
+            json!({ "hints": { "parameterNames": false, "constantValues": false } }),
+            json!({ "hints": { "constantValues": true }, "gofumpt": true }),
+        ]);
+        assert_eq!(configuration["hints"]["parameterNames"], json!(false));
+        assert_eq!(configuration["hints"]["constantValues"], json!(true));
+        assert_eq!(configuration["hints"]["assignVariableTypes"], json!(true));
+        assert_eq!(configuration["gofumpt"], json!(true));
+    }
+
+    #[test]
+    fn test_go_bench_args() {
+        assert_eq!(
+            go_bench_args("BenchmarkParseConfig"),
+            [
+                "test",
+                "-bench",
+                "\\^BenchmarkParseConfig\\$",
+                "-run",
+                "\\^\\$",
+                "-benchmem"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_work_module_root() {
+        let go_work = r#"go 1.22
+
+use ./tools // linters
+
+use (
+	.
+	./services/api
+	"./services/worker"
+)
+"#;
+        assert_eq!(
+            parse_go_work_uses(go_work),
+            vec!["./tools", ".", "./services/api", "./services/worker"]
+        );
+
+        let go_work_dir = Path::new(path!("/work"));
+        assert_eq!(
+            go_work_module_root(
+                go_work_dir,
+                go_work,
+                Path::new(path!("/work/services/api/internal/handlers"))
+            ),
+            Some(PathBuf::from(path!("/work/services/api")))
+        );
+        assert_eq!(
+            go_work_module_root(
+                go_work_dir,
+                go_work,
+                Path::new(path!("/work/services/worker"))
+            ),
+            Some(PathBuf::from(path!("/work/services/worker")))
+        );
+        assert_eq!(
+            go_work_module_root(go_work_dir, go_work, Path::new(path!("/work/cmd/app"))),
+            Some(PathBuf::from(path!("/work")))
+        );
+        assert_eq!(
+            go_work_module_root(
+                go_work_dir,
+                "go 1.22\nuse ../shared\n",
+                Path::new(path!("/shared/pkg"))
+            ),
+            Some(PathBuf::from(path!("/shared")))
+        );
+        assert_eq!(
+            go_work_module_root(
+                go_work_dir,
+                "go 1.22\nuse ./services/api\n",
+                Path::new(path!("/work/services/apigateway"))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_go_import_path() {
+        let module_dir = Path::new(path!("/src/app"));
+        assert_eq!(
+            go_import_path("example.com/app", module_dir, module_dir),
+            Some("example.com/app".to_string())
+        );
+        assert_eq!(
+            go_import_path(
+                "example.com/app",
+                module_dir,
+                Path::new(path!("/src/app/internal/server"))
+            ),
+            Some("example.com/app/internal/server".to_string())
+        );
+        assert_eq!(
+            go_import_path(
+                "example.com/app",
+                module_dir,
+                Path::new(path!("/src/other"))
+            ),
+            None
+        );
+    }
 }
@@ -65,13 +65,45 @@
   (#set! tag go-subtest)
 )
 
+; Named cases of a table-driven test
+(
+  (function_declaration
+    name: (_) @_test_name
+    (#match? @_test_name "^Test")
+    body: (block
+      (short_var_declaration
+        right: (expression_list
+          (composite_literal
+            body: (literal_value
+              (literal_element
+                (literal_value
+                  (keyed_element
+                    key: (literal_element (identifier) @_field_name)
+                    value: (literal_element
+                      [
+                        (interpreted_string_literal)
+                        (raw_string_literal)
+                      ] @run @_table_test_case_name)
+                    (#eq? @_field_name "name")
+                  )
+                )
+              )
+            )
+          )
+        )
+      )
+    )
+  ) @_
+  (#set! tag go-subtest)
+)
+
 ; Functions names start with `Benchmark`
 (
   (
     (function_declaration name: (_) @run @_name
       (#match? @_name "^Benchmark.+"))
   ) @_
-  (#set! tag go-benchmark)
+  (#set! tag go-bench)
 )
 
 ; Functions names start with `Fuzz`
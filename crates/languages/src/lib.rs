@@ -23,6 +23,7 @@ mod rust;
 mod tailwind;
 mod typescript;
 mod vtsls;
+mod worktree_env;
 mod yaml;
 
 pub(crate) use package_json::{PackageJson, PackageJsonData};
@@ -88,10 +89,13 @@ pub fn init(languages: Arc<LanguageRegistry>, cx: &mut App) {
     let python_context_provider = Arc::new(python::PythonContextProvider);
     let python_lsp_adapter = Arc::new(python::PythonLspAdapter::new());
     let python_toolchain_provider = Arc::new(python::PythonToolchainProvider::default());
-    let rust_context_provider = Arc::new(rust::RustContextProvider);
+    let rust_context_provider = Arc::new(rust::RustContextProvider::new());
     let rust_lsp_adapter = Arc::new(rust::RustLspAdapter);
     let tailwind_adapter = Arc::new(tailwind::TailwindLspAdapter::new());
-    let typescript_context = Arc::new(typescript::TypeScriptContextProvider::new());
+    let typescript_context = typescript::TypeScriptContextProvider::new("TypeScript".into());
+    let tsx_context = Arc::new(typescript_context.for_language("TSX".into()));
+    let javascript_context = Arc::new(typescript_context.for_language("JavaScript".into()));
+    let typescript_context = Arc::new(typescript_context);
     let typescript_lsp_adapter = Arc::new(typescript::TypeScriptLspAdapter::new());
     let vtsls_adapter = Arc::new(vtsls::VtslsLspAdapter::new());
     let yaml_lsp_adapter = Arc::new(yaml::YamlLspAdapter::new());
@@ -177,19 +181,19 @@ pub fn init(languages: Arc<LanguageRegistry>, cx: &mut App) {
         LanguageInfo {
             name: "tsx",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
-            context: Some(typescript_context.clone()),
+            context: Some(tsx_context),
             ..Default::default()
         },
         LanguageInfo {
             name: "typescript",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
-            context: Some(typescript_context.clone()),
+            context: Some(typescript_context),
             ..Default::default()
         },
         LanguageInfo {
             name: "javascript",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
-            context: Some(typescript_context.clone()),
+            context: Some(javascript_context),
             ..Default::default()
         },
         LanguageInfo {
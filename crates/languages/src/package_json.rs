@@ -1,5 +1,5 @@
 use chrono::{DateTime, Local};
-use collections::{BTreeSet, HashMap};
+use collections::{BTreeMap, BTreeSet, HashMap};
 use serde_json_lenient::Value;
 use std::{path::Path, sync::Arc};
 
@@ -15,8 +15,17 @@ pub struct PackageJsonData {
     pub mocha_package_path: Option<Arc<Path>>,
     pub vitest_package_path: Option<Arc<Path>>,
     pub jasmine_package_path: Option<Arc<Path>>,
+    pub tsx_package_path: Option<Arc<Path>>,
+    pub ts_node_package_path: Option<Arc<Path>>,
+    pub playwright_package_path: Option<Arc<Path>>,
+    pub cypress_package_path: Option<Arc<Path>>,
     pub scripts: BTreeSet<(Arc<Path>, String)>,
+    /// The `name` of each package.json that declares one, keyed by its path.
+    pub package_names: BTreeMap<Arc<Path>, String>,
     pub package_manager: Option<&'static str>,
+    /// The version of `package_manager` pinned by the `packageManager` field, e.g. `8.6.0` for
+    /// `pnpm@8.6.0+sha512.abc`.
+    pub package_manager_version: Option<String>,
 }
 
 impl PackageJsonData {
@@ -31,10 +40,21 @@ impl PackageJsonData {
             );
         }
 
+        let mut package_names = BTreeMap::new();
+        if let Some(Value::String(name)) = package_json.get("name") {
+            if !name.is_empty() {
+                package_names.insert(path.clone(), name.clone());
+            }
+        }
+
         let mut jest_package_path = None;
         let mut mocha_package_path = None;
         let mut vitest_package_path = None;
         let mut jasmine_package_path = None;
+        let mut tsx_package_path = None;
+        let mut ts_node_package_path = None;
+        let mut playwright_package_path = None;
+        let mut cypress_package_path = None;
         if let Some(Value::Object(dependencies)) = package_json.get("devDependencies") {
             if dependencies.contains_key("jest") {
                 jest_package_path.get_or_insert_with(|| path.clone());
@@ -48,6 +68,18 @@ impl PackageJsonData {
             if dependencies.contains_key("jasmine") {
                 jasmine_package_path.get_or_insert_with(|| path.clone());
             }
+            if dependencies.contains_key("tsx") {
+                tsx_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dependencies.contains_key("ts-node") {
+                ts_node_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dependencies.contains_key("@playwright/test") {
+                playwright_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dependencies.contains_key("cypress") {
+                cypress_package_path.get_or_insert_with(|| path.clone());
+            }
         }
         if let Some(Value::Object(dev_dependencies)) = package_json.get("dependencies") {
             if dev_dependencies.contains_key("jest") {
@@ -62,30 +94,54 @@ impl PackageJsonData {
             if dev_dependencies.contains_key("jasmine") {
                 jasmine_package_path.get_or_insert_with(|| path.clone());
             }
+            if dev_dependencies.contains_key("tsx") {
+                tsx_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dev_dependencies.contains_key("ts-node") {
+                ts_node_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dev_dependencies.contains_key("@playwright/test") {
+                playwright_package_path.get_or_insert_with(|| path.clone());
+            }
+            if dev_dependencies.contains_key("cypress") {
+                cypress_package_path.get_or_insert_with(|| path.clone());
+            }
         }
 
-        let package_manager = package_json
+        let package_manager_field = package_json
             .get("packageManager")
-            .and_then(|value| value.as_str())
-            .and_then(|value| {
-                if value.starts_with("pnpm") {
-                    Some("pnpm")
-                } else if value.starts_with("yarn") {
-                    Some("yarn")
-                } else if value.starts_with("npm") {
-                    Some("npm")
-                } else {
-                    None
-                }
-            });
+            .and_then(|value| value.as_str());
+        let package_manager = package_manager_field.and_then(|value| {
+            if value.starts_with("pnpm") {
+                Some("pnpm")
+            } else if value.starts_with("yarn") {
+                Some("yarn")
+            } else if value.starts_with("npm") {
+                Some("npm")
+            } else {
+                None
+            }
+        });
+        let package_manager_version = package_manager
+            .and(package_manager_field)
+            .and_then(|value| value.split_once('@'))
+            .and_then(|(_, version)| version.split('+').next())
+            .filter(|version| !version.is_empty())
+            .map(|version| version.to_owned());
 
         Self {
             jest_package_path,
             mocha_package_path,
             vitest_package_path,
             jasmine_package_path,
+            tsx_package_path,
+            ts_node_package_path,
+            playwright_package_path,
+            cypress_package_path,
             scripts,
+            package_names,
             package_manager,
+            package_manager_version,
         }
     }
 
@@ -100,7 +156,47 @@ impl PackageJsonData {
             .jasmine_package_path
             .take()
             .or(other.jasmine_package_path);
+        self.tsx_package_path = self.tsx_package_path.take().or(other.tsx_package_path);
+        self.ts_node_package_path = self
+            .ts_node_package_path
+            .take()
+            .or(other.ts_node_package_path);
+        self.playwright_package_path = self
+            .playwright_package_path
+            .take()
+            .or(other.playwright_package_path);
+        self.cypress_package_path = self
+            .cypress_package_path
+            .take()
+            .or(other.cypress_package_path);
         self.scripts.extend(other.scripts);
-        self.package_manager = self.package_manager.or(other.package_manager);
+        self.package_names.extend(other.package_names);
+        if self.package_manager.is_none() {
+            self.package_manager = other.package_manager;
+            self.package_manager_version = other.package_manager_version;
+        }
+    }
+
+    /// The pinned version of the given package manager, if it is the one the `packageManager`
+    /// field names. Corepack makes sure that this exact version runs.
+    pub fn pinned_package_manager_version(&self, package_manager: &str) -> Option<&str> {
+        if self.package_manager? == package_manager {
+            self.package_manager_version.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// The tool used to run TypeScript files directly, along with the package.json declaring it.
+    /// `tsx` is preferred over `ts-node` when both are available.
+    pub fn typescript_file_runner(&self) -> Option<(&'static str, &Arc<Path>)> {
+        self.tsx_package_path
+            .as_ref()
+            .map(|path| ("tsx", path))
+            .or_else(|| {
+                self.ts_node_package_path
+                    .as_ref()
+                    .map(|path| ("ts-node", path))
+            })
     }
 }
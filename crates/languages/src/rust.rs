@@ -1,6 +1,6 @@
 use anyhow::{Result};
 use async_trait::async_trait;
-use collections::HashMap;
+use collections::{BTreeSet, HashMap};
 
 use gpui::{App, AppContext, AsyncApp, SharedString, Task};
 pub use language::*;
@@ -11,11 +11,16 @@ use project::project_settings::ProjectSettings;
 use regex::Regex;
 use serde_json::json;
 use settings::Settings as _;
+use smol::lock::RwLock;
 use std::fmt::Display;
 use std::{
     borrow::Cow,
     path::{Path, PathBuf},
-    sync::{Arc, LazyLock},
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, Ordering::SeqCst},
+    },
+    time::SystemTime,
 };
 use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
 use util::merge_json_value_into;
@@ -25,6 +30,7 @@ use util::{
 };
 
 use crate::language_settings::language_settings;
+use crate::worktree_env::WorktreeEnvironments;
 
 pub struct RustLspAdapter;
 
@@ -93,27 +99,30 @@ impl LspAdapter for RustLspAdapter {
         &self,
         delegate: &dyn LspAdapterDelegate,
         _: Arc<dyn LanguageToolchainStore>,
-        _: &AsyncApp,
+        cx: &AsyncApp,
     ) -> Option<LanguageServerBinary> {
+        static DID_SHOW_NOTIFICATION: AtomicBool = AtomicBool::new(false);
+
         let path = delegate.which("rust-analyzer".as_ref()).await?;
         let env = delegate.shell_env().await;
 
         // It is surprisingly common for ~/.cargo/bin/rust-analyzer to be a symlink to
         // /usr/bin/rust-analyzer that fails when you run it; so we need to test it.
         log::info!("found rust-analyzer in PATH. trying to run `rust-analyzer --help`");
-        let result = delegate
-            .try_exec(LanguageServerBinary {
-                path: path.clone(),
-                arguments: vec!["--help".into()],
-                env: Some(env.clone()),
-            })
-            .await;
-        if let Err(err) = result {
-            log::debug!(
-                "failed to run rust-analyzer after detecting it in PATH: binary: {:?}: {}",
-                path,
-                err
-            );
+        if !can_run_rust_analyzer(delegate, &path, &env).await {
+            if DID_SHOW_NOTIFICATION
+                .compare_exchange(false, true, SeqCst, SeqCst)
+                .is_ok()
+            {
+                let message = format!(
+                    "Found rust-analyzer at {} in PATH, but it failed to run. \
+                    It may be a broken symlink or a rustup proxy without the rust-analyzer \
+                    component installed.",
+                    path.display()
+                );
+                cx.update(|cx| delegate.show_notification(&message, cx))
+                    .ok();
+            }
             return None;
         }
 
@@ -124,6 +133,25 @@ impl LspAdapter for RustLspAdapter {
         })
     }
 
+    async fn check_configured_binary(
+        &self,
+        binary: &LanguageServerBinary,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> bool {
+        // A worktree may pin the rust-analyzer of a specific toolchain, which is just as likely to
+        // be a broken rustup proxy as the one in PATH.
+        log::info!(
+            "trying to run configured rust-analyzer at {:?} with `--help`",
+            binary.path
+        );
+        can_run_rust_analyzer(
+            delegate,
+            &binary.path,
+            binary.env.as_ref().unwrap_or(&HashMap::default()),
+        )
+        .await
+    }
+
     fn disk_based_diagnostic_sources(&self) -> Vec<String> {
         vec![CARGO_DIAGNOSTICS_SOURCE_NAME.to_owned()]
     }
@@ -417,7 +445,44 @@ impl LspAdapter for RustLspAdapter {
     }
 }
 
-pub(crate) struct RustContextProvider;
+async fn can_run_rust_analyzer(
+    delegate: &dyn LspAdapterDelegate,
+    path: &Path,
+    env: &HashMap<String, String>,
+) -> bool {
+    let result = delegate
+        .try_exec(LanguageServerBinary {
+            path: path.to_path_buf(),
+            arguments: vec!["--help".into()],
+            env: Some(env.clone()),
+        })
+        .await;
+    if let Err(err) = result {
+        log::debug!("failed to run rust-analyzer: binary: {:?}: {}", path, err);
+        return false;
+    }
+    true
+}
+
+pub(crate) struct RustContextProvider {
+    cargo_metadata: CargoMetadataCache,
+    changed_files: ChangedFilesCache,
+    /// Whether `rust-script` could be run, for each `PATH` of the project environments it was
+    /// looked up in.
+    rust_script_installed: Arc<RwLock<HashMap<Option<String>, bool>>>,
+    worktree_envs: WorktreeEnvironments,
+}
+
+impl RustContextProvider {
+    pub fn new() -> Self {
+        Self {
+            cargo_metadata: CargoMetadataCache::default(),
+            changed_files: ChangedFilesCache::default(),
+            rust_script_installed: Arc::default(),
+            worktree_envs: WorktreeEnvironments::default(),
+        }
+    }
+}
 
 const RUST_PACKAGE_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_PACKAGE"));
@@ -444,12 +509,45 @@ const RUST_TEST_FRAGMENT_TASK_VARIABLE: VariableName =
 const RUST_DOC_TEST_NAME_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_DOC_TEST_NAME"));
 
+/// The line of the opening fence of the doc comment code block under the cursor, which rustdoc
+/// uses to tell apart the doctests of an item.
+const RUST_DOC_TEST_LINE_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_DOC_TEST_LINE"));
+
+/// The path of the item whose doc examples to run, e.g. `parser::Token`, or of the module when
+/// the cursor isn't on a documented item.
+const RUST_DOC_EXAMPLE_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_DOC_EXAMPLE_NAME"));
+
 const RUST_TEST_NAME_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_TEST_NAME"));
 
+/// The full path of the test under the cursor, as libtest names it, e.g. `parser::tests::empty`.
+const RUST_TEST_PATH_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_TEST_PATH"));
+
+const RUST_BENCH_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_BENCH_NAME"));
+
+/// The `rust-script` command, set for files outside of any cargo package when it is installed.
+const RUST_SCRIPT_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("RUST_SCRIPT"));
+
+/// The target triple cargo builds for, from `CARGO_BUILD_TARGET` or `build.target` in
+/// `.cargo/config.toml`. Empty when building for the host.
+const RUST_TARGET_TRIPLE_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_TARGET_TRIPLE"));
+
+/// `--target` when a target triple is configured, empty otherwise.
+const RUST_TARGET_FLAG_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_TARGET_FLAG"));
+
 const RUST_MANIFEST_DIRNAME_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_MANIFEST_DIRNAME"));
 
+/// The `-p <package>` flags for every package with files changed since `HEAD`
+const RUST_CHANGED_PACKAGES_FLAGS_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_CHANGED_PACKAGES_FLAGS"));
+
 impl ContextProvider for RustContextProvider {
     fn build_context(
         &self,
@@ -473,17 +571,69 @@ impl ContextProvider for RustContextProvider {
             let fragment = test_fragment(&variables, &path, stem);
             variables.insert(RUST_TEST_FRAGMENT_TASK_VARIABLE, fragment);
         };
+        let module_path = location
+            .file_location
+            .buffer
+            .read(cx)
+            .file()
+            .and_then(|file| rust_module_path(file.path()));
         if let Some(test_name) =
             task_variables.get(&VariableName::Custom(Cow::Borrowed("_test_name")))
         {
             variables.insert(RUST_TEST_NAME_TASK_VARIABLE, test_name.into());
+            let enclosing_modules = enclosing_modules_at(location.file_location, cx);
+            variables.insert(
+                RUST_TEST_PATH_TASK_VARIABLE,
+                test_path(module_path.as_deref(), &enclosing_modules, test_name),
+            );
         }
-        if let Some(doc_test_name) =
-            task_variables.get(&VariableName::Custom(Cow::Borrowed("_doc_test_name")))
-        {
+        let doc_test_name =
+            task_variables.get(&VariableName::Custom(Cow::Borrowed("_doc_test_name")));
+        if let Some(doc_test_name) = doc_test_name {
             variables.insert(RUST_DOC_TEST_NAME_TASK_VARIABLE, doc_test_name.into());
         }
+        let doc_example_name = match (module_path, doc_test_name) {
+            (Some(module_path), Some(name)) => Some(format!("{module_path}::{name}")),
+            (None, Some(name)) => Some(name.to_owned()),
+            (Some(module_path), None) => Some(module_path),
+            (None, None) => None,
+        };
+        if let Some(doc_example_name) = doc_example_name {
+            variables.insert(RUST_DOC_EXAMPLE_NAME_TASK_VARIABLE, doc_example_name);
+        }
+        if let Some(doc_test_line) = doc_test_line_at(location.file_location, cx) {
+            variables.insert(RUST_DOC_TEST_LINE_TASK_VARIABLE, doc_test_line.to_string());
+        }
+        if let Some(bench_name) =
+            task_variables.get(&VariableName::Custom(Cow::Borrowed("_bench_name")))
+        {
+            variables.insert(RUST_BENCH_NAME_TASK_VARIABLE, bench_name.into());
+        }
+        let cargo_metadata_cache = self.cargo_metadata.clone();
+        let changed_files_cache = self.changed_files.clone();
+        let rust_script_installed = self.rust_script_installed.clone();
+        let worktree_scan_id =
+            project::File::from_dyn(location.file_location.buffer.read(cx).file())
+                .map(|file| file.worktree.read(cx).scan_id());
         cx.background_spawn(async move {
+            // Always set, so that the tasks they're spliced into don't get hidden for host builds.
+            let target_triple = match local_abs_path.as_deref().and_then(Path::parent) {
+                Some(directory) => cargo_build_target(directory, project_env.as_ref()).await,
+                None => None,
+            };
+            variables.insert(
+                RUST_TARGET_FLAG_TASK_VARIABLE,
+                if target_triple.is_some() {
+                    "--target".into()
+                } else {
+                    String::new()
+                },
+            );
+            variables.insert(
+                RUST_TARGET_TRIPLE_TASK_VARIABLE,
+                target_triple.unwrap_or_default(),
+            );
+
             if let Some(path) = local_abs_path
                 .as_deref()
                 .and_then(|local_abs_path| local_abs_path.parent())
@@ -495,36 +645,60 @@ impl ContextProvider for RustContextProvider {
                 }
             }
             if let Some(path) = local_abs_path.as_ref()
-                && let Some((target, manifest_path)) =
-                    target_info_from_abs_path(&path, project_env.as_ref()).await
+                && let Some(metadata) = cargo_metadata_cache.get(&path, project_env.as_ref()).await
             {
-                if let Some(target) = target {
-                    variables.extend(TaskVariables::from_iter([
-                        (RUST_PACKAGE_TASK_VARIABLE.clone(), target.package_name),
-                        (RUST_BIN_NAME_TASK_VARIABLE.clone(), target.target_name),
-                        (
-                            RUST_BIN_KIND_TASK_VARIABLE.clone(),
-                            target.target_kind.to_string(),
-                        ),
-                    ]));
-                    if target.required_features.is_empty() {
-                        variables.insert(RUST_BIN_REQUIRED_FEATURES_FLAG_TASK_VARIABLE, "".into());
-                        variables.insert(RUST_BIN_REQUIRED_FEATURES_TASK_VARIABLE, "".into());
-                    } else {
-                        variables.insert(
-                            RUST_BIN_REQUIRED_FEATURES_FLAG_TASK_VARIABLE.clone(),
-                            "--features".to_string(),
-                        );
-                        variables.insert(
-                            RUST_BIN_REQUIRED_FEATURES_TASK_VARIABLE.clone(),
-                            target.required_features.join(","),
-                        );
+                let changed_packages = changed_files_cache
+                    .get(&path, worktree_scan_id, project_env.as_ref())
+                    .await
+                    .map(|changed_files| packages_for_files(&metadata, &changed_files))
+                    .unwrap_or_default();
+                if !changed_packages.is_empty() {
+                    variables.insert(
+                        RUST_CHANGED_PACKAGES_FLAGS_TASK_VARIABLE,
+                        changed_packages
+                            .iter()
+                            .map(|package| format!("-p {package}"))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    );
+                }
+
+                if let Some((target, manifest_path)) = target_info_from_metadata(metadata, &path) {
+                    if let Some(target) = target {
+                        variables.extend(TaskVariables::from_iter([
+                            (RUST_PACKAGE_TASK_VARIABLE.clone(), target.package_name),
+                            (RUST_BIN_NAME_TASK_VARIABLE.clone(), target.target_name),
+                            (
+                                RUST_BIN_KIND_TASK_VARIABLE.clone(),
+                                target.target_kind.to_string(),
+                            ),
+                        ]));
+                        if target.required_features.is_empty() {
+                            variables
+                                .insert(RUST_BIN_REQUIRED_FEATURES_FLAG_TASK_VARIABLE, "".into());
+                            variables.insert(RUST_BIN_REQUIRED_FEATURES_TASK_VARIABLE, "".into());
+                        } else {
+                            variables.insert(
+                                RUST_BIN_REQUIRED_FEATURES_FLAG_TASK_VARIABLE.clone(),
+                                "--features".to_string(),
+                            );
+                            variables.insert(
+                                RUST_BIN_REQUIRED_FEATURES_TASK_VARIABLE.clone(),
+                                target.required_features.join(","),
+                            );
+                        }
                     }
+                    variables.extend(TaskVariables::from_iter([(
+                        RUST_MANIFEST_DIRNAME_TASK_VARIABLE.clone(),
+                        manifest_path.to_string_lossy().into_owned(),
+                    )]));
                 }
-                variables.extend(TaskVariables::from_iter([(
-                    RUST_MANIFEST_DIRNAME_TASK_VARIABLE.clone(),
-                    manifest_path.to_string_lossy().into_owned(),
-                )]));
+            }
+            if let Some(path) = local_abs_path.as_deref()
+                && !is_in_cargo_package(path).await
+                && is_rust_script_installed(&rust_script_installed, project_env.as_ref()).await
+            {
+                variables.insert(RUST_SCRIPT_TASK_VARIABLE, "rust-script".into());
             }
             Ok(variables)
         })
@@ -532,12 +706,13 @@ impl ContextProvider for RustContextProvider {
 
     fn associated_tasks(
         &self,
-        _: Arc<dyn Fs>,
+        fs: Arc<dyn Fs>,
         file: Option<Arc<dyn language::File>>,
         cx: &App,
     ) -> Task<Option<TaskTemplates>> {
         const DEFAULT_RUN_NAME_STR: &str = "RUST_DEFAULT_PACKAGE_RUN";
         const CUSTOM_TARGET_DIR: &str = "RUST_TARGET_DIR";
+        const TOOLCHAIN: &str = "RUST_TOOLCHAIN";
 
         let language_sets = language_settings(Some("Rust".into()), file.as_ref(), cx);
         let package_to_run = language_sets
@@ -550,11 +725,12 @@ impl ContextProvider for RustContextProvider {
             .variables
             .get(CUSTOM_TARGET_DIR)
             .cloned();
-        let run_task_args = if let Some(package_to_run) = package_to_run.clone() {
-            vec!["run".into(), "-p".into(), package_to_run]
-        } else {
-            vec!["run".into()]
-        };
+        let toolchain = language_sets
+            .tasks
+            .variables
+            .get(TOOLCHAIN)
+            .map(|toolchain| toolchain.trim().trim_start_matches('+').to_owned())
+            .filter(|toolchain| !toolchain.is_empty());
         let mut task_templates = vec![
             TaskTemplate {
                 label: format!(
@@ -671,39 +847,314 @@ impl ContextProvider for RustContextProvider {
                 cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
                 ..TaskTemplate::default()
             },
+        ];
+        // Appended after the baseline templates, so that the order of those, and which one comes
+        // first, doesn't change.
+        let additional_task_templates = vec![
             TaskTemplate {
-                label: "Run".into(),
+                label: format!(
+                    "Test '{}' exactly (package: {})",
+                    RUST_TEST_PATH_TASK_VARIABLE.template_value(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                ),
                 command: "cargo".into(),
-                args: run_task_args,
-                cwd: Some("$ZED_DIRNAME".to_owned()),
+                args: vec![
+                    "test".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    "--".into(),
+                    "--nocapture".into(),
+                    "--include-ignored".into(),
+                    "--exact".into(),
+                    RUST_TEST_PATH_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-single-test".to_owned()],
+                cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
                 ..TaskTemplate::default()
             },
             TaskTemplate {
-                label: "Clean".into(),
+                label: format!(
+                    "Doc test '{}' at line {} (package: {})",
+                    RUST_DOC_TEST_NAME_TASK_VARIABLE.template_value(),
+                    RUST_DOC_TEST_LINE_TASK_VARIABLE.template_value(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                ),
                 command: "cargo".into(),
-                args: vec!["clean".into()],
-                cwd: Some("$ZED_DIRNAME".to_owned()),
+                args: vec![
+                    "test".into(),
+                    "--doc".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    "--".into(),
+                    "--nocapture".into(),
+                    "--include-ignored".into(),
+                    // Quoted, as tasks run through a shell and the filter has parentheses.
+                    format!(
+                        "'{} (line {})'",
+                        RUST_DOC_TEST_NAME_TASK_VARIABLE.template_value(),
+                        RUST_DOC_TEST_LINE_TASK_VARIABLE.template_value(),
+                    ),
+                ],
+                tags: vec!["rust-doc-test".to_owned()],
+                cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "Run doc example '{}' (package: {})",
+                    RUST_DOC_EXAMPLE_NAME_TASK_VARIABLE.template_value(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "test".into(),
+                    "--doc".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    "--".into(),
+                    "--nocapture".into(),
+                    "--include-ignored".into(),
+                    RUST_DOC_EXAMPLE_NAME_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-doc-example".to_owned()],
+                cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "Bench '{}' (package: {})",
+                    RUST_BENCH_NAME_TASK_VARIABLE.template_value(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "bench".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    "--".into(),
+                    RUST_BENCH_NAME_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-bench".to_owned()],
+                cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "Test changed (since HEAD)".into(),
+                command: "cargo".into(),
+                args: vec![
+                    "test".into(),
+                    RUST_CHANGED_PACKAGES_FLAGS_TASK_VARIABLE.template_value(),
+                ],
+                cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
                 ..TaskTemplate::default()
             },
         ];
+        let clean_task_template = TaskTemplate {
+            label: "Clean".into(),
+            command: "cargo".into(),
+            args: vec!["clean".into()],
+            cwd: Some("$ZED_DIRNAME".to_owned()),
+            ..TaskTemplate::default()
+        };
 
-        if let Some(custom_target_dir) = custom_target_dir {
-            task_templates = task_templates
-                .into_iter()
-                .map(|mut task_template| {
-                    let mut args = task_template.args.split_off(1);
-                    task_template.args.append(&mut vec![
-                        "--target-dir".to_string(),
-                        custom_target_dir.clone(),
-                    ]);
-                    task_template.args.append(&mut args);
-
-                    task_template
-                })
-                .collect();
-        }
+        let local_abs_path = file
+            .as_ref()
+            .and_then(|file| Some(file.as_local()?.abs_path(cx)));
+        let project_env = project::File::from_dyn(file.as_ref())
+            .filter(|_| local_abs_path.is_some())
+            .map(|file| self.worktree_envs.get(fs, &file.worktree, cx));
+        let cargo_metadata_cache = self.cargo_metadata.clone();
+        cx.background_spawn(async move {
+            let metadata = match &local_abs_path {
+                Some(abs_path) => {
+                    // Run in the same environment as the tasks, so that e.g. `RUSTUP_TOOLCHAIN`
+                    // and `CARGO_HOME` overrides apply.
+                    let project_env = match project_env {
+                        Some(project_env) => Some(project_env.await),
+                        None => None,
+                    };
+                    cargo_metadata_cache
+                        .get(abs_path, project_env.as_ref())
+                        .await
+                }
+                None => None,
+            };
+            let run_task_args = match (package_to_run, &local_abs_path, &metadata) {
+                (Some(package_to_run), _, _) => {
+                    Some(vec!["run".into(), "-p".into(), package_to_run])
+                }
+                (None, Some(abs_path), Some(metadata)) => default_run_args(metadata, abs_path),
+                (None, _, _) => Some(vec!["run".into()]),
+            };
+            if let Some(run_task_args) = run_task_args {
+                task_templates.push(TaskTemplate {
+                    label: "Run".into(),
+                    command: "cargo".into(),
+                    args: run_task_args,
+                    cwd: Some("$ZED_DIRNAME".to_owned()),
+                    ..TaskTemplate::default()
+                });
+            }
+            task_templates.push(clean_task_template);
+            task_templates.extend(additional_task_templates);
+            if let Some(abs_path) = &local_abs_path
+                && let Some(metadata) = &metadata
+                && let Some((package_name, binaries)) = package_binaries(metadata, abs_path)
+            {
+                task_templates.extend(binaries.into_iter().map(|binary| {
+                    let mut args = vec![
+                        "run".into(),
+                        "-p".into(),
+                        package_name.clone(),
+                        "--bin".into(),
+                        binary.name.clone(),
+                    ];
+                    if !binary.required_features.is_empty() {
+                        args.push("--features".into());
+                        args.push(binary.required_features.join(","));
+                    }
+                    TaskTemplate {
+                        label: format!("Run bin {} (package: {package_name})", binary.name),
+                        command: "cargo".into(),
+                        args,
+                        cwd: Some("$ZED_DIRNAME".to_owned()),
+                        ..TaskTemplate::default()
+                    }
+                }));
+            }
+
+            if let Some(metadata) = &metadata {
+                for (member, has_binaries) in workspace_members(metadata) {
+                    task_templates.push(TaskTemplate {
+                        label: format!("Check member '{member}'"),
+                        command: "cargo".into(),
+                        args: vec!["check".into(), "-p".into(), member.clone()],
+                        cwd: Some("$ZED_DIRNAME".to_owned()),
+                        ..TaskTemplate::default()
+                    });
+                    task_templates.push(TaskTemplate {
+                        label: format!("Test member '{member}'"),
+                        command: "cargo".into(),
+                        args: vec!["test".into(), "-p".into(), member.clone()],
+                        cwd: Some("$ZED_DIRNAME".to_owned()),
+                        ..TaskTemplate::default()
+                    });
+                    if has_binaries {
+                        task_templates.push(TaskTemplate {
+                            label: format!("Run member '{member}'"),
+                            command: "cargo".into(),
+                            args: vec!["run".into(), "-p".into(), member],
+                            cwd: Some("$ZED_DIRNAME".to_owned()),
+                            ..TaskTemplate::default()
+                        });
+                    }
+                }
+            }
+
+            if let Some(custom_target_dir) = custom_target_dir {
+                task_templates = task_templates
+                    .into_iter()
+                    .map(|mut task_template| {
+                        let mut args = task_template.args.split_off(1);
+                        task_template.args.append(&mut vec![
+                            "--target-dir".to_string(),
+                            custom_target_dir.clone(),
+                        ]);
+                        task_template.args.append(&mut args);
+
+                        task_template
+                    })
+                    .collect();
+            }
+
+            // Both variables are empty when building for the host, which leaves these untouched.
+            for task_template in &mut task_templates {
+                if task_template.command == "cargo"
+                    && matches!(
+                        task_template.args.first().map(String::as_str),
+                        Some("check" | "test" | "run")
+                    )
+                {
+                    task_template.args.splice(
+                        1..1,
+                        [
+                            RUST_TARGET_FLAG_TASK_VARIABLE.template_value(),
+                            RUST_TARGET_TRIPLE_TASK_VARIABLE.template_value(),
+                        ],
+                    );
+                }
+            }
+
+            if let Some(abs_path) = &local_abs_path
+                && let Some(metadata) = &metadata
+                && package_uses_insta(metadata, abs_path)
+            {
+                let mut insta_test_args = vec!["insta".into(), "test".into()];
+                if let Some(custom_target_dir) = &custom_target_dir {
+                    insta_test_args.push("--target-dir".into());
+                    insta_test_args.push(custom_target_dir.clone());
+                }
+                insta_test_args.push("-p".into());
+                insta_test_args.push(RUST_PACKAGE_TASK_VARIABLE.template_value());
+                task_templates.push(TaskTemplate {
+                    label: format!(
+                        "Insta test (package: {})",
+                        RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    ),
+                    command: "cargo".into(),
+                    args: insta_test_args,
+                    cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
+                    ..TaskTemplate::default()
+                });
+                task_templates.push(TaskTemplate {
+                    label: "Insta review".into(),
+                    command: "cargo".into(),
+                    args: vec!["insta".into(), "review".into()],
+                    cwd: Some(RUST_MANIFEST_DIRNAME_TASK_VARIABLE.template_value()),
+                    ..TaskTemplate::default()
+                });
+            }
+
+            // Neither `cargo fmt` nor rust-script accept `--target-dir`, so these go after it has
+            // been added.
+            task_templates.push(TaskTemplate {
+                label: "Format all (workspace)".into(),
+                command: "cargo".into(),
+                args: vec!["fmt".into(), "--all".into()],
+                cwd: Some("$ZED_DIRNAME".to_owned()),
+                ..TaskTemplate::default()
+            });
+            task_templates.push(TaskTemplate {
+                label: "Check formatting (workspace)".into(),
+                command: "cargo".into(),
+                args: vec!["fmt".into(), "--all".into(), "--check".into()],
+                cwd: Some("$ZED_DIRNAME".to_owned()),
+                ..TaskTemplate::default()
+            });
+            task_templates.push(TaskTemplate {
+                label: "Run as rust-script".into(),
+                command: RUST_SCRIPT_TASK_VARIABLE.template_value(),
+                args: vec![VariableName::File.template_value()],
+                cwd: Some("$ZED_DIRNAME".to_owned()),
+                ..TaskTemplate::default()
+            });
+
+            if let Some(toolchain) = &toolchain {
+                apply_cargo_toolchain(&mut task_templates, toolchain);
+                // Unstable rustfmt options are the most common reason to configure nightly.
+                if toolchain.starts_with("nightly") {
+                    task_templates.push(TaskTemplate {
+                        label: format!("Format all with {toolchain} rustfmt (workspace)"),
+                        command: "cargo".into(),
+                        args: vec![format!("+{toolchain}"), "fmt".into(), "--all".into()],
+                        cwd: Some("$ZED_DIRNAME".to_owned()),
+                        ..TaskTemplate::default()
+                    });
+                }
+            }
 
-        Task::ready(Some(TaskTemplates(task_templates)))
+            Some(TaskTemplates(task_templates))
+        })
     }
 
     fn lsp_task_source(&self) -> Option<LanguageServerName> {
@@ -711,20 +1162,177 @@ impl ContextProvider for RustContextProvider {
     }
 }
 
+/// Resolves the target triple cargo builds for from `directory`: `CARGO_BUILD_TARGET` takes
+/// precedence, followed by the `build.target` of the closest `.cargo/config.toml`.
+async fn cargo_build_target(
+    directory: &Path,
+    project_env: Option<&HashMap<String, String>>,
+) -> Option<String> {
+    let from_env = project_env
+        .and_then(|env| env.get("CARGO_BUILD_TARGET").cloned())
+        .or_else(|| std::env::var("CARGO_BUILD_TARGET").ok())
+        .filter(|target| !target.trim().is_empty());
+    if from_env.is_some() {
+        return from_env;
+    }
+    for ancestor in directory.ancestors() {
+        for file_name in ["config.toml", "config"] {
+            let Ok(contents) =
+                smol::fs::read_to_string(ancestor.join(".cargo").join(file_name)).await
+            else {
+                continue;
+            };
+            if let Some(target) = build_target_from_config(&contents) {
+                return Some(target);
+            }
+        }
+    }
+    None
+}
+
+/// Reads `build.target` from the contents of a cargo config file. Several targets can't be
+/// passed as a single `--target`, so those are ignored.
+fn build_target_from_config(contents: &str) -> Option<String> {
+    let config = toml::from_str::<toml::Table>(contents).log_err()?;
+    match config.get("build")?.get("target")? {
+        toml::Value::String(target) => Some(target.clone()),
+        toml::Value::Array(targets) if targets.len() == 1 => {
+            targets[0].as_str().map(ToOwned::to_owned)
+        }
+        _ => None,
+    }
+    .filter(|target| !target.is_empty())
+}
+
+/// Makes every cargo invocation among `task_templates` use the given rustup toolchain, as in
+/// `cargo +nightly fmt`.
+fn apply_cargo_toolchain(task_templates: &mut [TaskTemplate], toolchain: &str) {
+    for task_template in task_templates {
+        if task_template.command == "cargo" {
+            task_template.args.insert(0, format!("+{toolchain}"));
+        }
+    }
+}
+
+/// Names of the inline `mod` items around the location, outermost first.
+fn enclosing_modules_at(location: &Location, cx: &App) -> Vec<String> {
+    let buffer = location.buffer.read(cx);
+    let offset = location.range.start.to_offset(buffer);
+    let Some(layer) = buffer.syntax_layer_at(offset) else {
+        return Vec::new();
+    };
+    let mut modules = Vec::new();
+    let mut node = layer.node().descendant_for_byte_range(offset, offset);
+    while let Some(current) = node {
+        if current.kind() == "mod_item"
+            && let Some(name) = current.child_by_field_name("name")
+        {
+            modules.push(buffer.text_for_range(name.byte_range()).collect::<String>());
+        }
+        node = current.parent();
+    }
+    modules.reverse();
+    modules
+}
+
+/// Joins the module path of a file, the modules enclosing a test in it and the test's name into
+/// the name libtest gives the test, which `--exact` has to be given in full.
+fn test_path(module_path: Option<&str>, enclosing_modules: &[String], test_name: &str) -> String {
+    module_path
+        .into_iter()
+        .chain(enclosing_modules.iter().map(String::as_str))
+        .chain([test_name])
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Returns the 1-based line of the opening fence of the doc comment code block containing the
+/// start of `location`, if that block is a Rust doctest.
+fn doc_test_line_at(location: &Location, cx: &App) -> Option<u32> {
+    let buffer = location.buffer.read(cx);
+    let cursor_row = location.range.start.to_point(buffer).row;
+    let doc_comment_row = |row: u32| {
+        let line = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        let line = line.trim_start();
+        line.strip_prefix("///")
+            .or_else(|| line.strip_prefix("//!"))
+            .map(ToOwned::to_owned)
+    };
+    doc_comment_row(cursor_row)?;
+
+    let mut first_row = cursor_row;
+    while first_row > 0 && doc_comment_row(first_row - 1).is_some() {
+        first_row -= 1;
+    }
+    let doc_lines = (first_row..=cursor_row)
+        .filter_map(doc_comment_row)
+        .collect::<Vec<_>>();
+    let fence_index = doc_test_fence_index(&doc_lines)?;
+    Some(first_row + fence_index as u32 + 1)
+}
+
+/// Given the doc comment lines up to and including the cursor's one, returns the index of the
+/// opening fence of the code block the cursor is in, provided rustdoc would run it as a test.
+fn doc_test_fence_index(doc_lines: &[String]) -> Option<usize> {
+    let mut open_fence = None;
+    for (ix, line) in doc_lines.iter().enumerate() {
+        let Some(info) = line.trim().strip_prefix("```") else {
+            continue;
+        };
+        let is_cursor_line = ix + 1 == doc_lines.len();
+        match open_fence {
+            Some(_) if !is_cursor_line => open_fence = None,
+            Some(_) => {}
+            None => open_fence = Some((ix, is_rust_code_block(info))),
+        }
+    }
+    let (fence_index, is_rust) = open_fence?;
+    is_rust.then_some(fence_index)
+}
+
+/// Whether a code block with the given info string is Rust code, which rustdoc compiles and runs.
+fn is_rust_code_block(info: &str) -> bool {
+    info.split([',', ' ', '\t']).all(|attribute| {
+        matches!(
+            attribute,
+            "" | "rust"
+                | "ignore"
+                | "should_panic"
+                | "no_run"
+                | "compile_fail"
+                | "test_harness"
+                | "standalone_crate"
+        ) || attribute.starts_with("edition")
+            || attribute.starts_with("ignore-")
+    })
+}
+
 /// Part of the data structure of Cargo metadata
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Deserialize)]
 struct CargoMetadata {
     packages: Vec<CargoPackage>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Deserialize)]
 struct CargoPackage {
     id: String,
     targets: Vec<CargoTarget>,
     manifest_path: Arc<Path>,
+    #[serde(default)]
+    dependencies: Vec<CargoDependency>,
+    #[serde(default)]
+    default_run: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct CargoDependency {
+    name: String,
+    kind: Option<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Deserialize)]
 struct CargoTarget {
     name: String,
     kind: Vec<String>,
@@ -767,10 +1375,76 @@ struct TargetInfo {
     required_features: Vec<String>,
 }
 
-async fn target_info_from_abs_path(
+/// `cargo metadata` output, cached per manifest until one of the `Cargo.toml` files it may
+/// depend on changes.
+#[derive(Clone, Debug, Default)]
+struct CargoMetadataCache(Arc<RwLock<HashMap<PathBuf, CachedCargoMetadata>>>);
+
+#[derive(Debug)]
+struct CachedCargoMetadata {
+    /// Modification times of the closest manifest and of the ones above it, any of which may be
+    /// the root of the workspace.
+    manifest_mtimes: Vec<(PathBuf, SystemTime)>,
+    metadata: CargoMetadata,
+}
+
+impl CargoMetadataCache {
+    async fn get(
+        &self,
+        abs_path: &Path,
+        project_env: Option<&HashMap<String, String>>,
+    ) -> Option<CargoMetadata> {
+        self.get_or_load(abs_path, cargo_metadata(abs_path, project_env))
+            .await
+    }
+
+    async fn get_or_load(
+        &self,
+        abs_path: &Path,
+        load: impl Future<Output = Option<CargoMetadata>>,
+    ) -> Option<CargoMetadata> {
+        let manifest_mtimes = manifest_mtimes(abs_path).await;
+        let Some((manifest_path, _)) = manifest_mtimes.first() else {
+            return load.await;
+        };
+        if let Some(cached) = self.0.read().await.get(manifest_path)
+            && cached.manifest_mtimes == manifest_mtimes
+        {
+            return Some(cached.metadata.clone());
+        }
+
+        let metadata = load.await?;
+        self.0.write().await.insert(
+            manifest_path.clone(),
+            CachedCargoMetadata {
+                manifest_mtimes,
+                metadata: metadata.clone(),
+            },
+        );
+        Some(metadata)
+    }
+}
+
+/// Paths and modification times of the `Cargo.toml` files in the ancestors of `abs_path`,
+/// closest first.
+async fn manifest_mtimes(abs_path: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let mut mtimes = Vec::new();
+    for directory in abs_path.ancestors().skip(1) {
+        let manifest_path = directory.join("Cargo.toml");
+        if let Ok(mtime) = smol::fs::metadata(&manifest_path)
+            .await
+            .and_then(|metadata| metadata.modified())
+        {
+            mtimes.push((manifest_path, mtime));
+        }
+    }
+    mtimes
+}
+
+async fn cargo_metadata(
     abs_path: &Path,
     project_env: Option<&HashMap<String, String>>,
-) -> Option<(Option<TargetInfo>, Arc<Path>)> {
+) -> Option<CargoMetadata> {
     let mut command = util::command::new_smol_command("cargo");
     if let Some(envs) = project_env {
         command.envs(envs);
@@ -786,8 +1460,154 @@ async fn target_info_from_abs_path(
         .log_err()?
         .stdout;
 
-    let metadata: CargoMetadata = serde_json::from_slice(&output).log_err()?;
-    target_info_from_metadata(metadata, abs_path)
+    serde_json::from_slice(&output).log_err()
+}
+
+/// Whether any ancestor directory of `abs_path` has a `Cargo.toml`.
+async fn is_in_cargo_package(abs_path: &Path) -> bool {
+    for directory in abs_path.ancestors().skip(1) {
+        if smol::fs::metadata(directory.join("Cargo.toml"))
+            .await
+            .is_ok_and(|metadata| metadata.is_file())
+        {
+            return true;
+        }
+    }
+    false
+}
+
+async fn is_rust_script_installed(
+    cache: &RwLock<HashMap<Option<String>, bool>>,
+    project_env: Option<&HashMap<String, String>>,
+) -> bool {
+    let path = project_env.and_then(|envs| envs.get("PATH").cloned());
+    if let Some(installed) = cache.read().await.get(&path) {
+        return *installed;
+    }
+
+    let mut command = util::command::new_smol_command("rust-script");
+    if let Some(envs) = project_env {
+        command.envs(envs);
+    }
+    let installed = command
+        .arg("--version")
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success());
+    cache.write().await.insert(path, installed);
+    installed
+}
+
+/// The files changed since `HEAD`, cached per git repository until the worktree they were listed
+/// for is scanned again, as it is after any edit, checkout or commit.
+#[derive(Clone, Debug, Default)]
+struct ChangedFilesCache(Arc<RwLock<HashMap<PathBuf, CachedChangedFiles>>>);
+
+#[derive(Debug)]
+struct CachedChangedFiles {
+    worktree_scan_id: usize,
+    changed_files: Vec<PathBuf>,
+}
+
+impl ChangedFilesCache {
+    async fn get(
+        &self,
+        abs_path: &Path,
+        worktree_scan_id: Option<usize>,
+        project_env: Option<&HashMap<String, String>>,
+    ) -> Option<Vec<PathBuf>> {
+        let repository_root = repository_root(abs_path).await?;
+        let load = changed_files_since_head(&repository_root, project_env);
+        match worktree_scan_id {
+            Some(worktree_scan_id) => {
+                self.get_or_load(repository_root.clone(), worktree_scan_id, load)
+                    .await
+            }
+            None => load.await,
+        }
+    }
+
+    async fn get_or_load(
+        &self,
+        repository_root: PathBuf,
+        worktree_scan_id: usize,
+        load: impl Future<Output = Option<Vec<PathBuf>>>,
+    ) -> Option<Vec<PathBuf>> {
+        if let Some(cached) = self.0.read().await.get(&repository_root)
+            && cached.worktree_scan_id == worktree_scan_id
+        {
+            return Some(cached.changed_files.clone());
+        }
+
+        let changed_files = load.await?;
+        self.0.write().await.insert(
+            repository_root,
+            CachedChangedFiles {
+                worktree_scan_id,
+                changed_files: changed_files.clone(),
+            },
+        );
+        Some(changed_files)
+    }
+}
+
+/// The closest ancestor directory of `abs_path` with a `.git` entry, which is a file for linked
+/// worktrees and submodules.
+async fn repository_root(abs_path: &Path) -> Option<PathBuf> {
+    for directory in abs_path.ancestors().skip(1) {
+        if smol::fs::metadata(directory.join(".git")).await.is_ok() {
+            return Some(directory.to_path_buf());
+        }
+    }
+    None
+}
+
+/// Lists the files changed since `HEAD` in the git repository at `repository_root`.
+async fn changed_files_since_head(
+    repository_root: &Path,
+    project_env: Option<&HashMap<String, String>>,
+) -> Option<Vec<PathBuf>> {
+    let mut command = util::command::new_smol_command("git");
+    if let Some(envs) = project_env {
+        command.envs(envs);
+    }
+    let output = command
+        .current_dir(repository_root)
+        .args(["diff", "--name-only", "HEAD"])
+        .output()
+        .await
+        .log_err()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8(output.stdout)
+            .ok()?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| repository_root.join(line))
+            .collect(),
+    )
+}
+
+/// Maps each file to the name of the most specific package containing it.
+fn packages_for_files(metadata: &CargoMetadata, files: &[PathBuf]) -> BTreeSet<String> {
+    files
+        .iter()
+        .filter_map(|file| {
+            metadata
+                .packages
+                .iter()
+                .filter_map(|package| {
+                    let manifest_dir_path = package.manifest_path.parent()?;
+                    file.starts_with(manifest_dir_path)
+                        .then(|| (manifest_dir_path.components().count(), package))
+                })
+                .max_by_key(|(depth, _)| *depth)
+                .and_then(|(_, package)| package_name_from_pkgid(&package.id))
+                .map(ToOwned::to_owned)
+        })
+        .collect()
 }
 
 fn target_info_from_metadata(
@@ -842,6 +1662,104 @@ fn target_info_from_metadata(
     manifest_path.map(|(path, _)| (None, path))
 }
 
+/// When `abs_path` isn't itself a binary's entry point but belongs to a package with several
+/// `[[bin]]` targets, returns that package's name along with all of its binaries, so that each
+/// of them can get its own run task.
+fn package_binaries(
+    metadata: &CargoMetadata,
+    abs_path: &Path,
+) -> Option<(String, Vec<&CargoTarget>)> {
+    let package = containing_package(metadata, abs_path)?;
+    let binaries = package
+        .targets
+        .iter()
+        .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+        .collect::<Vec<_>>();
+    let is_binary_entry_point = binaries
+        .iter()
+        .any(|target| Path::new(&target.src_path) == abs_path);
+    if binaries.len() < 2 || is_binary_entry_point {
+        return None;
+    }
+
+    let package_name = package_name_from_pkgid(&package.id)?.to_owned();
+    Some((package_name, binaries))
+}
+
+/// The arguments of the plain "Run" task for the package containing `abs_path`. Its `default-run`
+/// binary is targeted explicitly, so that the task also works from the root of a workspace, and
+/// `None` is returned when the package has several binaries but no default to pick from them.
+fn default_run_args(metadata: &CargoMetadata, abs_path: &Path) -> Option<Vec<String>> {
+    let Some(package) = containing_package(metadata, abs_path) else {
+        return Some(vec!["run".into()]);
+    };
+    let package_name = package_name_from_pkgid(&package.id)?;
+    if let Some(default_run) = &package.default_run {
+        return Some(vec![
+            "run".into(),
+            "-p".into(),
+            package_name.to_owned(),
+            "--bin".into(),
+            default_run.clone(),
+        ]);
+    }
+    let binary_count = package
+        .targets
+        .iter()
+        .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+        .count();
+    (binary_count <= 1).then(|| vec!["run".into()])
+}
+
+/// Names of the workspace members, sorted, along with whether each has a binary target. Returns
+/// nothing for single-package workspaces, which have no siblings to target.
+fn workspace_members(metadata: &CargoMetadata) -> Vec<(String, bool)> {
+    if metadata.packages.len() < 2 {
+        return Vec::new();
+    }
+    let mut members = metadata
+        .packages
+        .iter()
+        .filter_map(|package| {
+            let name = package_name_from_pkgid(&package.id)?.to_owned();
+            let has_binaries = package
+                .targets
+                .iter()
+                .any(|target| target.kind.iter().any(|kind| kind == "bin"));
+            Some((name, has_binaries))
+        })
+        .collect::<Vec<_>>();
+    members.sort();
+    members
+}
+
+/// The most specific package whose manifest directory contains `abs_path`.
+fn containing_package<'a>(
+    metadata: &'a CargoMetadata,
+    abs_path: &Path,
+) -> Option<&'a CargoPackage> {
+    metadata
+        .packages
+        .iter()
+        .filter_map(|package| {
+            let manifest_dir_path = package.manifest_path.parent()?;
+            abs_path
+                .starts_with(manifest_dir_path)
+                .then(|| (manifest_dir_path.components().count(), package))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, package)| package)
+}
+
+/// Whether the package containing `abs_path` uses `insta` for snapshot testing.
+fn package_uses_insta(metadata: &CargoMetadata, abs_path: &Path) -> bool {
+    containing_package(metadata, abs_path).is_some_and(|package| {
+        package.dependencies.iter().any(|dependency| {
+            dependency.name == "insta" && dependency.kind.as_deref() == Some("dev")
+        })
+    })
+}
+
 async fn human_readable_package_name(
     package_directory: &Path,
     project_env: Option<&HashMap<String, String>>,
@@ -919,6 +1837,34 @@ fn test_fragment(variables: &TaskVariables, path: &Path, stem: &str) -> String {
     fragment.unwrap_or_else(|| "--".to_owned())
 }
 
+/// The module path of a source file relative to the `src` directory of its crate, as rustdoc
+/// prefixes doc example names with it. `None` for crate roots and files outside of `src`.
+///
+/// Takes the path relative to the worktree root, so that a `src` directory the worktree itself
+/// lives in isn't mistaken for the crate's.
+fn rust_module_path(worktree_path: &Path) -> Option<String> {
+    let src_dir = worktree_path
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.file_name().is_some_and(|name| name == "src"))?;
+    let relative_path = worktree_path.strip_prefix(src_dir).ok()?.with_extension("");
+    let mut segments = relative_path
+        .iter()
+        .map(|segment| segment.to_str())
+        .collect::<Option<Vec<_>>>()?;
+    // Every file or directory in `src/bin` is a crate of its own.
+    if segments.first() == Some(&"bin") {
+        segments.drain(..segments.len().min(2));
+    }
+    if segments.is_empty() || (segments.len() == 1 && matches!(segments[0], "lib" | "main")) {
+        return None;
+    }
+    if segments.last() == Some(&"mod") {
+        segments.pop();
+    }
+    (!segments.is_empty()).then(|| segments.join("::"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -927,7 +1873,7 @@ mod tests {
     use crate::language;
     use anyhow::Context as _;
     use gpui::{BorrowAppContext, Hsla, TestAppContext};
-    use language::language_settings::AllLanguageSettings;
+    use language::language_settings::{AllLanguageSettings, LanguageTaskConfig};
     use lsp::CompletionItemLabelDetails;
     use settings::SettingsStore;
     use theme::SyntaxTheme;
@@ -1354,6 +2300,349 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_package_binaries() {
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/tools#0.1.0","manifest_path":"/path/to/tools/Cargo.toml","targets":[{"name":"tools","kind":["lib"],"src_path":"/path/to/tools/src/lib.rs"},{"name":"server","kind":["bin"],"src_path":"/path/to/tools/src/bin/server.rs"},{"name":"client","kind":["bin"],"src_path":"/path/to/tools/src/bin/client.rs","required-features":["tui"]}]},{"id":"path+file:///path/to/single#0.1.0","manifest_path":"/path/to/single/Cargo.toml","targets":[{"name":"single","kind":["bin"],"src_path":"/path/to/single/src/main.rs"}]}]}"#,
+        )
+        .unwrap();
+
+        let (package_name, binaries) =
+            package_binaries(&metadata, Path::new("/path/to/tools/src/lib.rs")).unwrap();
+        assert_eq!(package_name, "tools");
+        assert_eq!(
+            binaries
+                .iter()
+                .map(|binary| (binary.name.as_str(), binary.required_features.clone()))
+                .collect::<Vec<_>>(),
+            vec![("server", vec![]), ("client", vec!["tui".to_owned()])]
+        );
+
+        assert!(
+            package_binaries(&metadata, Path::new("/path/to/tools/src/bin/server.rs")).is_none()
+        );
+        assert!(package_binaries(&metadata, Path::new("/path/to/single/src/util.rs")).is_none());
+        assert!(package_binaries(&metadata, Path::new("/path/to/elsewhere/src/lib.rs")).is_none());
+    }
+
+    #[test]
+    fn test_default_run_args() {
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/single#0.1.0","manifest_path":"/path/to/single/Cargo.toml","targets":[{"name":"single","kind":["bin"],"src_path":"/path/to/single/src/main.rs"}],"default_run":null},{"id":"path+file:///path/to/tools#0.1.0","manifest_path":"/path/to/tools/Cargo.toml","targets":[{"name":"server","kind":["bin"],"src_path":"/path/to/tools/src/bin/server.rs"},{"name":"client","kind":["bin"],"src_path":"/path/to/tools/src/bin/client.rs"}]},{"id":"path+file:///path/to/app#0.1.0","manifest_path":"/path/to/app/Cargo.toml","targets":[{"name":"app","kind":["bin"],"src_path":"/path/to/app/src/main.rs"},{"name":"migrate","kind":["bin"],"src_path":"/path/to/app/src/bin/migrate.rs"}],"default_run":"app"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            default_run_args(&metadata, Path::new("/path/to/single/src/main.rs")),
+            Some(vec!["run".to_owned()])
+        );
+        assert_eq!(
+            default_run_args(&metadata, Path::new("/path/to/tools/src/bin/client.rs")),
+            None
+        );
+        assert_eq!(
+            default_run_args(&metadata, Path::new("/path/to/app/src/bin/migrate.rs")),
+            Some(
+                ["run", "-p", "app", "--bin", "app"]
+                    .map(str::to_owned)
+                    .to_vec()
+            )
+        );
+        assert_eq!(
+            default_run_args(&metadata, Path::new("/path/to/elsewhere/src/main.rs")),
+            Some(vec!["run".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_doc_test_fence_index() {
+        let doc_lines = |text: &str| text.lines().map(ToOwned::to_owned).collect::<Vec<_>>();
+
+        let docs = " Adds numbers.\n\n ```\n assert_eq!(add(1, 2), 3);";
+        assert_eq!(doc_test_fence_index(&doc_lines(docs)), Some(2));
+        let docs = " Adds numbers.\n\n ```rust,no_run";
+        assert_eq!(doc_test_fence_index(&doc_lines(docs)), Some(2));
+        let docs = " ```\n first();\n ```\n\n ```should_panic\n second();\n ```";
+        assert_eq!(doc_test_fence_index(&doc_lines(docs)), Some(4));
+        let docs = " ```\n first();\n ```\n Outside of any block.";
+        assert_eq!(doc_test_fence_index(&doc_lines(docs)), None);
+        let docs = " ```text\n not rust";
+        assert_eq!(doc_test_fence_index(&doc_lines(docs)), None);
+    }
+
+    #[test]
+    fn test_build_target_from_config() {
+        assert_eq!(
+            build_target_from_config("[build]\ntarget = \"thumbv7em-none-eabihf\"\n").as_deref(),
+            Some("thumbv7em-none-eabihf")
+        );
+        assert_eq!(
+            build_target_from_config("build.target = [\"wasm32-unknown-unknown\"]").as_deref(),
+            Some("wasm32-unknown-unknown")
+        );
+        assert_eq!(
+            build_target_from_config(
+                "[build]\ntarget = [\"x86_64-unknown-linux-gnu\", \"aarch64-apple-darwin\"]"
+            ),
+            None
+        );
+        assert_eq!(build_target_from_config("[build]\njobs = 4"), None);
+        assert_eq!(build_target_from_config("[alias]\nb = \"build\""), None);
+    }
+
+    #[test]
+    fn test_cargo_metadata_cache() {
+        let directory =
+            std::env::temp_dir().join(format!("zed-cargo-metadata-cache-{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("src")).unwrap();
+        let manifest_path = directory.join("Cargo.toml");
+        std::fs::write(&manifest_path, "[package]\nname = \"cached\"\n").unwrap();
+        let abs_path = directory.join("src").join("main.rs");
+
+        let cache = CargoMetadataCache::default();
+        let loads = std::sync::atomic::AtomicUsize::new(0);
+        let load = || async {
+            loads.fetch_add(1, SeqCst);
+            serde_json::from_str::<CargoMetadata>(r#"{"packages":[]}"#).ok()
+        };
+        smol::block_on(async {
+            assert!(cache.get_or_load(&abs_path, load()).await.is_some());
+            assert!(cache.get_or_load(&abs_path, load()).await.is_some());
+            assert_eq!(loads.load(SeqCst), 1);
+
+            std::fs::File::options()
+                .write(true)
+                .open(&manifest_path)
+                .unwrap()
+                .set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+                .unwrap();
+            assert!(cache.get_or_load(&abs_path, load()).await.is_some());
+            assert_eq!(loads.load(SeqCst), 2);
+        });
+
+        std::fs::remove_dir_all(&directory).ok();
+    }
+
+    #[test]
+    fn test_changed_files_cache() {
+        let cache = ChangedFilesCache::default();
+        let loads = std::sync::atomic::AtomicUsize::new(0);
+        let load = || async {
+            loads.fetch_add(1, SeqCst);
+            Some(vec![PathBuf::from("/repo/src/lib.rs")])
+        };
+        let repository_root = PathBuf::from("/repo");
+        smol::block_on(async {
+            for _ in 0..2 {
+                assert_eq!(
+                    cache.get_or_load(repository_root.clone(), 1, load()).await,
+                    Some(vec![PathBuf::from("/repo/src/lib.rs")])
+                );
+            }
+            assert_eq!(loads.load(SeqCst), 1);
+
+            // The worktree was scanned again, so the files may have changed.
+            assert!(
+                cache
+                    .get_or_load(repository_root.clone(), 2, load())
+                    .await
+                    .is_some()
+            );
+            assert_eq!(loads.load(SeqCst), 2);
+        });
+    }
+
+    #[test]
+    fn test_apply_cargo_toolchain() {
+        let mut task_templates = vec![
+            TaskTemplate {
+                label: "Format".into(),
+                command: "cargo".into(),
+                args: vec!["fmt".into()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "Echo".into(),
+                command: "echo".into(),
+                args: vec!["fmt".into()],
+                ..TaskTemplate::default()
+            },
+        ];
+        apply_cargo_toolchain(&mut task_templates, "nightly");
+        assert_eq!(task_templates[0].args, vec!["+nightly", "fmt"]);
+        assert_eq!(task_templates[1].args, vec!["fmt"]);
+    }
+
+    #[gpui::test]
+    async fn test_nightly_fmt_task(cx: &mut TestAppContext) {
+        init_task_test(cx);
+        let nightly_fmt_args = async |toolchain: Option<&str>, cx: &mut TestAppContext| {
+            let variables = toolchain
+                .map(|toolchain| {
+                    HashMap::from_iter([("RUST_TOOLCHAIN".to_owned(), toolchain.to_owned())])
+                })
+                .unwrap_or_default();
+            associated_task_templates(variables, cx)
+                .await
+                .into_iter()
+                .filter(|task_template| task_template.label.contains("rustfmt"))
+                .map(|task_template| task_template.args)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            nightly_fmt_args(Some("nightly"), cx).await,
+            [vec!["+nightly", "fmt", "--all"]]
+        );
+        assert_eq!(
+            nightly_fmt_args(Some("+nightly-2025-01-01"), cx).await,
+            [vec!["+nightly-2025-01-01", "fmt", "--all"]]
+        );
+        assert!(nightly_fmt_args(Some("stable"), cx).await.is_empty());
+        assert!(nightly_fmt_args(None, cx).await.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_doc_test_at_line_task(cx: &mut TestAppContext) {
+        init_task_test(cx);
+        let task_template = associated_task_templates(HashMap::default(), cx)
+            .await
+            .into_iter()
+            .find(|task_template| {
+                task_template.label.starts_with("Doc test '")
+                    && task_template.label.contains("at line")
+            })
+            .unwrap();
+        let task_context = task::TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([
+                (RUST_PACKAGE_TASK_VARIABLE, "parser".to_owned()),
+                (RUST_DOC_TEST_NAME_TASK_VARIABLE, "Token::new".to_owned()),
+                (RUST_DOC_TEST_LINE_TASK_VARIABLE, "12".to_owned()),
+                (RUST_MANIFEST_DIRNAME_TASK_VARIABLE, "/project".to_owned()),
+                (RUST_TARGET_FLAG_TASK_VARIABLE, String::new()),
+                (RUST_TARGET_TRIPLE_TASK_VARIABLE, String::new()),
+            ]),
+            project_env: HashMap::default(),
+        };
+        let resolved_task = task_template.resolve_task("test", &task_context).unwrap();
+        assert_eq!(
+            resolved_task.resolved.args.last().map(String::as_str),
+            Some("'Token::new (line 12)'")
+        );
+
+        let (_, shell_args) = task::ShellBuilder::new(true, &task::Shell::Program("sh".into()))
+            .non_interactive()
+            .build(
+                resolved_task.resolved.command.clone(),
+                &resolved_task.resolved.args,
+            );
+        assert!(
+            shell_args
+                .last()
+                .unwrap()
+                .ends_with("--nocapture --include-ignored 'Token::new (line 12)'"),
+            "unexpected command line: {shell_args:?}"
+        );
+    }
+
+    fn init_task_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            language::init(cx);
+        });
+    }
+
+    /// The task templates of a file outside of any worktree, with the given Rust task variables
+    /// configured.
+    async fn associated_task_templates(
+        variables: HashMap<String, String>,
+        cx: &mut TestAppContext,
+    ) -> Vec<TaskTemplate> {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |s| {
+                    s.defaults.tasks = Some(LanguageTaskConfig {
+                        variables,
+                        enabled: true,
+                        prefer_lsp: true,
+                    });
+                });
+            });
+        });
+        let fs = project::FakeFs::new(cx.executor());
+        cx.update(|cx| RustContextProvider::new().associated_tasks(fs, None, cx))
+            .await
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_workspace_members() {
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/workspace/crates/server#0.1.0","manifest_path":"/path/to/workspace/crates/server/Cargo.toml","targets":[{"name":"server","kind":["bin"],"src_path":"/path/to/workspace/crates/server/src/main.rs"}]},{"id":"path+file:///path/to/workspace/crates/core#0.1.0","manifest_path":"/path/to/workspace/crates/core/Cargo.toml","targets":[{"name":"core","kind":["lib"],"src_path":"/path/to/workspace/crates/core/src/lib.rs"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            workspace_members(&metadata),
+            vec![("core".to_owned(), false), ("server".to_owned(), true)]
+        );
+
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/single#0.1.0","manifest_path":"/path/to/single/Cargo.toml","targets":[]}]}"#,
+        )
+        .unwrap();
+        assert!(workspace_members(&metadata).is_empty());
+    }
+
+    #[test]
+    fn test_package_uses_insta() {
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/snapshots#0.1.0","manifest_path":"/path/to/snapshots/Cargo.toml","targets":[],"dependencies":[{"name":"insta","kind":"dev"},{"name":"serde","kind":null}]},{"id":"path+file:///path/to/runtime#0.1.0","manifest_path":"/path/to/runtime/Cargo.toml","targets":[],"dependencies":[{"name":"insta","kind":null}]},{"id":"path+file:///path/to/plain#0.1.0","manifest_path":"/path/to/plain/Cargo.toml","targets":[]}]}"#,
+        )
+        .unwrap();
+
+        assert!(package_uses_insta(
+            &metadata,
+            Path::new("/path/to/snapshots/tests/snapshots.rs")
+        ));
+        assert!(!package_uses_insta(
+            &metadata,
+            Path::new("/path/to/runtime/src/lib.rs")
+        ));
+        assert!(!package_uses_insta(
+            &metadata,
+            Path::new("/path/to/plain/src/lib.rs")
+        ));
+        assert!(!package_uses_insta(
+            &metadata,
+            Path::new("/path/to/elsewhere/src/lib.rs")
+        ));
+    }
+
+    #[test]
+    fn test_packages_for_files() {
+        let metadata: CargoMetadata = serde_json::from_str(
+            r#"{"packages":[{"id":"path+file:///path/to/workspace#root@0.1.0","manifest_path":"/path/to/workspace/Cargo.toml","targets":[]},{"id":"path+file:///path/to/workspace/crates/nested#0.1.0","manifest_path":"/path/to/workspace/crates/nested/Cargo.toml","targets":[]},{"id":"path+file:///path/to/workspace/crates/other#0.1.0","manifest_path":"/path/to/workspace/crates/other/Cargo.toml","targets":[]}]}"#,
+        )
+        .unwrap();
+
+        let changed_files = [
+            "/path/to/workspace/src/lib.rs",
+            "/path/to/workspace/crates/nested/src/lib.rs",
+            "/path/to/workspace/crates/nested/Cargo.toml",
+            "/path/to/elsewhere/src/lib.rs",
+        ]
+        .map(PathBuf::from);
+
+        assert_eq!(
+            packages_for_files(&metadata, &changed_files),
+            BTreeSet::from_iter(["root".to_owned(), "nested".to_owned()])
+        );
+        assert!(packages_for_files(&metadata, &[]).is_empty());
+    }
+
     #[test]
     fn test_rust_test_fragment() {
         #[track_caller]
@@ -1383,4 +2672,65 @@ mod tests {
         );
         check([], "/project/src/main.rs", "--");
     }
+
+    #[test]
+    fn test_rust_test_path() {
+        let tests_module = ["tests".to_owned()];
+        // `--exact` only matches the full name, so the test has to be qualified with every
+        // module it is nested in.
+        assert_eq!(
+            test_path(Some("parser::lexer"), &tests_module, "empty_input"),
+            "parser::lexer::tests::empty_input"
+        );
+        assert_eq!(
+            test_path(None, &tests_module, "empty_input"),
+            "tests::empty_input"
+        );
+        assert_eq!(
+            test_path(
+                Some("parser"),
+                &["tests".to_owned(), "edge_cases".to_owned()],
+                "unicode"
+            ),
+            "parser::tests::edge_cases::unicode"
+        );
+        assert_eq!(test_path(None, &[], "top_level"), "top_level");
+    }
+
+    #[test]
+    fn test_rust_module_path() {
+        assert_eq!(rust_module_path(Path::new("src/lib.rs")), None);
+        assert_eq!(rust_module_path(Path::new("src/main.rs")), None);
+        assert_eq!(
+            rust_module_path(Path::new("src/parser.rs")).as_deref(),
+            Some("parser")
+        );
+        assert_eq!(
+            rust_module_path(Path::new("src/parser/lexer.rs")).as_deref(),
+            Some("parser::lexer")
+        );
+        assert_eq!(
+            rust_module_path(Path::new("src/parser/mod.rs")).as_deref(),
+            Some("parser")
+        );
+        assert_eq!(rust_module_path(Path::new("src/bin/tool.rs")), None);
+        assert_eq!(
+            rust_module_path(Path::new("src/bin/tool/cli.rs")).as_deref(),
+            Some("cli")
+        );
+        assert_eq!(
+            rust_module_path(Path::new("crates/parser/src/lexer.rs")).as_deref(),
+            Some("lexer")
+        );
+
+        // Files outside of `src`, e.g. in a worktree checked out at `~/src/project`.
+        assert_eq!(rust_module_path(Path::new("build.rs")), None);
+        assert_eq!(rust_module_path(Path::new("tests/integration.rs")), None);
+        assert_eq!(rust_module_path(Path::new("examples/demo.rs")), None);
+        assert_eq!(rust_module_path(Path::new("benches/parsing.rs")), None);
+        assert_eq!(
+            rust_module_path(Path::new("crates/parser/tests/lexer.rs")),
+            None
+        );
+    }
 }
@@ -37,6 +37,44 @@
         ) @_end
     )
     (#set! tag rust-test)
+    (#set! tag rust-single-test)
+)
+
+; Rust bench
+(
+    (
+        (attribute_item (attribute
+            (identifier) @_attribute
+            (#eq? @_attribute "bench")
+        )) @_start
+        .
+        (attribute_item) *
+        .
+        [(line_comment) (block_comment)] *
+        .
+        (function_item
+            name: (_) @run @_bench_name
+            body: _
+        ) @_end
+    )
+    (#set! tag rust-bench)
+)
+
+; Criterion benchmark or benchmark group
+(
+    (call_expression
+        function: (field_expression
+            field: (field_identifier) @_method
+            (#any-of? @_method "bench_function" "benchmark_group")
+        )
+        arguments: (arguments
+            .
+            (string_literal
+                (string_content) @run @_bench_name
+            )
+        )
+    )
+    (#set! tag rust-bench)
 )
 
 ; Rust doc test
@@ -77,6 +115,7 @@
         )] @_end
     )
     (#set! tag rust-doc-test)
+    (#set! tag rust-doc-example)
 )
 
 ; Rust main function
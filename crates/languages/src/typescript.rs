@@ -3,9 +3,11 @@ use async_trait::async_trait;
 use chrono::{DateTime, Local};
 use collections::HashMap;
 use futures::future::join_all;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{App, AppContext, AsyncApp, Task};
 use language::{
-    ContextLocation, ContextProvider, File, LanguageToolchainStore, LspAdapter, LspAdapterDelegate,
+    ContextLocation, ContextProvider, File, LanguageName, LanguageToolchainStore, LspAdapter,
+    LspAdapterDelegate, language_settings::language_settings,
 };
 use lsp::{CodeActionKind, LanguageServerName};
 use project::{Fs, lsp_store::language_server_settings};
@@ -20,22 +22,39 @@ use task::{TaskTemplate, TaskTemplates, VariableName};
 use util::merge_json_value_into;
 use util::{ResultExt};
 
+use crate::worktree_env::WorktreeEnvironments;
 use crate::{PackageJson, PackageJsonData};
 
 #[derive(Debug)]
 pub(crate) struct TypeScriptContextProvider {
+    /// The language whose settings configure the tasks, as TypeScript, TSX and JavaScript each
+    /// get their own provider.
+    language_name: LanguageName,
     last_package_json: PackageJsonContents,
+    worktree_envs: WorktreeEnvironments,
 }
 
 const TYPESCRIPT_RUNNER_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER"));
 
+/// The version of the package manager pinned by the `packageManager` field of package.json.
+const TYPESCRIPT_RUNNER_VERSION_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER_VERSION"));
+
+/// How tasks invoke the package manager: through corepack when package.json pins its version,
+/// so that the pinned version runs, and directly otherwise.
+const TYPESCRIPT_RUNNER_COMMAND_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER_COMMAND"));
+
 const TYPESCRIPT_JEST_TEST_NAME_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_JEST_TEST_NAME"));
 
 const TYPESCRIPT_VITEST_TEST_NAME_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_VITEST_TEST_NAME"));
 
+const TYPESCRIPT_PLAYWRIGHT_TEST_NAME_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_PLAYWRIGHT_TEST_NAME"));
+
 const TYPESCRIPT_JEST_PACKAGE_PATH_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_JEST_PACKAGE_PATH"));
 
@@ -48,15 +67,183 @@ const TYPESCRIPT_VITEST_PACKAGE_PATH_VARIABLE: VariableName =
 const TYPESCRIPT_JASMINE_PACKAGE_PATH_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_JASMINE_PACKAGE_PATH"));
 
+const TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH"));
+
+const TYPESCRIPT_CYPRESS_PACKAGE_PATH_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_CYPRESS_PACKAGE_PATH"));
+
+const TYPESCRIPT_FILE_RUNNER_PACKAGE_PATH_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_FILE_RUNNER_PACKAGE_PATH"));
+
+const TYPESCRIPT_DENO_PACKAGE_PATH_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_DENO_PACKAGE_PATH"));
+
+const TYPESCRIPT_NODE_VERSION_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_NODE_VERSION"));
+
+const TYPESCRIPT_PNPM_VERSION_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_PNPM_VERSION"));
+
+const TYPESCRIPT_YARN_VERSION_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_YARN_VERSION"));
+
+const TYPESCRIPT_BUN_VERSION_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_BUN_VERSION"));
+
+const DENO_CONFIG_FILE_NAMES: &[&str] = &["deno.json", "deno.jsonc"];
+
+const ANGULAR_CONFIG_FILE_NAME: &str = "angular.json";
+
+const JEST_CONFIG_FILE_NAMES: &[&str] = &[
+    "jest.config.js",
+    "jest.config.ts",
+    "jest.config.mjs",
+    "jest.config.cjs",
+    "jest.config.json",
+];
+
+const VITEST_CONFIG_FILE_NAMES: &[&str] = &[
+    "vitest.config.js",
+    "vitest.config.ts",
+    "vitest.config.mjs",
+    "vitest.config.cjs",
+    "vitest.config.mts",
+    "vitest.config.cts",
+];
+
+/// A `tasks.variables` setting that, when `"true"`, runs the execute-selection and run-file tasks
+/// with the environment of a login shell in the worktree root, including e.g. direnv's variables.
+const TYPESCRIPT_PROJECT_ENV_SETTING: &str = "TYPESCRIPT_PROJECT_ENV";
+
+/// A `tasks.variables` setting naming the package manager that runs scripts and tests, e.g.
+/// `bun`, for projects where it can't be detected from the package.json or lockfiles.
+const TYPESCRIPT_RUNNER_SETTING: &str = "TYPESCRIPT_RUNNER";
+
+/// A `tasks.variables` setting choosing how package.json script tasks are labeled:
+/// `"short"` (the default) or `"relative_path"`.
+const TYPESCRIPT_SCRIPT_LABELS_SETTING: &str = "TYPESCRIPT_SCRIPT_LABELS";
+
+/// A `tasks.variables` setting with comma-separated glob patterns of package.json script names
+/// that should become tasks. When set, only matching scripts are shown, even if they are excluded.
+const TYPESCRIPT_SCRIPTS_INCLUDE_SETTING: &str = "TYPESCRIPT_SCRIPTS_INCLUDE";
+
+/// A `tasks.variables` setting with comma-separated glob patterns of package.json script names
+/// that should not become tasks. Defaults to excluding lifecycle scripts, see
+/// [`is_lifecycle_script`].
+const TYPESCRIPT_SCRIPTS_EXCLUDE_SETTING: &str = "TYPESCRIPT_SCRIPTS_EXCLUDE";
+
+/// Scripts that npm runs on its own as part of its built-in commands.
+const NPM_LIFECYCLE_SCRIPTS: &[&str] = &[
+    "prepare",
+    "prepublish",
+    "prepublishOnly",
+    "prepack",
+    "postpack",
+    "preinstall",
+    "install",
+    "postinstall",
+    "preuninstall",
+    "uninstall",
+    "postuninstall",
+    "preversion",
+    "version",
+    "postversion",
+    "dependencies",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScriptLabelStyle {
+    /// `package.json > test`, qualified by the package name (or its directory name, if it has
+    /// none) when several packages define the same script.
+    #[default]
+    Short,
+    /// `packages/app/package.json > test`, relative to the worktree root.
+    RelativePath,
+}
+
+impl ScriptLabelStyle {
+    fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("relative_path") => Self::RelativePath,
+            _ => Self::Short,
+        }
+    }
+}
+
+/// Decides which package.json scripts become tasks.
+#[derive(Debug, Default)]
+struct ScriptFilter {
+    include: Option<GlobSet>,
+    /// Patterns of excluded scripts, or `None` to exclude lifecycle scripts.
+    exclude: Option<GlobSet>,
+}
+
+impl ScriptFilter {
+    fn from_settings(include: Option<&str>, exclude: Option<&str>) -> Self {
+        let include = include
+            .map(split_patterns)
+            .and_then(|patterns| build_glob_set(patterns).log_err());
+        let exclude = exclude
+            .map(split_patterns)
+            .and_then(|patterns| build_glob_set(patterns).log_err());
+        Self { include, exclude }
+    }
+
+    /// Whether the script becomes a task, given whether its package.json defines other scripts.
+    fn is_allowed(&self, script: &str, has_script: impl Fn(&str) -> bool) -> bool {
+        match (&self.include, &self.exclude) {
+            (Some(include), _) => include.is_match(script),
+            (None, Some(exclude)) => !exclude.is_match(script),
+            (None, None) => !is_lifecycle_script(script, has_script),
+        }
+    }
+}
+
+/// Whether the script is run by the package manager rather than directly: one of npm's built-in
+/// lifecycle scripts, or a `pre<name>`/`post<name>` hook of another script in the same package.
+fn is_lifecycle_script(script: &str, has_script: impl Fn(&str) -> bool) -> bool {
+    NPM_LIFECYCLE_SCRIPTS.contains(&script)
+        || ["pre", "post"].iter().any(|prefix| {
+            script
+                .strip_prefix(prefix)
+                .is_some_and(|hooked| !hooked.is_empty() && has_script(hooked))
+        })
+}
+
+fn split_patterns(patterns: &str) -> impl Iterator<Item = &str> {
+    patterns
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+}
+
+fn build_glob_set<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(
+            Glob::new(pattern).with_context(|| format!("invalid script pattern {pattern:?}"))?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
 #[derive(Clone, Debug, Default)]
 struct PackageJsonContents(Arc<RwLock<HashMap<PathBuf, PackageJson>>>);
 
 impl PackageJsonData {
-    fn fill_task_templates(&self, task_templates: &mut TaskTemplates) {
+    fn fill_task_templates(
+        &self,
+        task_templates: &mut TaskTemplates,
+        script_label_style: ScriptLabelStyle,
+        script_filter: &ScriptFilter,
+        worktree_root: &Path,
+        run_file_env: &HashMap<String, String>,
+    ) {
         if self.jest_package_path.is_some() {
             task_templates.0.push(TaskTemplate {
                 label: "jest file test".to_owned(),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -65,11 +252,12 @@ impl PackageJsonData {
                     VariableName::File.template_value(),
                 ],
                 cwd: Some(TYPESCRIPT_JEST_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
             task_templates.0.push(TaskTemplate {
                 label: format!("jest test {}", VariableName::Symbol.template_value()),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -88,6 +276,7 @@ impl PackageJsonData {
                     "tsx-test".to_owned(),
                 ],
                 cwd: Some(TYPESCRIPT_JEST_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
         }
@@ -95,7 +284,7 @@ impl PackageJsonData {
         if self.vitest_package_path.is_some() {
             task_templates.0.push(TaskTemplate {
                 label: format!("{} file test", "vitest".to_owned()),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -106,6 +295,7 @@ impl PackageJsonData {
                     VariableName::File.template_value(),
                 ],
                 cwd: Some(TYPESCRIPT_VITEST_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
             task_templates.0.push(TaskTemplate {
@@ -114,7 +304,7 @@ impl PackageJsonData {
                     "vitest".to_owned(),
                     VariableName::Symbol.template_value(),
                 ),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -135,6 +325,7 @@ impl PackageJsonData {
                     "tsx-test".to_owned(),
                 ],
                 cwd: Some(TYPESCRIPT_VITEST_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
         }
@@ -142,7 +333,7 @@ impl PackageJsonData {
         if self.mocha_package_path.is_some() {
             task_templates.0.push(TaskTemplate {
                 label: format!("{} file test", "mocha".to_owned()),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -150,6 +341,7 @@ impl PackageJsonData {
                     VariableName::File.template_value(),
                 ],
                 cwd: Some(TYPESCRIPT_MOCHA_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
             task_templates.0.push(TaskTemplate {
@@ -158,7 +350,7 @@ impl PackageJsonData {
                     "mocha".to_owned(),
                     VariableName::Symbol.template_value(),
                 ),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -173,6 +365,7 @@ impl PackageJsonData {
                     "tsx-test".to_owned(),
                 ],
                 cwd: Some(TYPESCRIPT_MOCHA_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
         }
@@ -180,7 +373,7 @@ impl PackageJsonData {
         if self.jasmine_package_path.is_some() {
             task_templates.0.push(TaskTemplate {
                 label: format!("{} file test", "jasmine".to_owned()),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -188,6 +381,7 @@ impl PackageJsonData {
                     VariableName::File.template_value(),
                 ],
                 cwd: Some(TYPESCRIPT_JASMINE_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
             task_templates.0.push(TaskTemplate {
@@ -196,7 +390,7 @@ impl PackageJsonData {
                     "jasmine".to_owned(),
                     VariableName::Symbol.template_value(),
                 ),
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec![
                     "exec".to_owned(),
                     "--".to_owned(),
@@ -210,19 +404,118 @@ impl PackageJsonData {
                     "tsx-test".to_owned(),
                 ],
                 cwd: Some(TYPESCRIPT_JASMINE_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
                 ..TaskTemplate::default()
             });
         }
 
+        if self.playwright_package_path.is_some() {
+            task_templates.0.push(TaskTemplate {
+                label: "playwright file test".to_owned(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
+                args: vec![
+                    "exec".to_owned(),
+                    "--".to_owned(),
+                    "playwright".to_owned(),
+                    "test".to_owned(),
+                    VariableName::File.template_value(),
+                ],
+                cwd: Some(TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
+                ..TaskTemplate::default()
+            });
+            task_templates.0.push(TaskTemplate {
+                label: format!("playwright test {}", VariableName::Symbol.template_value()),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
+                args: vec![
+                    "exec".to_owned(),
+                    "--".to_owned(),
+                    "playwright".to_owned(),
+                    "test".to_owned(),
+                    VariableName::File.template_value(),
+                    "-g".to_owned(),
+                    format!(
+                        "\"{}\"",
+                        TYPESCRIPT_PLAYWRIGHT_TEST_NAME_VARIABLE.template_value()
+                    ),
+                ],
+                tags: vec![
+                    "ts-test".to_owned(),
+                    "js-test".to_owned(),
+                    "tsx-test".to_owned(),
+                ],
+                cwd: Some(TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
+                ..TaskTemplate::default()
+            });
+        }
+
+        if self.cypress_package_path.is_some() {
+            // Cypress can't select a single test from the command line, so tests run their spec.
+            task_templates.0.push(TaskTemplate {
+                label: "cypress file test".to_owned(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
+                args: vec![
+                    "exec".to_owned(),
+                    "--".to_owned(),
+                    "cypress".to_owned(),
+                    "run".to_owned(),
+                    "--spec".to_owned(),
+                    VariableName::File.template_value(),
+                ],
+                tags: vec![
+                    "ts-test".to_owned(),
+                    "js-test".to_owned(),
+                    "tsx-test".to_owned(),
+                ],
+                cwd: Some(TYPESCRIPT_CYPRESS_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("test".to_owned()),
+                ..TaskTemplate::default()
+            });
+        }
+
+        if let Some((file_runner, _)) = self.typescript_file_runner() {
+            task_templates.0.push(TaskTemplate {
+                label: "Run TS file".to_owned(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
+                args: vec![
+                    "exec".to_owned(),
+                    "--".to_owned(),
+                    file_runner.to_owned(),
+                    VariableName::File.template_value(),
+                ],
+                env: run_file_env.clone(),
+                cwd: Some(TYPESCRIPT_FILE_RUNNER_PACKAGE_PATH_VARIABLE.template_value()),
+                group: Some("run".to_owned()),
+                ..TaskTemplate::default()
+            });
+        }
+
+        let scripts = self
+            .scripts
+            .iter()
+            .filter(|(path, script)| {
+                script_filter.is_allowed(script, |hooked| {
+                    self.scripts.contains(&(path.clone(), hooked.to_owned()))
+                })
+            })
+            .collect::<Vec<_>>();
         let script_name_counts: HashMap<_, usize> =
-            self.scripts
+            scripts
                 .iter()
                 .fold(HashMap::default(), |mut acc, (_, script)| {
                     *acc.entry(script).or_default() += 1;
                     acc
                 });
-        for (path, script) in &self.scripts {
-            let label = if script_name_counts.get(script).copied().unwrap_or_default() > 1
+        for (path, script) in scripts {
+            let label = if script_label_style == ScriptLabelStyle::RelativePath {
+                let relative_path = path.strip_prefix(worktree_root).unwrap_or(path);
+                format!("{} > {script}", relative_path.to_string_lossy())
+            } else if script_name_counts.get(script).copied().unwrap_or_default() > 1
+                && let Some(name) = self.package_names.get(path)
+            {
+                format!("package.json ({name}) > {script}")
+            } else if script_name_counts.get(script).copied().unwrap_or_default() > 1
                 && let Some(parent) = path.parent().and_then(|parent| parent.file_name())
             {
                 let parent = parent.to_string_lossy();
@@ -232,7 +525,7 @@ impl PackageJsonData {
             };
             task_templates.0.push(TaskTemplate {
                 label,
-                command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+                command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
                 args: vec!["run".to_owned(), script.to_owned()],
                 tags: vec!["package-script".into()],
                 cwd: Some(
@@ -241,6 +534,7 @@ impl PackageJsonData {
                         .to_string_lossy()
                         .to_string(),
                 ),
+                group: Some("scripts".to_owned()),
                 ..TaskTemplate::default()
             });
         }
@@ -248,9 +542,21 @@ impl PackageJsonData {
 }
 
 impl TypeScriptContextProvider {
-    pub fn new() -> Self {
+    pub fn new(language_name: LanguageName) -> Self {
         Self {
+            language_name,
             last_package_json: PackageJsonContents::default(),
+            worktree_envs: WorktreeEnvironments::default(),
+        }
+    }
+
+    /// A provider for another language, sharing the package.json files and worktree environments
+    /// loaded by this one.
+    pub fn for_language(&self, language_name: LanguageName) -> Self {
+        Self {
+            language_name,
+            last_package_json: self.last_package_json.clone(),
+            worktree_envs: self.worktree_envs.clone(),
         }
     }
 
@@ -261,11 +567,14 @@ impl TypeScriptContextProvider {
         file_relative_path: &Path,
         cx: &App,
     ) -> Task<anyhow::Result<PackageJsonData>> {
-        let new_json_data = file_relative_path
+        let directories = file_relative_path
             .ancestors()
             .map(|path| worktree_root.join(path))
+            .collect::<Vec<_>>();
+        let new_json_data = directories
+            .iter()
             .map(|parent_path| {
-                self.package_json_data(&parent_path, self.last_package_json.clone(), fs.clone(), cx)
+                self.package_json_data(parent_path, self.last_package_json.clone(), fs.clone(), cx)
             })
             .collect::<Vec<_>>();
 
@@ -274,6 +583,16 @@ impl TypeScriptContextProvider {
             for new_data in join_all(new_json_data).await.into_iter().flatten() {
                 package_json_data.merge(new_data);
             }
+            // The runner may be installed globally or by a parent workspace, in which case only
+            // its config file tells us that it is used.
+            if package_json_data.jest_package_path.is_none() {
+                package_json_data.jest_package_path =
+                    find_config_file(fs.as_ref(), &directories, JEST_CONFIG_FILE_NAMES).await;
+            }
+            if package_json_data.vitest_package_path.is_none() {
+                package_json_data.vitest_package_path =
+                    find_config_file(fs.as_ref(), &directories, VITEST_CONFIG_FILE_NAMES).await;
+            }
             Ok(package_json_data)
         })
     }
@@ -331,23 +650,241 @@ impl TypeScriptContextProvider {
     }
 }
 
+/// Returns the path of the first of `file_names` found in `directories`, searched in order.
+async fn find_config_file(
+    fs: &dyn Fs,
+    directories: &[PathBuf],
+    file_names: &[&str],
+) -> Option<Arc<Path>> {
+    for directory in directories {
+        for file_name in file_names {
+            let config_path = directory.join(file_name);
+            if fs.is_file(&config_path).await {
+                return Some(config_path.into());
+            }
+        }
+    }
+    None
+}
+
+/// Tool versions pinned by asdf in a `.tool-versions` file.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ToolVersions {
+    node: Option<String>,
+    pnpm: Option<String>,
+    yarn: Option<String>,
+    bun: Option<String>,
+}
+
+impl ToolVersions {
+    fn parse(contents: &str) -> Self {
+        let mut tool_versions = Self::default();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let (Some(tool), Some(version)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let slot = match tool {
+                "nodejs" | "node" => &mut tool_versions.node,
+                "pnpm" => &mut tool_versions.pnpm,
+                "yarn" => &mut tool_versions.yarn,
+                "bun" => &mut tool_versions.bun,
+                _ => continue,
+            };
+            slot.get_or_insert_with(|| version.to_owned());
+        }
+        tool_versions
+    }
+
+    fn package_manager(&self) -> Option<&'static str> {
+        if self.pnpm.is_some() {
+            Some("pnpm")
+        } else if self.yarn.is_some() {
+            Some("yarn")
+        } else if self.bun.is_some() {
+            Some("bun")
+        } else {
+            None
+        }
+    }
+}
+
+async fn load_tool_versions(fs: &dyn Fs, worktree_root: &Path) -> Option<ToolVersions> {
+    let contents = fs.load(&worktree_root.join(".tool-versions")).await.ok()?;
+    Some(ToolVersions::parse(&contents))
+}
+
 async fn detect_package_manager(
     worktree_root: PathBuf,
     fs: Arc<dyn Fs>,
     package_json_data: Option<PackageJsonData>,
-) -> &'static str {
+    tool_versions: Option<&ToolVersions>,
+    runner_setting: Option<&str>,
+) -> String {
+    if let Some(runner) = runner_setting
+        .map(str::trim)
+        .filter(|runner| !runner.is_empty())
+    {
+        return runner.to_owned();
+    }
     if let Some(package_json_data) = package_json_data {
         if let Some(package_manager) = package_json_data.package_manager {
-            return package_manager;
+            return package_manager.to_owned();
         }
     }
+    if let Some(package_manager) =
+        tool_versions.and_then(|tool_versions| tool_versions.package_manager())
+    {
+        return package_manager.to_owned();
+    }
     if fs.is_file(&worktree_root.join("pnpm-lock.yaml")).await {
-        return "pnpm";
+        return "pnpm".to_owned();
     }
     if fs.is_file(&worktree_root.join("yarn.lock")).await {
-        return "yarn";
+        return "yarn".to_owned();
+    }
+    if fs.is_file(&worktree_root.join("bun.lockb")).await
+        || fs.is_file(&worktree_root.join("bun.lock")).await
+    {
+        return "bun".to_owned();
+    }
+    "npm".to_owned()
+}
+
+fn runner_command(runner: &str, pinned_version: Option<&str>) -> String {
+    if pinned_version.is_some() {
+        format!("corepack {runner}")
+    } else {
+        runner.to_owned()
+    }
+}
+
+/// Finds the directory Deno tasks for the given file should run in.
+///
+/// This is the directory of the nearest `deno.json` or `deno.jsonc`, unless an ancestor
+/// config declares a `workspace` that doesn't list it as a member, in which case the
+/// workspace root is used instead.
+async fn detect_deno_project_root(
+    fs: Arc<dyn Fs>,
+    worktree_root: &Path,
+    file_relative_path: &Path,
+) -> Option<PathBuf> {
+    let mut nearest_config_dir = None;
+    for directory in file_relative_path
+        .ancestors()
+        .map(|path| worktree_root.join(path))
+    {
+        let Some(deno_json) = load_deno_json(fs.as_ref(), &directory).await else {
+            continue;
+        };
+        let nearest_config_dir = nearest_config_dir.get_or_insert_with(|| directory.clone());
+        let Some(members) = deno_json
+            .get("workspace")
+            .and_then(|workspace| workspace.as_array())
+        else {
+            continue;
+        };
+        let is_member = members
+            .iter()
+            .filter_map(|member| member.as_str())
+            .any(|member| directory.join(member) == *nearest_config_dir);
+        return if is_member {
+            Some(nearest_config_dir.clone())
+        } else {
+            Some(directory)
+        };
+    }
+    nearest_config_dir
+}
+
+async fn load_deno_json(fs: &dyn Fs, directory: &Path) -> Option<serde_json_lenient::Value> {
+    for file_name in DENO_CONFIG_FILE_NAMES {
+        let deno_json_path = directory.join(file_name);
+        if let Ok(contents) = fs.load(&deno_json_path).await {
+            return serde_json_lenient::from_str(&contents)
+                .with_context(|| format!("parsing {deno_json_path:?}"))
+                .log_err();
+        }
+    }
+    None
+}
+
+fn inherits_project_env(variables: &HashMap<String, String>) -> bool {
+    variables
+        .get(TYPESCRIPT_PROJECT_ENV_SETTING)
+        .is_some_and(|value| value.trim() == "true")
+}
+
+fn fill_deno_task_templates(
+    task_templates: &mut TaskTemplates,
+    run_file_env: &HashMap<String, String>,
+) {
+    let cwd = Some(TYPESCRIPT_DENO_PACKAGE_PATH_VARIABLE.template_value());
+    task_templates.0.push(TaskTemplate {
+        label: "deno test".to_owned(),
+        command: "deno".to_owned(),
+        args: vec!["test".to_owned()],
+        cwd: cwd.clone(),
+        group: Some("test".to_owned()),
+        ..TaskTemplate::default()
+    });
+    task_templates.0.push(TaskTemplate {
+        label: "deno file test".to_owned(),
+        command: "deno".to_owned(),
+        args: vec!["test".to_owned(), VariableName::File.template_value()],
+        cwd: cwd.clone(),
+        group: Some("test".to_owned()),
+        ..TaskTemplate::default()
+    });
+    task_templates.0.push(TaskTemplate {
+        label: format!("deno test {}", VariableName::Symbol.template_value()),
+        command: "deno".to_owned(),
+        args: vec![
+            "test".to_owned(),
+            "--filter".to_owned(),
+            format!("\"{}\"", VariableName::Symbol.template_value()),
+            VariableName::File.template_value(),
+        ],
+        tags: vec![
+            "ts-test".to_owned(),
+            "js-test".to_owned(),
+            "tsx-test".to_owned(),
+        ],
+        cwd: cwd.clone(),
+        group: Some("test".to_owned()),
+        ..TaskTemplate::default()
+    });
+    task_templates.0.push(TaskTemplate {
+        label: "deno run file".to_owned(),
+        command: "deno".to_owned(),
+        args: vec!["run".to_owned(), VariableName::File.template_value()],
+        env: run_file_env.clone(),
+        cwd,
+        group: Some("run".to_owned()),
+        ..TaskTemplate::default()
+    });
+}
+
+/// Adds the Angular CLI's `serve`, `test` and `build` commands, run through the project's
+/// package manager from the worktree root where `angular.json` lives.
+fn fill_angular_task_templates(task_templates: &mut TaskTemplates) {
+    for (command, group) in [("serve", "run"), ("test", "test"), ("build", "build")] {
+        task_templates.0.push(TaskTemplate {
+            label: format!("ng {command}"),
+            command: TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value(),
+            args: vec![
+                "exec".to_owned(),
+                "--".to_owned(),
+                "ng".to_owned(),
+                command.to_owned(),
+            ],
+            tags: vec!["angular".to_owned()],
+            cwd: Some(VariableName::WorktreeRoot.template_value()),
+            group: Some(group.to_owned()),
+            ..TaskTemplate::default()
+        });
     }
-    "npm"
 }
 
 impl ContextProvider for TypeScriptContextProvider {
@@ -357,6 +894,28 @@ impl ContextProvider for TypeScriptContextProvider {
         file: Option<Arc<dyn File>>,
         cx: &App,
     ) -> Task<Option<TaskTemplates>> {
+        let language_settings =
+            language_settings(Some(self.language_name.clone()), file.as_ref(), cx);
+        let inherit_project_env = inherits_project_env(&language_settings.tasks.variables);
+        let script_label_style = ScriptLabelStyle::from_setting(
+            language_settings
+                .tasks
+                .variables
+                .get(TYPESCRIPT_SCRIPT_LABELS_SETTING)
+                .map(String::as_str),
+        );
+        let script_filter = ScriptFilter::from_settings(
+            language_settings
+                .tasks
+                .variables
+                .get(TYPESCRIPT_SCRIPTS_INCLUDE_SETTING)
+                .map(String::as_str),
+            language_settings
+                .tasks
+                .variables
+                .get(TYPESCRIPT_SCRIPTS_EXCLUDE_SETTING)
+                .map(String::as_str),
+        );
         let Some(file) = project::File::from_dyn(file.as_ref()).cloned() else {
             return Task::ready(None);
         };
@@ -366,8 +925,19 @@ impl ContextProvider for TypeScriptContextProvider {
         let file_relative_path = file.path().clone();
         let package_json_data =
             self.combined_package_json_data(fs.clone(), &worktree_root, &file_relative_path, cx);
+        let run_file_env =
+            inherit_project_env.then(|| self.worktree_envs.get(fs.clone(), &file.worktree, cx));
 
         cx.background_spawn(async move {
+            let run_file_env = match run_file_env {
+                Some(run_file_env) => run_file_env.await,
+                None => HashMap::default(),
+            };
+            let is_angular_workspace = fs
+                .is_file(&worktree_root.join(ANGULAR_CONFIG_FILE_NAME))
+                .await;
+            let deno_project_root =
+                detect_deno_project_root(fs, &worktree_root, &file_relative_path).await;
             let mut task_templates = TaskTemplates(Vec::new());
             task_templates.0.push(TaskTemplate {
                 label: format!(
@@ -379,12 +949,20 @@ impl ContextProvider for TypeScriptContextProvider {
                     "-e".to_owned(),
                     format!("\"{}\"", VariableName::SelectedText.template_value()),
                 ],
+                env: run_file_env.clone(),
+                group: Some("run".to_owned()),
                 ..TaskTemplate::default()
             });
 
             match package_json_data.await {
                 Ok(package_json) => {
-                    package_json.fill_task_templates(&mut task_templates);
+                    package_json.fill_task_templates(
+                        &mut task_templates,
+                        script_label_style,
+                        &script_filter,
+                        &worktree_root,
+                        &run_file_env,
+                    );
                 }
                 Err(e) => {
                     log::error!(
@@ -393,6 +971,14 @@ impl ContextProvider for TypeScriptContextProvider {
                 }
             }
 
+            if deno_project_root.is_some() {
+                fill_deno_task_templates(&mut task_templates, &run_file_env);
+            }
+
+            if is_angular_workspace {
+                fill_angular_task_templates(&mut task_templates);
+            }
+
             Some(task_templates)
         })
     }
@@ -416,32 +1002,79 @@ impl ContextProvider for TypeScriptContextProvider {
                 TYPESCRIPT_VITEST_TEST_NAME_VARIABLE,
                 replace_test_name_parameters(symbol),
             );
+            vars.insert(
+                TYPESCRIPT_PLAYWRIGHT_TEST_NAME_VARIABLE,
+                replace_test_name_parameters(symbol),
+            );
         }
-        let file_path = location
-            .file_location
-            .buffer
-            .read(cx)
-            .file()
-            .map(|file| file.path());
+        let buffer = location.file_location.buffer.read(cx);
+        let file_path = buffer.file().map(|file| file.path().clone());
+        let task_settings = &language_settings(
+            buffer.language().map(|language| language.name()),
+            buffer.file(),
+            cx,
+        )
+        .tasks;
+        let runner_setting = task_settings
+            .variables
+            .get(TYPESCRIPT_RUNNER_SETTING)
+            .cloned();
 
         let args = location.worktree_root.zip(location.fs).zip(file_path).map(
             |((worktree_root, fs), file_path)| {
                 (
-                    self.combined_package_json_data(fs.clone(), &worktree_root, file_path, cx),
+                    self.combined_package_json_data(fs.clone(), &worktree_root, &file_path, cx),
                     worktree_root,
+                    file_path,
                     fs,
                 )
             },
         );
         cx.background_spawn(async move {
-            if let Some((task, worktree_root, fs)) = args {
+            if let Some((task, worktree_root, file_path, fs)) = args {
+                if let Some(deno_project_root) =
+                    detect_deno_project_root(fs.clone(), &worktree_root, &file_path).await
+                {
+                    vars.insert(
+                        TYPESCRIPT_DENO_PACKAGE_PATH_VARIABLE,
+                        deno_project_root.to_string_lossy().to_string(),
+                    );
+                }
+
+                let tool_versions = load_tool_versions(fs.as_ref(), &worktree_root).await;
+                if let Some(tool_versions) = &tool_versions {
+                    for (variable, version) in [
+                        (TYPESCRIPT_NODE_VERSION_VARIABLE, &tool_versions.node),
+                        (TYPESCRIPT_PNPM_VERSION_VARIABLE, &tool_versions.pnpm),
+                        (TYPESCRIPT_YARN_VERSION_VARIABLE, &tool_versions.yarn),
+                        (TYPESCRIPT_BUN_VERSION_VARIABLE, &tool_versions.bun),
+                    ] {
+                        if let Some(version) = version {
+                            vars.insert(variable, version.clone());
+                        }
+                    }
+                }
+
                 let package_json_data = task.await.log_err();
+                let runner = detect_package_manager(
+                    worktree_root,
+                    fs,
+                    package_json_data.clone(),
+                    tool_versions.as_ref(),
+                    runner_setting.as_deref(),
+                )
+                .await;
+                let pinned_version = package_json_data
+                    .as_ref()
+                    .and_then(|data| data.pinned_package_manager_version(&runner));
                 vars.insert(
-                    TYPESCRIPT_RUNNER_VARIABLE,
-                    detect_package_manager(worktree_root, fs, package_json_data.clone())
-                        .await
-                        .to_owned(),
+                    TYPESCRIPT_RUNNER_COMMAND_VARIABLE,
+                    runner_command(&runner, pinned_version),
                 );
+                if let Some(version) = pinned_version {
+                    vars.insert(TYPESCRIPT_RUNNER_VERSION_VARIABLE, version.to_owned());
+                }
+                vars.insert(TYPESCRIPT_RUNNER_VARIABLE, runner);
 
                 if let Some(package_json_data) = package_json_data {
                     if let Some(path) = package_json_data.jest_package_path {
@@ -474,6 +1107,16 @@ impl ContextProvider for TypeScriptContextProvider {
                         );
                     }
 
+                    if let Some((_, path)) = package_json_data.typescript_file_runner() {
+                        vars.insert(
+                            TYPESCRIPT_FILE_RUNNER_PACKAGE_PATH_VARIABLE,
+                            path.parent()
+                                .unwrap_or(Path::new(""))
+                                .to_string_lossy()
+                                .to_string(),
+                        );
+                    }
+
                     if let Some(path) = package_json_data.jasmine_package_path {
                         vars.insert(
                             TYPESCRIPT_JASMINE_PACKAGE_PATH_VARIABLE,
@@ -483,6 +1126,26 @@ impl ContextProvider for TypeScriptContextProvider {
                                 .to_string(),
                         );
                     }
+
+                    if let Some(path) = package_json_data.playwright_package_path {
+                        vars.insert(
+                            TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH_VARIABLE,
+                            path.parent()
+                                .unwrap_or(Path::new(""))
+                                .to_string_lossy()
+                                .to_string(),
+                        );
+                    }
+
+                    if let Some(path) = package_json_data.cypress_package_path {
+                        vars.insert(
+                            TYPESCRIPT_CYPRESS_PACKAGE_PATH_VARIABLE,
+                            path.parent()
+                                .unwrap_or(Path::new(""))
+                                .to_string_lossy()
+                                .to_string(),
+                        );
+                    }
                 }
             }
             Ok(vars)
@@ -755,17 +1418,25 @@ async fn handle_symlink(src_dir: PathBuf, dest_dir: PathBuf) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
 
+    use collections::HashMap;
     use gpui::{AppContext as _, BackgroundExecutor, TestAppContext};
     use language::language_settings;
-    use project::{FakeFs, Project};
+    use project::{FakeFs, Fs, Project};
     use serde_json::json;
     use task::TaskTemplates;
     use unindent::Unindent;
     use util::path;
 
-    use crate::typescript::{PackageJsonData, TypeScriptContextProvider};
+    use crate::typescript::{
+        PackageJsonData, ScriptFilter, ScriptLabelStyle, ToolVersions, TypeScriptContextProvider,
+        detect_deno_project_root, detect_package_manager, fill_deno_task_templates,
+        inherits_project_env, runner_command,
+    };
 
     #[gpui::test]
     async fn test_outline(cx: &mut TestAppContext) {
@@ -876,6 +1547,9 @@ mod tests {
                 "mocha": "1.0.0",
                 "vitest": "1.0.0"
             },
+            "devDependencies": {
+                "@playwright/test": "1.0.0"
+            },
             "scripts": {
                 "test": ""
             }
@@ -883,6 +1557,7 @@ mod tests {
         .to_string();
 
         let package_json_2 = json!({
+            "name": "@acme/sub",
             "devDependencies": {
                 "vitest": "2.0.0"
             },
@@ -905,7 +1580,7 @@ mod tests {
         )
         .await;
 
-        let provider = TypeScriptContextProvider::new();
+        let provider = TypeScriptContextProvider::new("TypeScript".into());
         let package_json_data = cx
             .update(|cx| {
                 provider.combined_package_json_data(
@@ -924,6 +1599,10 @@ mod tests {
                 mocha_package_path: Some(Path::new(path!("/root/package.json")).into()),
                 vitest_package_path: Some(Path::new(path!("/root/sub/package.json")).into()),
                 jasmine_package_path: None,
+                tsx_package_path: None,
+                ts_node_package_path: None,
+                playwright_package_path: Some(Path::new(path!("/root/package.json")).into()),
+                cypress_package_path: None,
                 scripts: [
                     (
                         Path::new(path!("/root/package.json")).into(),
@@ -936,12 +1615,25 @@ mod tests {
                 ]
                 .into_iter()
                 .collect(),
+                package_names: [(
+                    Path::new(path!("/root/sub/package.json")).into(),
+                    "@acme/sub".to_owned()
+                )]
+                .into_iter()
+                .collect(),
                 package_manager: None,
+                package_manager_version: None,
             }
         );
 
         let mut task_templates = TaskTemplates::default();
-        package_json_data.fill_task_templates(&mut task_templates);
+        package_json_data.fill_task_templates(
+            &mut task_templates,
+            ScriptLabelStyle::Short,
+            &ScriptFilter::default(),
+            path!("/root").as_ref(),
+            &HashMap::default(),
+        );
         let task_templates = task_templates
             .0
             .into_iter()
@@ -966,15 +1658,360 @@ mod tests {
                     "mocha test $ZED_SYMBOL".into(),
                     Some("$ZED_CUSTOM_TYPESCRIPT_MOCHA_PACKAGE_PATH".into()),
                 ),
+                (
+                    "playwright file test".into(),
+                    Some("$ZED_CUSTOM_TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH".into()),
+                ),
+                (
+                    "playwright test $ZED_SYMBOL".into(),
+                    Some("$ZED_CUSTOM_TYPESCRIPT_PLAYWRIGHT_PACKAGE_PATH".into()),
+                ),
                 (
                     "root/package.json > test".into(),
                     Some(path!("/root").into())
                 ),
                 (
-                    "sub/package.json > test".into(),
+                    "package.json (@acme/sub) > test".into(),
                     Some(path!("/root/sub").into())
                 ),
             ]
         );
+
+        let mut task_templates = TaskTemplates::default();
+        package_json_data.fill_task_templates(
+            &mut task_templates,
+            ScriptLabelStyle::RelativePath,
+            &ScriptFilter::default(),
+            path!("/root").as_ref(),
+            &HashMap::default(),
+        );
+        let script_labels = task_templates
+            .0
+            .into_iter()
+            .filter(|template| template.tags.contains(&"package-script".to_owned()))
+            .map(|template| template.label)
+            .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(
+            script_labels,
+            [
+                "package.json > test".to_owned(),
+                path!("sub/package.json > test").to_owned(),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_test_runner_config_discovery(
+        executor: BackgroundExecutor,
+        cx: &mut TestAppContext,
+    ) {
+        cx.update(|cx| {
+            settings::init(cx);
+            Project::init_settings(cx);
+            language_settings::init(cx);
+        });
+
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "package.json": json!({ "scripts": { "test": "" } }).to_string(),
+                "vitest.config.ts": "",
+                "packages": {
+                    "app": {
+                        "package.json": "{}",
+                        "jest.config.js": "",
+                        "src": { "app.test.ts": "" },
+                    },
+                },
+            }),
+        )
+        .await;
+
+        let provider = TypeScriptContextProvider::new("TypeScript".into());
+        let package_json_data = cx
+            .update(|cx| {
+                provider.combined_package_json_data(
+                    fs.clone(),
+                    path!("/root").as_ref(),
+                    "packages/app/src/app.test.ts".as_ref(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            package_json_data.jest_package_path.as_deref(),
+            Some(Path::new(path!("/root/packages/app/jest.config.js")))
+        );
+        assert_eq!(
+            package_json_data.vitest_package_path.as_deref(),
+            Some(Path::new(path!("/root/vitest.config.ts")))
+        );
+        assert_eq!(package_json_data.mocha_package_path, None);
+    }
+
+    #[test]
+    fn test_script_filter() {
+        let scripts = [
+            "build",
+            "prebuild",
+            "test",
+            "pretest",
+            "posttest",
+            "test:unit",
+            "preview",
+            "prettier",
+            "postcss",
+            "prebuild-assets",
+            "prepare",
+            "postinstall",
+            "lint:fix",
+        ];
+        let has_script = |script: &str| scripts.contains(&script);
+        let allowed = |filter: &ScriptFilter| {
+            scripts
+                .into_iter()
+                .filter(|script| filter.is_allowed(script, has_script))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            allowed(&ScriptFilter::default()),
+            [
+                "build",
+                "test",
+                "test:unit",
+                "preview",
+                "prettier",
+                "postcss",
+                "prebuild-assets",
+                "lint:fix",
+            ]
+        );
+
+        let exclude = ScriptFilter::from_settings(None, Some("lint*, "));
+        assert!(!exclude.is_allowed("lint:fix", has_script));
+        assert!(exclude.is_allowed("prepare", has_script));
+        assert!(exclude.is_allowed("pretest", has_script));
+
+        let no_exclude = ScriptFilter::from_settings(None, Some(""));
+        assert!(no_exclude.is_allowed("postinstall", has_script));
+
+        let include = ScriptFilter::from_settings(Some("test*,prepare"), Some("prepare"));
+        assert!(include.is_allowed("test:unit", has_script));
+        assert!(include.is_allowed("prepare", has_script));
+        assert!(!include.is_allowed("build", has_script));
+
+        let invalid = ScriptFilter::from_settings(Some("[test"), None);
+        assert!(invalid.is_allowed("build", has_script));
+        assert!(!invalid.is_allowed("prepare", has_script));
+    }
+
+    #[test]
+    fn test_typescript_file_runner_task() {
+        let package_json_data = |package_json: &str| {
+            PackageJsonData::new(
+                Path::new(path!("/root/package.json")).into(),
+                serde_json_lenient::from_str(package_json).unwrap(),
+            )
+        };
+        let run_file_tasks = |package_json_data: PackageJsonData| {
+            let mut task_templates = TaskTemplates::default();
+            package_json_data.fill_task_templates(
+                &mut task_templates,
+                ScriptLabelStyle::Short,
+                &ScriptFilter::default(),
+                path!("/root").as_ref(),
+                &HashMap::default(),
+            );
+            task_templates
+                .0
+                .into_iter()
+                .filter(|template| template.label == "Run TS file")
+                .map(|template| template.args)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run_file_tasks(package_json_data(
+                r#"{"devDependencies": {"ts-node": "10.9.2", "tsx": "4.7.0"}}"#
+            )),
+            vec![vec!["exec", "--", "tsx", "$ZED_FILE"]]
+        );
+        assert_eq!(
+            run_file_tasks(package_json_data(
+                r#"{"dependencies": {"ts-node": "10.9.2"}}"#
+            )),
+            vec![vec!["exec", "--", "ts-node", "$ZED_FILE"]]
+        );
+        assert!(run_file_tasks(package_json_data(r#"{"devDependencies": {}}"#)).is_empty());
+    }
+
+    #[test]
+    fn test_project_env_for_run_file_tasks() {
+        assert!(!inherits_project_env(&HashMap::default()));
+        assert!(!inherits_project_env(&HashMap::from_iter([(
+            "TYPESCRIPT_PROJECT_ENV".to_owned(),
+            "false".to_owned()
+        )])));
+        assert!(inherits_project_env(&HashMap::from_iter([(
+            "TYPESCRIPT_PROJECT_ENV".to_owned(),
+            "true".to_owned()
+        )])));
+
+        let package_json_data = PackageJsonData::new(
+            Path::new(path!("/root/package.json")).into(),
+            serde_json_lenient::from_str(
+                r#"{"scripts": {"build": "tsc"}, "devDependencies": {"tsx": "4.7.0"}}"#,
+            )
+            .unwrap(),
+        );
+        let project_env = HashMap::from_iter([("API_URL".to_owned(), "localhost".to_owned())]);
+        let mut task_templates = TaskTemplates::default();
+        package_json_data.fill_task_templates(
+            &mut task_templates,
+            ScriptLabelStyle::Short,
+            &ScriptFilter::default(),
+            path!("/root").as_ref(),
+            &project_env,
+        );
+        fill_deno_task_templates(&mut task_templates, &project_env);
+        let templates_with_env = task_templates
+            .0
+            .iter()
+            .filter(|template| template.env == project_env)
+            .map(|template| template.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(templates_with_env, ["Run TS file", "deno run file"]);
+    }
+
+    #[test]
+    fn test_parse_tool_versions() {
+        let tool_versions = ToolVersions::parse(
+            &r#"
+            # Pinned for CI
+            nodejs 20.11.1 system
+            pnpm 9.1.0 # latest 9.x
+            python 3.12.2
+            yarn
+            "#
+            .unindent(),
+        );
+        assert_eq!(
+            tool_versions,
+            ToolVersions {
+                node: Some("20.11.1".to_owned()),
+                pnpm: Some("9.1.0".to_owned()),
+                yarn: None,
+                bun: None,
+            }
+        );
+        assert_eq!(tool_versions.package_manager(), Some("pnpm"));
+    }
+
+    #[test]
+    fn test_pinned_package_manager() {
+        let package_json_data = PackageJsonData::new(
+            Path::new(path!("/root/package.json")).into(),
+            serde_json_lenient::from_str(r#"{ "packageManager": "pnpm@8.6.0+sha512.1a2b3c" }"#)
+                .unwrap(),
+        );
+        assert_eq!(package_json_data.package_manager, Some("pnpm"));
+        assert_eq!(
+            package_json_data.package_manager_version.as_deref(),
+            Some("8.6.0")
+        );
+
+        let pinned_version = package_json_data.pinned_package_manager_version("pnpm");
+        assert_eq!(pinned_version, Some("8.6.0"));
+        assert_eq!(runner_command("pnpm", pinned_version), "corepack pnpm");
+        // A runner chosen in the settings isn't the pinned one.
+        let pinned_version = package_json_data.pinned_package_manager_version("bun");
+        assert_eq!(pinned_version, None);
+        assert_eq!(runner_command("bun", pinned_version), "bun");
+
+        let package_json_data = PackageJsonData::new(
+            Path::new(path!("/root/package.json")).into(),
+            serde_json_lenient::from_str(r#"{ "packageManager": "yarn" }"#).unwrap(),
+        );
+        assert_eq!(package_json_data.package_manager, Some("yarn"));
+        assert_eq!(
+            package_json_data.pinned_package_manager_version("yarn"),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_detect_package_manager(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "bun": { "bun.lockb": "", "package.json": "{}" },
+                "pnpm": { "pnpm-lock.yaml": "", "package.json": "{}" },
+                "plain": { "package.json": "{}" },
+            }),
+        )
+        .await;
+        let fs: Arc<dyn Fs> = fs;
+
+        for (directory, runner_setting, expected_runner) in [
+            (path!("/root/bun"), None, "bun"),
+            (path!("/root/pnpm"), None, "pnpm"),
+            (path!("/root/plain"), None, "npm"),
+            (path!("/root/pnpm"), Some("bun"), "bun"),
+            (path!("/root/plain"), Some(" "), "npm"),
+        ] {
+            assert_eq!(
+                detect_package_manager(
+                    PathBuf::from(directory),
+                    fs.clone(),
+                    None,
+                    None,
+                    runner_setting,
+                )
+                .await,
+                expected_runner,
+                "unexpected runner for {directory} with setting {runner_setting:?}"
+            );
+        }
+    }
+
+    #[gpui::test]
+    async fn test_deno_workspace_member_discovery(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "deno.json": r#"{ "workspace": ["./packages/member"] }"#,
+                "packages": {
+                    "member": {
+                        "deno.jsonc": "// member config\n{ \"name\": \"@scope/member\" }",
+                        "src": { "mod.ts": "" },
+                    },
+                    "other": {
+                        "deno.json": "{}",
+                        "mod.ts": "",
+                    },
+                },
+                "scripts": { "build.ts": "" },
+            }),
+        )
+        .await;
+
+        for (file, expected_root) in [
+            ("packages/member/src/mod.ts", path!("/root/packages/member")),
+            ("packages/other/mod.ts", path!("/root")),
+            ("scripts/build.ts", path!("/root")),
+        ] {
+            let deno_project_root =
+                detect_deno_project_root(fs.clone(), path!("/root").as_ref(), file.as_ref()).await;
+            assert_eq!(
+                deno_project_root.as_deref(),
+                Some(Path::new(expected_root)),
+                "unexpected Deno project root for {file}"
+            );
+        }
     }
 }
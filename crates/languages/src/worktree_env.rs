@@ -0,0 +1,110 @@
+use collections::HashMap;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, AppContext as _, Entity, Task, WeakEntity};
+use parking_lot::Mutex;
+use project::{Fs, MTime, Worktree};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Files whose edits make direnv (and similar shell hooks) export a different environment.
+const ENVIRONMENT_FILE_NAMES: &[&str] = &[".envrc", ".env"];
+
+/// The login shell environment of each worktree root, as a terminal `cd`ed into it would see it.
+///
+/// `ContextProvider::associated_tasks` isn't given the project environment, so providers that
+/// need it to list their tasks load it through this instead.
+#[derive(Clone, Default)]
+pub(crate) struct WorktreeEnvironments(Arc<Mutex<HashMap<PathBuf, CachedEnvironment>>>);
+
+struct CachedEnvironment {
+    worktree: WeakEntity<Worktree>,
+    environment_files_mtimes: Vec<Option<MTime>>,
+    environment: Shared<Task<HashMap<String, String>>>,
+}
+
+impl fmt::Debug for WorktreeEnvironments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WorktreeEnvironments")
+            .field(&self.0.lock().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl WorktreeEnvironments {
+    /// Returns the environment of the worktree's root directory, capturing it at most once per
+    /// root even when asked concurrently.
+    ///
+    /// The capture is redone once the root's environment files change, and roots of released
+    /// worktrees are forgotten.
+    pub(crate) fn get(
+        &self,
+        fs: Arc<dyn Fs>,
+        worktree: &Entity<Worktree>,
+        cx: &App,
+    ) -> Task<HashMap<String, String>> {
+        self.0
+            .lock()
+            .retain(|_, cached| cached.worktree.upgrade().is_some());
+        let Some(worktree_root) = worktree.read(cx).root_dir() else {
+            return Task::ready(HashMap::default());
+        };
+
+        let environments = self.0.clone();
+        let worktree = worktree.downgrade();
+        let executor = cx.background_executor().clone();
+        cx.background_spawn(async move {
+            let environment_files_mtimes =
+                environment_files_mtimes(fs.as_ref(), &worktree_root).await;
+            let environment = {
+                let mut environments = environments.lock();
+                match environments.get(worktree_root.as_ref()) {
+                    Some(cached) if cached.environment_files_mtimes == environment_files_mtimes => {
+                        cached.environment.clone()
+                    }
+                    _ => {
+                        let environment = executor
+                            .spawn(load_shell_environment(worktree_root.to_path_buf()))
+                            .shared();
+                        environments.insert(
+                            worktree_root.to_path_buf(),
+                            CachedEnvironment {
+                                worktree,
+                                environment_files_mtimes,
+                                environment: environment.clone(),
+                            },
+                        );
+                        environment
+                    }
+                }
+            };
+            environment.await
+        })
+    }
+}
+
+async fn environment_files_mtimes(fs: &dyn Fs, worktree_root: &Path) -> Vec<Option<MTime>> {
+    let mut mtimes = Vec::with_capacity(ENVIRONMENT_FILE_NAMES.len());
+    for file_name in ENVIRONMENT_FILE_NAMES {
+        let metadata = fs.metadata(&worktree_root.join(file_name)).await;
+        mtimes.push(metadata.ok().flatten().map(|metadata| metadata.mtime));
+    }
+    mtimes
+}
+
+#[cfg(unix)]
+async fn load_shell_environment(directory: PathBuf) -> HashMap<String, String> {
+    use util::ResultExt as _;
+
+    smol::unblock(move || util::shell_env::capture(&directory))
+        .await
+        .log_err()
+        .unwrap_or_default()
+}
+
+#[cfg(not(unix))]
+async fn load_shell_environment(_: PathBuf) -> HashMap<String, String> {
+    HashMap::default()
+}
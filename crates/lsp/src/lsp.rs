@@ -1104,6 +1104,11 @@ impl LanguageServer {
         self.server_id
     }
 
+    /// Get the id of the language server's process, if Zed spawned it and it is still running.
+    pub fn process_id(&self) -> Option<u32> {
+        self.server.lock().as_ref().map(|server| server.id())
+    }
+
     /// Language server's binary information.
     pub fn binary(&self) -> &LanguageServerBinary {
         &self.binary
@@ -13,6 +13,15 @@ use task::{BuildTaskDefinition, DebugScenario, ShellBuilder, SpawnInTerminal, Ta
 
 pub(crate) struct CargoLocator;
 
+/// The position of the cargo subcommand in `args`, which follows a `+<toolchain>` override when
+/// there is one.
+fn cargo_action_index(args: &[String]) -> usize {
+    match args.first() {
+        Some(arg) if arg.starts_with('+') => 1,
+        _ => 0,
+    }
+}
+
 async fn find_best_executable(executables: &[String], test_name: &str) -> Option<String> {
     if executables.len() == 1 {
         return executables.first().cloned();
@@ -68,7 +77,8 @@ impl DapLocator for CargoLocator {
             return None;
         }
         let mut task_template = build_config.clone();
-        let cargo_action = task_template.args.first_mut()?;
+        let cargo_action_index = cargo_action_index(&task_template.args);
+        let cargo_action = task_template.args.get_mut(cargo_action_index)?;
         if cargo_action == "check" || cargo_action == "clean" {
             return None;
         }
@@ -145,7 +155,7 @@ impl DapLocator for CargoLocator {
 
         let is_test = build_config
             .args
-            .first()
+            .get(cargo_action_index(&build_config.args))
             .map_or(false, |arg| arg == "test" || arg == "t");
 
         let executables = output
@@ -215,3 +225,65 @@ impl DapLocator for CargoLocator {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    async fn scenario_args(args: &[&str]) -> Option<Vec<String>> {
+        let task = TaskTemplate {
+            label: "cargo".into(),
+            command: "cargo".into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+        let scenario = CargoLocator
+            .create_scenario(&task, "test label", &DebugAdapterName("CodeLLDB".into()))
+            .await?;
+        match scenario.build? {
+            BuildTaskDefinition::Template { task_template, .. } => Some(task_template.args),
+            BuildTaskDefinition::ByName(_) => None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_create_scenario_with_toolchain(_: &mut TestAppContext) {
+        assert_eq!(
+            scenario_args(&["+nightly", "run", "-p", "app"]).await,
+            Some(vec![
+                "+nightly".into(),
+                "build".into(),
+                "-p".into(),
+                "app".into()
+            ])
+        );
+        assert_eq!(
+            scenario_args(&["+nightly", "test", "-p", "app", "--", "tests::it_works"]).await,
+            Some(vec![
+                "+nightly".into(),
+                "test".into(),
+                "-p".into(),
+                "app".into(),
+                "--no-run".into(),
+                "--".into(),
+                "tests::it_works".into(),
+            ])
+        );
+        assert_eq!(scenario_args(&["+nightly", "check"]).await, None);
+        assert_eq!(scenario_args(&["+nightly", "clean"]).await, None);
+    }
+
+    #[gpui::test]
+    async fn test_create_scenario_without_toolchain(_: &mut TestAppContext) {
+        assert_eq!(
+            scenario_args(&["run"]).await,
+            Some(vec!["build".to_string()])
+        );
+        assert_eq!(
+            scenario_args(&["bench"]).await,
+            Some(vec!["bench".into(), "--no-run".into()])
+        );
+        assert_eq!(scenario_args(&["check"]).await, None);
+    }
+}
@@ -11,6 +11,8 @@ pub(crate) struct NodeLocator;
 
 const TYPESCRIPT_RUNNER_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER"));
+const TYPESCRIPT_RUNNER_COMMAND_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER_COMMAND"));
 
 #[async_trait]
 impl DapLocator for NodeLocator {
@@ -28,7 +30,14 @@ impl DapLocator for NodeLocator {
         if adapter.0.as_ref() != "JavaScript" {
             return None;
         }
-        if build_config.command != TYPESCRIPT_RUNNER_VARIABLE.template_value()
+        // The runner command may go through corepack, which can't be the runtime executable.
+        let runtime_executable =
+            if build_config.command == TYPESCRIPT_RUNNER_COMMAND_VARIABLE.template_value() {
+                TYPESCRIPT_RUNNER_VARIABLE.template_value()
+            } else {
+                build_config.command.clone()
+            };
+        if runtime_executable != TYPESCRIPT_RUNNER_VARIABLE.template_value()
             && build_config.command != "npm"
             && build_config.command != "pnpm"
             && build_config.command != "yarn"
@@ -41,7 +50,7 @@ impl DapLocator for NodeLocator {
             "type": "pwa-node",
             "args": build_config.args.clone(),
             "cwd": build_config.cwd.clone(),
-            "runtimeExecutable": build_config.command.clone(),
+            "runtimeExecutable": runtime_executable,
             "env": build_config.env.clone(),
             "runtimeArgs": ["--inspect-brk"],
             "console": "integratedTerminal",
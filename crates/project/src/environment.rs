@@ -57,6 +57,17 @@ impl ProjectEnvironment {
         cx.emit(ProjectEnvironmentEvent::ErrorsUpdated);
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn insert_environment_error(
+        &mut self,
+        abs_path: Arc<Path>,
+        error: EnvironmentErrorMessage,
+        cx: &mut Context<Self>,
+    ) {
+        self.environment_error_messages.insert(abs_path, error);
+        cx.emit(ProjectEnvironmentEvent::ErrorsUpdated);
+    }
+
     pub(crate) fn get_buffer_environment(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3144,11 +3144,12 @@ impl Repository {
                                 this.active_jobs.insert(
                                     job_id,
                                     JobInfo {
-                                        start: Instant::now(),
+                                        start: cx.background_executor().now(),
                                         message: s.clone(),
                                     },
                                 );
 
+                                cx.emit(JobsUpdated);
                                 cx.notify();
                             })
                             .ok();
@@ -3156,7 +3157,9 @@ impl Repository {
                         let result = job.await;
 
                         this.update(cx, |this, cx| {
-                            this.active_jobs.remove(&job_id);
+                            if this.active_jobs.remove(&job_id).is_some() {
+                                cx.emit(JobsUpdated);
+                            }
                             cx.notify();
                         })
                         .ok();
@@ -2,6 +2,7 @@ pub mod clangd_ext;
 pub mod json_language_server_ext;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
+mod server_resources;
 
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionResponse, CompletionSource,
@@ -103,6 +104,8 @@ use zedless::SilentError;
 
 pub use fs::*;
 pub use language::Location;
+pub use server_resources::LanguageServerResources;
+use server_resources::{SAMPLE_INTERVAL, ServerResourceSampler};
 pub use worktree::{
     Entry, EntryKind, FS_WATCH_LATENCY, File, LocalWorktree, PathChange, ProjectEntryId,
     UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
@@ -171,6 +174,10 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    /// The usage of the servers with pending work, as last sampled by `sample_server_resources`.
+    server_resources: HashMap<LanguageServerId, LanguageServerResources>,
+    /// Only runs while some server has pending work.
+    sample_server_resources: Option<Task<()>>,
 }
 
 impl LocalLspStore {
@@ -422,25 +429,6 @@ impl LocalLspStore {
         .get(&adapter.name)
         .and_then(|s| s.binary.clone());
 
-        if settings.as_ref().is_some_and(|b| b.path.is_some()) {
-            let settings = settings.unwrap();
-
-            return cx.spawn(async move |_| {
-                let mut env = delegate.shell_env().await;
-                env.extend(settings.env.unwrap_or_default());
-
-                Ok(LanguageServerBinary {
-                    path: PathBuf::from(&settings.path.unwrap()),
-                    env: Some(env),
-                    arguments: settings
-                        .arguments
-                        .unwrap_or_default()
-                        .iter()
-                        .map(Into::into)
-                        .collect(),
-                })
-            });
-        }
         let lsp_binary_options = LanguageServerBinaryOptions {
             allow_path_lookup: !settings
                 .as_ref()
@@ -450,6 +438,36 @@ impl LocalLspStore {
         };
         let toolchains = self.toolchain_store.read(cx).as_language_toolchain_store();
         cx.spawn(async move |cx| {
+            if let Some(settings) = settings.as_ref()
+                && let Some(path) = settings.path.as_ref()
+            {
+                let mut env = delegate.shell_env().await;
+                env.extend(settings.env.clone().unwrap_or_default());
+
+                let binary = LanguageServerBinary {
+                    path: PathBuf::from(path),
+                    env: Some(env),
+                    arguments: settings
+                        .arguments
+                        .clone()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(Into::into)
+                        .collect(),
+                };
+                if adapter
+                    .adapter
+                    .check_configured_binary(&binary, delegate.as_ref())
+                    .await
+                {
+                    return Ok(binary);
+                }
+                log::warn!(
+                    "ignoring unusable {} binary configured at {path:?}",
+                    adapter.name
+                );
+            }
+
             let binary_result = adapter
                 .clone()
                 .get_language_server_command(delegate.clone(), toolchains, lsp_binary_options, cx)
@@ -508,7 +526,7 @@ impl LocalLspStore {
                                 params,
                                 None,
                                 DiagnosticSourceKind::Pushed,
-                                &adapter.disk_based_diagnostic_sources,
+                                &disk_based_diagnostic_sources(&adapter, cx),
                                 |_, diagnostic, cx| match diagnostic.source_kind {
                                     DiagnosticSourceKind::Other | DiagnosticSourceKind::Pushed => {
                                         adapter.retain_old_diagnostic(diagnostic, cx)
@@ -3583,6 +3601,13 @@ pub enum LspStoreEvent {
         edits: Vec<(lsp::Range, Snippet)>,
         most_recent_edit: clock::Lamport,
     },
+    /// Formatting or applying code actions on save failed for the given buffers.
+    FormattingFailed {
+        buffer_ids: Vec<BufferId>,
+        message: String,
+    },
+    /// The last formatting failure was resolved by a successful run, or dismissed.
+    FormattingFailureCleared,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -3764,6 +3789,8 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                server_resources: HashMap::default(),
+                sample_server_resources: None,
             }),
             last_formatting_failure: None,
             downstream_client: None,
@@ -4151,6 +4178,69 @@ impl LspStore {
         handle
     }
 
+    /// Starts sampling the processes of the local servers with pending work every
+    /// [`SAMPLE_INTERVAL`], off the main thread, so that status UIs only read the last sample when
+    /// rendering. Sampling stops, and the samples are cleared, once no server has pending work.
+    fn start_sampling_server_resources(&mut self, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        if local.sample_server_resources.is_some() {
+            return;
+        }
+        local.sample_server_resources = Some(cx.spawn(async move |this, cx| {
+            let mut sampler = ServerResourceSampler::new();
+            loop {
+                cx.background_executor().timer(SAMPLE_INTERVAL).await;
+                let Ok(processes) = this.update(cx, |this, _| {
+                    let processes = this.busy_server_processes();
+                    if processes.is_empty() {
+                        if let Some(local) = this.as_local_mut() {
+                            local.server_resources.clear();
+                            local.sample_server_resources = None;
+                        }
+                    }
+                    processes
+                }) else {
+                    break;
+                };
+                if processes.is_empty() {
+                    break;
+                }
+                let (returned_sampler, resources) = cx
+                    .background_spawn(async move {
+                        let resources = sampler.sample(processes);
+                        (sampler, resources)
+                    })
+                    .await;
+                sampler = returned_sampler;
+                let updated = this.update(cx, |this, _| {
+                    if let Some(local) = this.as_local_mut() {
+                        local.server_resources = resources;
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// The process IDs of the local language servers with pending work.
+    fn busy_server_processes(&self) -> Vec<(LanguageServerId, u32)> {
+        let Some(local) = self.as_local() else {
+            return Vec::new();
+        };
+        self.language_server_statuses
+            .iter()
+            .filter(|(_, status)| !status.pending_work.is_empty())
+            .filter_map(|(server_id, _)| {
+                let server = local.running_language_server_for_id(*server_id)?;
+                Some((*server_id, server.process_id()?))
+            })
+            .collect()
+    }
+
     fn maintain_buffer_languages(
         languages: Arc<LanguageRegistry>,
         cx: &mut Context<Self>,
@@ -4796,6 +4886,10 @@ impl LspStore {
         push_to_history: bool,
         cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<ProjectTransaction>> {
+        let buffer_ids = buffers
+            .iter()
+            .map(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
         if let Some(_) = self.as_local() {
             cx.spawn(async move |lsp_store, cx| {
                 let buffers = buffers.into_iter().collect::<Vec<_>>();
@@ -4807,8 +4901,8 @@ impl LspStore {
                     cx,
                 )
                 .await;
-                lsp_store.update(cx, |lsp_store, _| {
-                    lsp_store.update_last_formatting_failure(&result);
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.update_last_formatting_failure(&buffer_ids, &result, cx);
                 })?;
                 result
             })
@@ -4828,8 +4922,8 @@ impl LspStore {
                     })
                     .await
                     .and_then(|result| result.transaction.context("missing transaction"));
-                lsp_store.update(cx, |lsp_store, _| {
-                    lsp_store.update_last_formatting_failure(&result);
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.update_last_formatting_failure(&buffer_ids, &result, cx);
                 })?;
 
                 let transaction_response = result?;
@@ -6462,8 +6556,8 @@ impl LspStore {
                     let adapter = lsp_store.language_server_adapter_for_id(server_id);
                     let disk_based_sources = adapter
                         .as_ref()
-                        .map(|adapter| adapter.disk_based_diagnostic_sources.as_slice())
-                        .unwrap_or(&[]);
+                        .map(|adapter| disk_based_diagnostic_sources(adapter, cx))
+                        .unwrap_or_default();
                     match diagnostics {
                         PulledDiagnostics::Unchanged { result_id } => {
                             lsp_store
@@ -6476,7 +6570,7 @@ impl LspStore {
                                     },
                                     Some(result_id),
                                     DiagnosticSourceKind::Pulled,
-                                    disk_based_sources,
+                                    &disk_based_sources,
                                     |_, _, _| true,
                                     cx,
                                 )
@@ -6496,7 +6590,7 @@ impl LspStore {
                                     },
                                     result_id,
                                     DiagnosticSourceKind::Pulled,
-                                    disk_based_sources,
+                                    &disk_based_sources,
                                     |buffer, old_diagnostic, _| match old_diagnostic.source_kind {
                                         DiagnosticSourceKind::Pulled => {
                                             buffer.remote_id() != buffer_id
@@ -8882,6 +8976,33 @@ impl LspStore {
             .map(|(key, value)| (*key, value))
     }
 
+    /// Returns an owned copy of all in-progress language server work, with the most recently
+    /// registered servers first and, within each server, the most recently updated work first.
+    pub fn pending_work_snapshot(&self) -> Vec<PendingWorkSnapshot> {
+        let server_resources = self.as_local().map(|local| &local.server_resources);
+        self.language_server_statuses()
+            .rev()
+            .flat_map(|(server_id, status)| {
+                let resources = server_resources
+                    .and_then(|server_resources| server_resources.get(&server_id))
+                    .copied();
+                let mut pending_work = status.pending_work.iter().collect::<Vec<_>>();
+                pending_work.sort_by_key(|(_, progress)| Reverse(progress.last_update_at));
+                pending_work
+                    .into_iter()
+                    .map(move |(token, progress)| PendingWorkSnapshot {
+                        server_id,
+                        token: token.clone(),
+                        title: progress.title.clone(),
+                        percentage: progress.percentage,
+                        message: progress.message.clone(),
+                        cancellable: progress.is_cancellable,
+                        resources,
+                    })
+            })
+            .collect()
+    }
+
     pub(super) fn did_rename_entry(
         &self,
         worktree_id: WorktreeId,
@@ -9151,6 +9272,7 @@ impl LspStore {
     ) {
         if let Some(status) = self.language_server_statuses.get_mut(&language_server_id) {
             status.pending_work.insert(token.clone(), progress.clone());
+            self.start_sampling_server_resources(cx);
             cx.notify();
         }
         cx.emit(LspStoreEvent::LanguageServerUpdate {
@@ -9204,6 +9326,7 @@ impl LspStore {
         }
 
         if did_update {
+            self.start_sampling_server_resources(cx);
             cx.emit(LspStoreEvent::LanguageServerUpdate {
                 language_server_id,
                 name: self
@@ -9783,8 +9906,10 @@ impl LspStore {
         self.last_formatting_failure.as_deref()
     }
 
-    pub fn reset_last_formatting_failure(&mut self) {
-        self.last_formatting_failure = None;
+    pub fn reset_last_formatting_failure(&mut self, cx: &mut Context<Self>) {
+        if self.last_formatting_failure.take().is_some() {
+            cx.emit(LspStoreEvent::FormattingFailureCleared);
+        }
     }
 
     pub fn environment_for_buffer(
@@ -9823,6 +9948,7 @@ impl LspStore {
                     (buffer_handle, buffer_abs_path, buffer.remote_id())
                 })
                 .collect::<Vec<_>>();
+            let buffer_ids = buffers.iter().map(|(_, _, id)| *id).collect::<Vec<_>>();
 
             cx.spawn(async move |lsp_store, cx| {
                 let mut formattable_buffers = Vec::with_capacity(buffers.len());
@@ -9864,8 +9990,8 @@ impl LspStore {
 
                 zlog::trace!(logger => "Formatting completed with result {:?}", result.as_ref().map(|_| "<project-transaction>"));
 
-                lsp_store.update(cx, |lsp_store, _| {
-                    lsp_store.update_last_formatting_failure(&result);
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.update_last_formatting_failure(&buffer_ids, &result, cx);
                 })?;
 
                 result
@@ -9882,6 +10008,10 @@ impl LspStore {
                 }
             }
 
+            let buffer_ids = buffers
+                .iter()
+                .map(|buffer| buffer.read(cx).remote_id())
+                .collect::<Vec<_>>();
             let buffer_store = self.buffer_store();
             cx.spawn(async move |lsp_store, cx| {
                 zlog::trace!(logger => "Sending remote format request");
@@ -9901,8 +10031,8 @@ impl LspStore {
 
                 zlog::trace!(logger => "Remote format request resolved to {:?}", result.as_ref().map(|_| "<project_transaction>"));
 
-                lsp_store.update(cx, |lsp_store, _| {
-                    lsp_store.update_last_formatting_failure(&result);
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.update_last_formatting_failure(&buffer_ids, &result, cx);
                 })?;
 
                 let transaction_response = result?;
@@ -11180,14 +11310,23 @@ impl LspStore {
         })
     }
 
-    fn update_last_formatting_failure<T>(&mut self, formatting_result: &anyhow::Result<T>) {
+    fn update_last_formatting_failure<T>(
+        &mut self,
+        buffer_ids: &[BufferId],
+        formatting_result: &anyhow::Result<T>,
+        cx: &mut Context<Self>,
+    ) {
         match &formatting_result {
-            Ok(_) => self.last_formatting_failure = None,
+            Ok(_) => self.reset_last_formatting_failure(cx),
             Err(error) => {
                 let error_string = format!("{error:#}");
                 log::error!("Formatting failed: {error_string}");
-                self.last_formatting_failure
-                    .replace(error_string.lines().join(" "));
+                let message = error_string.lines().join(" ");
+                self.last_formatting_failure = Some(message.clone());
+                cx.emit(LspStoreEvent::FormattingFailed {
+                    buffer_ids: buffer_ids.to_vec(),
+                    message,
+                });
             }
         }
     }
@@ -11291,8 +11430,8 @@ impl LspStore {
             let adapter = self.language_server_adapter_for_id(server_id);
             let disk_based_sources = adapter
                 .as_ref()
-                .map(|adapter| adapter.disk_based_diagnostic_sources.as_slice())
-                .unwrap_or(&[]);
+                .map(|adapter| disk_based_diagnostic_sources(adapter, cx))
+                .unwrap_or_default();
 
             match diagnostics {
                 PulledDiagnostics::Unchanged { result_id } => {
@@ -11305,7 +11444,7 @@ impl LspStore {
                         },
                         Some(result_id),
                         DiagnosticSourceKind::Pulled,
-                        disk_based_sources,
+                        &disk_based_sources,
                         |_, _, _| true,
                         cx,
                     )
@@ -11324,7 +11463,7 @@ impl LspStore {
                         },
                         result_id,
                         DiagnosticSourceKind::Pulled,
-                        disk_based_sources,
+                        &disk_based_sources,
                         |buffer, old_diagnostic, cx| match old_diagnostic.source_kind {
                             DiagnosticSourceKind::Pulled => {
                                 let buffer_url = File::from_dyn(buffer.file())
@@ -11567,6 +11706,20 @@ fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     }
 }
 
+/// The diagnostic sources the adapter declares as disk-based, along with any extra ones
+/// configured for the server in `lsp.<server>.extra_disk_diagnostic_sources`.
+fn disk_based_diagnostic_sources(adapter: &CachedLspAdapter, cx: &App) -> Vec<String> {
+    let mut sources = adapter.disk_based_diagnostic_sources.clone();
+    if let Some(settings) = ProjectSettings::get_global(cx).lsp.get(&adapter.name) {
+        for source in &settings.extra_disk_diagnostic_sources {
+            if !sources.contains(source) {
+                sources.push(source.clone());
+            }
+        }
+    }
+    sources
+}
+
 async fn populate_labels_for_completions(
     new_completions: Vec<CoreCompletion>,
     language: Option<Arc<Language>>,
@@ -11960,6 +12113,20 @@ pub struct LanguageServerProgress {
     pub last_update_at: Instant,
 }
 
+/// A single piece of in-progress language server work, detached from the [`LspStore`] so that
+/// it can be held across frames by status UIs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingWorkSnapshot {
+    pub server_id: LanguageServerId,
+    pub token: String,
+    pub title: Option<String>,
+    pub percentage: Option<usize>,
+    pub message: Option<String>,
+    pub cancellable: bool,
+    /// The usage of the server process, when it runs locally on a platform that exposes it.
+    pub resources: Option<LanguageServerResources>,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize)]
 pub struct DiagnosticSummary {
     pub error_count: usize,
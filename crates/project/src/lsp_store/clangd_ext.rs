@@ -6,7 +6,7 @@ use language::{CachedLspAdapter, Diagnostic, DiagnosticSourceKind};
 use lsp::LanguageServer;
 use util::ResultExt as _;
 
-use crate::LspStore;
+use crate::{LspStore, lsp_store::disk_based_diagnostic_sources};
 
 pub const CLANGD_SERVER_NAME: &str = "clangd";
 const INACTIVE_REGION_MESSAGE: &str = "inactive region";
@@ -86,7 +86,7 @@ pub fn register_notifications(
                         mapped_diagnostics,
                         None,
                         DiagnosticSourceKind::Pushed,
-                        &adapter.disk_based_diagnostic_sources,
+                        &disk_based_diagnostic_sources(&adapter, cx),
                         |_, diag, _| !is_inactive_region(diag),
                         cx,
                     )
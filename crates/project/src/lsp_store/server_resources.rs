@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use lsp::LanguageServerId;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// CPU usage is measured between two samples, so sampling more often makes it meaningless.
+pub(crate) const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Memory and CPU usage of a language server process.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LanguageServerResources {
+    /// Resident set size, in bytes.
+    pub memory: u64,
+    /// Usage since the previous sample, in percent of a single core.
+    pub cpu_usage: f32,
+}
+
+/// Samples the processes of local language servers, for the platforms where sysinfo supports it.
+pub(crate) struct ServerResourceSampler {
+    system: System,
+}
+
+impl ServerResourceSampler {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+        }
+    }
+
+    /// Returns the usage of the given server processes since the previous sample, which should be
+    /// taken [`SAMPLE_INTERVAL`] earlier. Processes that can't be inspected are left out.
+    pub fn sample(
+        &mut self,
+        processes: impl IntoIterator<Item = (LanguageServerId, u32)>,
+    ) -> HashMap<LanguageServerId, LanguageServerResources> {
+        if !sysinfo::IS_SUPPORTED_SYSTEM {
+            return HashMap::default();
+        }
+
+        let processes = processes
+            .into_iter()
+            .map(|(server_id, pid)| (server_id, Pid::from_u32(pid)))
+            .collect::<Vec<_>>();
+        let pids = processes.iter().map(|(_, pid)| *pid).collect::<Vec<_>>();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+        processes
+            .into_iter()
+            .filter_map(|(server_id, pid)| {
+                let process = self.system.process(pid)?;
+                Some((
+                    server_id,
+                    LanguageServerResources {
+                        memory: process.memory(),
+                        cpu_usage: process.cpu_usage(),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
@@ -133,8 +133,8 @@ pub use task_inventory::{
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
     DiagnosticSummary, LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
-    LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
-    SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    LanguageServerResources, LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
+    PendingWorkSnapshot, SERVER_PROGRESS_THROTTLE_TIMEOUT,
 };
 pub use toolchain_store::ToolchainStore;
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -314,6 +314,11 @@ pub enum Event {
     RefreshCodeLens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    FormattingFailed {
+        buffer_ids: Vec<BufferId>,
+        message: String,
+    },
+    FormattingFailureCleared,
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
     AgentLocationChanged,
 }
@@ -1779,6 +1784,22 @@ impl Project {
         });
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn insert_environment_error(
+        &mut self,
+        abs_path: Arc<Path>,
+        error: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.environment.update(cx, |environment, cx| {
+            environment.insert_environment_error(
+                abs_path,
+                EnvironmentErrorMessage(error.into()),
+                cx,
+            );
+        });
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn has_open_buffer(&self, path: impl Into<ProjectPath>, cx: &App) -> bool {
         self.buffer_store
@@ -2878,6 +2899,14 @@ impl Project {
                     cx.emit(Event::SnippetEdit(*buffer_id, edits.clone()))
                 }
             }
+            LspStoreEvent::FormattingFailed {
+                buffer_ids,
+                message,
+            } => cx.emit(Event::FormattingFailed {
+                buffer_ids: buffer_ids.clone(),
+                message: message.clone(),
+            }),
+            LspStoreEvent::FormattingFailureCleared => cx.emit(Event::FormattingFailureCleared),
         }
     }
 
@@ -2902,6 +2931,7 @@ impl Project {
                 });
                 cx.emit(Event::DisconnectedFromSshRemote);
             }
+            remote::SshRemoteEvent::PortForwardsReestablished { .. } => {}
         }
     }
 
@@ -3261,13 +3291,17 @@ impl Project {
         self.lsp_store.read(cx).language_server_statuses()
     }
 
+    pub fn pending_work_snapshot(&self, cx: &App) -> Vec<PendingWorkSnapshot> {
+        self.lsp_store.read(cx).pending_work_snapshot()
+    }
+
     pub fn last_formatting_failure<'a>(&self, cx: &'a App) -> Option<&'a str> {
         self.lsp_store.read(cx).last_formatting_failure()
     }
 
     pub fn reset_last_formatting_failure(&self, cx: &mut App) {
         self.lsp_store
-            .update(cx, |store, _| store.reset_last_formatting_failure());
+            .update(cx, |store, cx| store.reset_last_formatting_failure(cx));
     }
 
     pub fn reload_buffers(
@@ -418,6 +418,11 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// How long (in milliseconds) a git operation has to run before it's shown in the
+    /// activity indicator, so that quick operations don't flash in the status bar.
+    ///
+    /// Default: 300
+    pub activity_delay_ms: Option<u64>,
 }
 
 impl GitSettings {
@@ -439,6 +444,10 @@ impl GitSettings {
         }
     }
 
+    pub fn activity_delay(&self) -> Duration {
+        Duration::from_millis(self.activity_delay_ms.unwrap_or(300))
+    }
+
     pub fn show_inline_commit_summary(&self) -> bool {
         match self.inline_blame {
             Some(InlineBlameSettings {
@@ -515,6 +524,12 @@ pub struct LspSettings {
     /// Default: true
     #[serde(default = "default_true")]
     pub enable_lsp_tasks: bool,
+    /// Additional diagnostic sources to treat as disk-based, i.e. produced by a
+    /// separate check that runs on save, on top of the ones the language server
+    /// adapter declares. Their diagnostics are kept until the next check finishes.
+    /// Default: []
+    #[serde(default)]
+    pub extra_disk_diagnostic_sources: Vec<String>,
 }
 
 impl Default for LspSettings {
@@ -524,6 +539,7 @@ impl Default for LspSettings {
             initialization_options: None,
             settings: None,
             enable_lsp_tasks: true,
+            extra_disk_diagnostic_sources: Vec::new(),
         }
     }
 }
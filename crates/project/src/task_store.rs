@@ -34,6 +34,7 @@ pub struct StoreState {
     buffer_store: WeakEntity<BufferStore>,
     worktree_store: Entity<WorktreeStore>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    variable_overrides: Option<TaskVariables>,
 }
 
 enum StoreMode {
@@ -175,6 +176,7 @@ impl TaskStore {
             buffer_store,
             toolchain_store,
             worktree_store,
+            variable_overrides: None,
         })
     }
 
@@ -196,6 +198,7 @@ impl TaskStore {
             buffer_store,
             toolchain_store,
             worktree_store,
+            variable_overrides: None,
         })
     }
 
@@ -205,30 +208,47 @@ impl TaskStore {
         location: Location,
         cx: &mut App,
     ) -> Task<Option<TaskContext>> {
-        match self {
-            TaskStore::Functional(state) => match &state.mode {
-                StoreMode::Local { environment, .. } => local_task_context_for_location(
-                    state.worktree_store.clone(),
-                    state.toolchain_store.clone(),
-                    environment.clone(),
-                    captured_variables,
-                    location,
-                    cx,
-                ),
-                StoreMode::Remote {
-                    upstream_client,
-                    project_id,
-                } => remote_task_context_for_location(
-                    *project_id,
-                    upstream_client.clone(),
-                    state.worktree_store.clone(),
-                    captured_variables,
-                    location,
-                    state.toolchain_store.clone(),
-                    cx,
-                ),
-            },
-            TaskStore::Noop => Task::ready(None),
+        let TaskStore::Functional(state) = self else {
+            return Task::ready(None);
+        };
+        let task_context = match &state.mode {
+            StoreMode::Local { environment, .. } => local_task_context_for_location(
+                state.worktree_store.clone(),
+                state.toolchain_store.clone(),
+                environment.clone(),
+                captured_variables,
+                location,
+                cx,
+            ),
+            StoreMode::Remote {
+                upstream_client,
+                project_id,
+            } => remote_task_context_for_location(
+                *project_id,
+                upstream_client.clone(),
+                state.worktree_store.clone(),
+                captured_variables,
+                location,
+                state.toolchain_store.clone(),
+                cx,
+            ),
+        };
+        let Some(variable_overrides) = state.variable_overrides.clone() else {
+            return task_context;
+        };
+        cx.background_spawn(async move {
+            let mut task_context = task_context.await?;
+            task_context.task_variables.extend(variable_overrides);
+            Some(task_context)
+        })
+    }
+
+    /// Sets task variables that take precedence over the ones computed for each location,
+    /// such as the ones configured for a project opened on a remote server.
+    pub fn set_variable_overrides(&mut self, variable_overrides: TaskVariables) {
+        if let TaskStore::Functional(state) = self {
+            state.variable_overrides =
+                (!variable_overrides.is_empty()).then_some(variable_overrides);
         }
     }
 
@@ -56,6 +56,8 @@ pub struct SshDetails {
     pub ssh_command: SshCommand,
     pub envs: Option<HashMap<String, String>>,
     pub path_style: PathStyle,
+    /// The shell configured for this connection, if it shouldn't be the remote login shell.
+    pub shell: Option<String>,
 }
 
 impl Project {
@@ -83,11 +85,13 @@ impl Project {
         if let Some(ssh_client) = &self.ssh_client {
             let ssh_client = ssh_client.read(cx);
             if let Some((SshArgs { arguments, envs }, path_style)) = ssh_client.ssh_info() {
+                let connection_options = ssh_client.connection_options();
                 return Some(SshDetails {
-                    host: ssh_client.connection_options().host.clone(),
+                    host: connection_options.host,
                     ssh_command: SshCommand { arguments },
                     envs,
                     path_style,
+                    shell: connection_options.shell,
                 });
             }
         }
@@ -276,6 +280,7 @@ impl Project {
                         ssh_command,
                         envs,
                         path_style,
+                        shell,
                     }) => {
                         log::debug!("Connecting to a remote server: {ssh_command:?}");
 
@@ -286,9 +291,10 @@ impl Project {
                         env.entry("TERM".to_string())
                             .or_insert_with(|| "xterm-256color".to_string());
 
+                        let login_shell_args = vec!["-l".to_string()];
                         let (program, args) = wrap_for_ssh(
                             &ssh_command,
-                            None,
+                            shell.as_ref().map(|shell| (shell, &login_shell_args)),
                             path.as_deref(),
                             env,
                             None,
@@ -339,6 +345,7 @@ impl Project {
                         ssh_command,
                         envs,
                         path_style,
+                        ..
                     }) => {
                         log::debug!("Connecting to a remote server: {ssh_command:?}");
                         env.entry("TERM".to_string())
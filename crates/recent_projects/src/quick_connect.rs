@@ -0,0 +1,411 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window,
+};
+use paths::{global_ssh_config_file, user_ssh_config_file};
+use picker::{Picker, PickerDelegate};
+use project::Fs;
+use remote::SshConnectionOptions;
+use settings::Settings;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+use crate::{
+    RemoteServerProjects, open_ssh_project, ssh_config::parse_ssh_config_hosts_with_includes,
+    ssh_connections::SshSettings,
+};
+
+/// A host that can be connected to from the quick connect picker.
+struct QuickConnectCandidate {
+    label: SharedString,
+    connection_options: SshConnectionOptions,
+    from_ssh_config: bool,
+}
+
+/// A keyboard-driven picker that fuzzy matches saved remote servers and ssh_config hosts, and
+/// opens the home directory of the selected one.
+pub struct QuickConnect {
+    picker: Entity<Picker<QuickConnectDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ModalView for QuickConnect {}
+
+impl QuickConnect {
+    pub fn open(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let weak_workspace = cx.entity().downgrade();
+        let fs = workspace.project().read(cx).fs().clone();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(weak_workspace, fs, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let read_ssh_config = RemoteServerProjects::read_ssh_config_override(&workspace, cx)
+            .unwrap_or(SshSettings::get_global(cx).read_ssh_config);
+        let delegate = QuickConnectDelegate {
+            workspace: workspace.clone(),
+            candidates: saved_candidates(cx),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+
+        if read_ssh_config {
+            cx.spawn_in(window, async move |this, cx| {
+                let hosts = load_ssh_config_hosts(fs.as_ref()).await;
+                this.update_in(cx, |this, window, cx| {
+                    this.picker.update(cx, |picker, cx| {
+                        picker.delegate.add_ssh_config_hosts(hosts);
+                        picker.refresh(window, cx);
+                    })
+                })
+                .ok();
+            })
+            .detach();
+        }
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for QuickConnect {}
+
+impl Focusable for QuickConnect {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for QuickConnect {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+fn saved_candidates(cx: &App) -> Vec<QuickConnectCandidate> {
+    SshSettings::get_global(cx)
+        .ssh_connections()
+        .map(|connection| {
+            let connection_options = SshConnectionOptions::from(connection);
+            QuickConnectCandidate {
+                label: connection_options.connection_string().into(),
+                connection_options,
+                from_ssh_config: false,
+            }
+        })
+        .collect()
+}
+
+async fn load_ssh_config_hosts(fs: &dyn Fs) -> BTreeSet<String> {
+    let mut hosts = BTreeSet::new();
+    for config_file in [user_ssh_config_file(), global_ssh_config_file().to_owned()] {
+        let Ok(contents) = fs.load(&config_file).await else {
+            continue;
+        };
+        let config_dir = config_file.parent().unwrap_or(Path::new("/"));
+        hosts.extend(parse_ssh_config_hosts_with_includes(&contents, config_dir, fs).await);
+    }
+    hosts
+}
+
+struct QuickConnectDelegate {
+    workspace: WeakEntity<Workspace>,
+    candidates: Vec<QuickConnectCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl QuickConnectDelegate {
+    fn add_ssh_config_hosts(&mut self, hosts: BTreeSet<String>) {
+        let new_candidates = hosts
+            .into_iter()
+            .filter(|host| {
+                !self
+                    .candidates
+                    .iter()
+                    .any(|candidate| &candidate.connection_options.host == host)
+            })
+            .map(|host| QuickConnectCandidate {
+                label: host.clone().into(),
+                connection_options: SshConnectionOptions {
+                    host,
+                    ..SshConnectionOptions::default()
+                },
+                from_ssh_config: true,
+            })
+            .collect::<Vec<_>>();
+        self.candidates.extend(new_candidates);
+    }
+
+    /// The connection of the selected match, which is opened on confirm.
+    fn selected_connection(&self) -> Option<SshConnectionOptions> {
+        let hit = self.matches.get(self.selected_index)?;
+        let candidate = self.candidates.get(hit.candidate_id)?;
+        Some(candidate.connection_options.clone())
+    }
+}
+
+impl PickerDelegate for QuickConnectDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Connect to a remote host…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.trim().to_owned();
+        let smart_case = query.chars().any(|c| c.is_uppercase());
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| StringMatchCandidate::new(id, &candidate.label))
+            .collect::<Vec<_>>();
+        let background = cx.background_executor().clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = fuzzy::match_strings(
+                candidates.as_slice(),
+                &query,
+                smart_case,
+                true,
+                100,
+                &Default::default(),
+                background,
+            )
+            .await;
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(connection_options) = self.selected_connection() else {
+            return;
+        };
+        let Some(app_state) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.app_state().clone())
+            .ok()
+        else {
+            return;
+        };
+        let replace_window = window.window_handle().downcast::<Workspace>();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let result = open_ssh_project(
+                connection_options,
+                vec![PathBuf::from("~")],
+                app_state,
+                OpenOptions {
+                    replace_window,
+                    ..OpenOptions::default()
+                },
+                cx,
+            )
+            .await;
+            if let Err(e) = result {
+                log::error!("Failed to connect: {e:#}");
+                cx.prompt(
+                    gpui::PromptLevel::Critical,
+                    "Failed to connect",
+                    Some(&e.to_string()),
+                    &["Ok"],
+                )
+                .await
+                .ok();
+            }
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, _cx: &mut Context<Picker<Self>>) {}
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.candidates.is_empty() {
+            "No remote servers or ssh_config hosts found".into()
+        } else {
+            "No matches".into()
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let candidate = self.candidates.get(hit.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    candidate.label.clone(),
+                    hit.positions.clone(),
+                ))
+                .when(candidate.from_ssh_config, |item| {
+                    item.end_slot(
+                        Label::new("ssh_config")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, UpdateGlobal as _, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+    use workspace::AppState;
+
+    use super::*;
+    use crate::ssh_connections::SshConnection;
+
+    #[gpui::test]
+    async fn test_quick_connect_filters_and_confirms(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<SshSettings>(cx, |settings| {
+                    settings.read_ssh_config = Some(false);
+                    settings.ssh_connections = Some(vec![
+                        SshConnection {
+                            host: "prod.example.com".into(),
+                            username: Some("deploy".to_owned()),
+                            ..SshConnection::default()
+                        },
+                        SshConnection {
+                            host: "staging.example.com".into(),
+                            ..SshConnection::default()
+                        },
+                    ]);
+                });
+            });
+        });
+
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+        let picker = workspace.update_in(cx, |workspace, window, cx| {
+            QuickConnect::open(workspace, window, cx);
+            workspace
+                .active_modal::<QuickConnect>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        cx.run_until_parked();
+        picker.read_with(cx, |picker, _| {
+            assert_eq!(
+                match_labels(picker),
+                ["deploy@prod.example.com", "staging.example.com"]
+            );
+        });
+
+        picker.update_in(cx, |picker, window, cx| {
+            picker.set_query("stag", window, cx)
+        });
+        cx.run_until_parked();
+        picker.read_with(cx, |picker, _| {
+            assert_eq!(match_labels(picker), ["staging.example.com"]);
+            assert_eq!(
+                picker
+                    .delegate
+                    .selected_connection()
+                    .map(|connection| connection.host),
+                Some("staging.example.com".to_owned())
+            );
+        });
+
+        picker.update_in(cx, |picker, window, cx| {
+            picker.set_query("nowhere", window, cx)
+        });
+        cx.run_until_parked();
+        picker.read_with(cx, |picker, _| {
+            assert!(match_labels(picker).is_empty());
+            assert!(picker.delegate.selected_connection().is_none());
+        });
+        cx.dispatch_action(menu::Confirm);
+        workspace.update(cx, |workspace, cx| {
+            assert!(
+                workspace.active_modal::<QuickConnect>(cx).is_some(),
+                "Confirming without a match should keep the modal open"
+            );
+        });
+    }
+
+    fn match_labels(picker: &Picker<QuickConnectDelegate>) -> Vec<&str> {
+        picker
+            .delegate
+            .matches
+            .iter()
+            .map(|hit| picker.delegate.candidates[hit.candidate_id].label.as_ref())
+            .collect()
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -1,4 +1,5 @@
 pub mod disconnected_overlay;
+mod quick_connect;
 mod remote_servers;
 mod ssh_config;
 mod ssh_connections;
@@ -9,13 +10,14 @@ use disconnected_overlay::DisconnectedOverlay;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
-    Subscription, Task, WeakEntity, Window,
+    Subscription, Task, WeakEntity, Window, actions,
 };
 use ordered_float::OrderedFloat;
 use picker::{
     Picker, PickerDelegate,
     highlighted_match_with_paths::{HighlightedMatch, HighlightedMatchWithPaths},
 };
+pub use quick_connect::QuickConnect;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
 pub use ssh_connections::SshSettings;
@@ -26,11 +28,31 @@ use std::{
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
 use util::{ResultExt, paths::PathExt};
 use workspace::{
-    CloseIntent, HistoryManager, ModalView, OpenOptions, SerializedWorkspaceLocation, WORKSPACE_DB,
-    Workspace, WorkspaceId, with_active_or_new_workspace,
+    CloseIntent, HistoryManager, ModalView, OpenOptions, SerializedWorkspaceLocation, Toast,
+    WORKSPACE_DB, Workspace, WorkspaceId, notifications::NotificationId,
+    with_active_or_new_workspace,
 };
 use zed_actions::{OpenRecent, OpenRemote};
 
+actions!(
+    projects,
+    [
+        /// Reconnects every open window whose remote SSH connection has been lost.
+        ReconnectAllRemoteSessions,
+        /// Fuzzy finds a saved remote server or ssh_config host and opens its home directory.
+        QuickConnectRemote,
+        /// Moves the selected remote server one position up in the list of saved servers.
+        MoveRemoteServerUp,
+        /// Moves the selected remote server one position down in the list of saved servers.
+        MoveRemoteServerDown,
+        /// Reopens the project that was opened most recently on the selected remote server.
+        ReopenLastRemoteProject,
+        /// Toggles whether the remote servers of the current window list the hosts from ssh_config,
+        /// overriding the `read_ssh_config` setting for this window only.
+        ToggleReadSshConfigForWindow
+    ]
+);
+
 pub fn init(cx: &mut App) {
     SshSettings::register(cx);
     cx.on_action(|open_recent: &OpenRecent, cx| {
@@ -64,9 +86,94 @@ pub fn init(cx: &mut App) {
         });
     });
 
+    cx.on_action(|_: &ReconnectAllRemoteSessions, cx| reconnect_all_remote_sessions(cx));
+    cx.on_action(|_: &QuickConnectRemote, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            QuickConnect::open(workspace, window, cx);
+        });
+    });
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|_, _: &ToggleReadSshConfigForWindow, _, cx| {
+            let workspace = cx.entity().downgrade();
+            let read_ssh_config = RemoteServerProjects::read_ssh_config_override(&workspace, cx)
+                .unwrap_or(SshSettings::get_global(cx).read_ssh_config);
+            RemoteServerProjects::override_read_ssh_config(&workspace, Some(!read_ssh_config), cx);
+        });
+    })
+    .detach();
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
+fn reconnect_all_remote_sessions(cx: &mut App) {
+    struct RemoteSessionReconnected;
+
+    let disconnected_windows = cx
+        .windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>())
+        .filter_map(|window| {
+            let workspace = window.read(cx).ok()?;
+            let project = workspace.project().read(cx);
+            if !project.is_via_ssh() || !project.is_disconnected(cx) {
+                return None;
+            }
+            let connection_options = project.ssh_connection_options(cx)?;
+            let paths = workspace
+                .serialized_ssh_project()?
+                .paths
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>();
+            Some((
+                window,
+                connection_options,
+                paths,
+                workspace.app_state().clone(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for (window, connection_options, paths, app_state) in disconnected_windows {
+        let host = connection_options.connection_string();
+        cx.spawn(async move |cx| {
+            let result = open_ssh_project(
+                connection_options,
+                paths,
+                app_state,
+                OpenOptions {
+                    replace_window: Some(window),
+                    ..OpenOptions::default()
+                },
+                cx,
+            )
+            .await;
+            let message = match result {
+                Ok(()) => format!("Reconnected to {host}"),
+                Err(error) => {
+                    log::error!("Failed to reconnect to {host}: {error:#}");
+                    format!("Failed to reconnect to {host}: {error}")
+                }
+            };
+            window
+                .update(cx, |workspace, _, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::composite::<RemoteSessionReconnected>(
+                                SharedString::from(host),
+                            ),
+                            message,
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                })
+                .log_err();
+        })
+        .detach();
+    }
+}
+
 pub struct RecentProjects {
     pub picker: Entity<Picker<RecentProjectsDelegate>>,
     rem_width: f32,
@@ -744,6 +851,53 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_read_ssh_config_override(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let fs = app_state.fs.clone();
+        fs.as_fake()
+            .insert_file(
+                paths::global_ssh_config_file(),
+                b"Host from-config".to_vec(),
+            )
+            .await;
+
+        let open_remote_servers = |workspace: &WindowHandle<Workspace>, cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |workspace, window, cx| {
+                    let handle = cx.entity().downgrade();
+                    let fs = fs.clone();
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        RemoteServerProjects::new(false, fs, window, handle, cx)
+                    });
+                    workspace.active_modal::<RemoteServerProjects>(cx).unwrap()
+                })
+                .unwrap()
+        };
+
+        let project = Project::test(fs.clone(), [], cx).await;
+        let overridden = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+        let project = Project::test(fs.clone(), [], cx).await;
+        let other = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+
+        cx.dispatch_action(overridden.into(), ToggleReadSshConfigForWindow);
+        let overridden_servers = open_remote_servers(&overridden, cx);
+        let other_servers = open_remote_servers(&other, cx);
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            assert!(overridden_servers.read(cx).ssh_config_servers().is_empty());
+            assert_eq!(
+                other_servers
+                    .read(cx)
+                    .ssh_config_servers()
+                    .iter()
+                    .collect::<Vec<_>>(),
+                ["from-config"]
+            );
+        });
+    }
+
     fn open_recent_projects(
         workspace: &WindowHandle<Workspace>,
         cx: &mut TestAppContext,
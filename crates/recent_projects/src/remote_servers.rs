@@ -1,12 +1,14 @@
 use std::any::Any;
 use std::borrow::Cow;
 use std::collections::BTreeSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic;
 use std::sync::atomic::AtomicUsize;
+use std::time::Duration;
 
+use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
 use file_finder::OpenPathDelegate;
 use futures::FutureExt;
@@ -20,26 +22,28 @@ use gpui::Task;
 use gpui::WeakEntity;
 use gpui::canvas;
 use gpui::{
-    AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
-    PromptLevel, ScrollHandle, Window,
+    Action, Animation, AnimationExt, AnyElement, App, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Global, PromptLevel, ScrollHandle, Transformation, Window, percentage,
 };
 use paths::global_ssh_config_file;
 use paths::user_ssh_config_file;
 use picker::Picker;
 use project::Fs;
 use project::Project;
-use remote::ssh_session::ConnectionIdentifier;
-use remote::{SshConnectionOptions, SshRemoteClient};
+use project::terminals::{SshCommand, wrap_for_ssh};
+use remote::ssh_session::{ConnectionIdentifier, SshArgs};
+use remote::{ConnectionState, SshConnectionOptions, SshRemoteClient};
 use settings::Settings;
 use settings::SettingsStore;
 use settings::update_settings_file;
 use settings::watch_config_file;
 use smol::stream::StreamExt as _;
+use task::{SpawnInTerminal, TaskId};
 use ui::Navigable;
 use ui::NavigableEntry;
 use ui::{
-    IconButtonShape, List, ListItem, ListSeparator, Modal, ModalHeader, Scrollbar, ScrollbarState,
-    Section, Tooltip, prelude::*,
+    IconButtonShape, Indicator, KeyBinding, List, ListItem, ListSeparator, Modal, ModalHeader,
+    Scrollbar, ScrollbarState, Section, Tooltip, prelude::*,
 };
 use util::{
     ResultExt,
@@ -53,7 +57,14 @@ use workspace::{
     open_ssh_project_with_existing_connection,
 };
 
+use crate::MoveRemoteServerDown;
+use crate::MoveRemoteServerUp;
+use crate::ReopenLastRemoteProject;
+use crate::ssh_config::SshConfigProxy;
+use crate::ssh_config::load_ssh_config_with_includes;
 use crate::ssh_config::parse_ssh_config_hosts;
+use crate::ssh_config::parse_ssh_config_proxies;
+use crate::ssh_config::proxy_args_for_host;
 use crate::ssh_connections::RemoteSettingsContent;
 use crate::ssh_connections::SshConnection;
 use crate::ssh_connections::SshConnectionHeader;
@@ -72,10 +83,24 @@ pub struct RemoteServerProjects {
     retained_connections: Vec<Entity<SshRemoteClient>>,
     ssh_config_updates: Task<()>,
     ssh_config_servers: BTreeSet<SharedString>,
+    /// The proxies configured in ssh_config, for the connections created from its hosts.
+    ssh_config_proxies: Vec<SshConfigProxy>,
     create_new_window: bool,
+    /// Whether opening a project leaves the modal open, so that several projects can be opened
+    /// in a row.
+    keep_open: bool,
+    /// A server that was just moved, along with the position of the focused entry within it,
+    /// so that focus can follow the server once the list is rebuilt from the new settings.
+    pending_server_focus: Option<(SshConnection, usize)>,
     _subscription: Subscription,
 }
 
+/// Per-workspace overrides of the global `read_ssh_config` setting.
+#[derive(Default)]
+struct ReadSshConfigOverrides(Vec<(WeakEntity<Workspace>, bool)>);
+
+impl Global for ReadSshConfigOverrides {}
+
 struct CreateRemoteServer {
     address_editor: Entity<Editor>,
     address_error: Option<SharedString>,
@@ -98,11 +123,15 @@ impl CreateRemoteServer {
     }
 }
 
+const MODAL_WIDTH: f32 = 34.;
+const MAX_PROJECT_PICKER_WIDTH: f32 = 60.;
+
 struct ProjectPicker {
     connection_string: SharedString,
     nickname: Option<SharedString>,
     picker: Entity<Picker<OpenPathDelegate>>,
     _path_task: Shared<Task<Option<()>>>,
+    _picker_subscription: Subscription,
 }
 
 struct EditNicknameState {
@@ -132,6 +161,219 @@ impl EditNicknameState {
     }
 }
 
+struct EditShellState {
+    index: usize,
+    editor: Entity<Editor>,
+}
+
+impl EditShellState {
+    fn new(index: usize, window: &mut Window, cx: &mut App) -> Self {
+        let this = Self {
+            index,
+            editor: cx.new(|cx| Editor::single_line(window, cx)),
+        };
+        let starting_text = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(index)
+            .and_then(|state| state.shell.clone())
+            .filter(|text| !text.is_empty());
+        this.editor.update(cx, |this, cx| {
+            this.set_placeholder_text(
+                "Shell for terminals on this server, e.g. /usr/bin/zsh (leave empty for the login shell)",
+                cx,
+            );
+            if let Some(starting_text) = starting_text {
+                this.set_text(starting_text, window, cx);
+            }
+        });
+        this.editor.focus_handle(cx).focus(window);
+        this
+    }
+}
+
+struct EditConnectionState {
+    index: usize,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+impl EditConnectionState {
+    fn new(index: usize, window: &mut Window, cx: &mut App) -> Self {
+        let this = Self {
+            index,
+            editor: cx.new(|cx| Editor::single_line(window, cx)),
+            error: None,
+        };
+        let starting_text = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(index)
+            .map(|connection| SshConnectionOptions::from(connection).command_line());
+        this.editor.update(cx, |this, cx| {
+            this.set_placeholder_text("ssh user@example -p 2222", cx);
+            if let Some(starting_text) = starting_text {
+                this.set_text(starting_text, window, cx);
+            }
+        });
+        this.editor.focus_handle(cx).focus(window);
+        this
+    }
+}
+
+struct OpenRemotePathState {
+    index: usize,
+    connection: SshConnection,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+impl OpenRemotePathState {
+    fn new(
+        index: usize,
+        connection: SshConnection,
+        starting_text: Option<String>,
+        error: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let this = Self {
+            index,
+            connection,
+            editor: cx.new(|cx| Editor::single_line(window, cx)),
+            error,
+        };
+        this.editor.update(cx, |this, cx| {
+            this.set_placeholder_text("Absolute path of a folder on this server, e.g. ~/src", cx);
+            if let Some(starting_text) = starting_text {
+                this.set_text(starting_text, window, cx);
+            }
+        });
+        this.editor.focus_handle(cx).focus(window);
+        this
+    }
+}
+
+fn last_username_key(host: &str) -> String {
+    format!("ssh-last-username-{host}")
+}
+
+/// The username last used to successfully add a server at `host`, for prefilling connections
+/// that leave it out.
+fn last_username_for_host(host: &str) -> Option<String> {
+    KEY_VALUE_STORE
+        .read_kvp(&last_username_key(host))
+        .log_err()
+        .flatten()
+}
+
+/// Fills in the username last used for the host of `connection_options` when it's left out.
+fn prefill_username(connection_options: &mut SshConnectionOptions) {
+    if connection_options.username.is_none() {
+        connection_options.username = last_username_for_host(&connection_options.host);
+    }
+}
+
+fn remember_username_for_host(host: String, username: String, cx: &App) {
+    cx.background_spawn(async move {
+        KEY_VALUE_STORE
+            .write_kvp(last_username_key(&host), username)
+            .await
+            .log_err();
+    })
+    .detach();
+}
+
+fn render_connection_preview(options: &SshConnectionOptions) -> impl IntoElement {
+    let endpoint = |host: &Option<String>, port: u16| {
+        format!("{}:{port}", host.as_deref().unwrap_or("localhost"))
+    };
+    let fields = [
+        ("Host", Some(options.host.clone())),
+        ("User", options.username.clone()),
+        ("Port", options.port.map(|port| port.to_string())),
+        (
+            "Arguments",
+            options
+                .args
+                .as_ref()
+                .filter(|args| !args.is_empty())
+                .map(|args| args.join(" ")),
+        ),
+        (
+            "Port forwards",
+            options
+                .port_forwards
+                .as_ref()
+                .filter(|forwards| !forwards.is_empty())
+                .map(|forwards| {
+                    forwards
+                        .iter()
+                        .map(|forward| {
+                            format!(
+                                "{} → {}",
+                                endpoint(&forward.local_host, forward.local_port),
+                                endpoint(&forward.remote_host, forward.remote_port)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }),
+        ),
+    ];
+
+    v_flex()
+        .p_2()
+        .w_full()
+        .gap_0p5()
+        .children(fields.into_iter().filter_map(|(name, value)| {
+            Some(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(format!("{name}:"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(value?).size(LabelSize::Small)),
+            )
+        }))
+}
+
+/// Remembers `paths` as the project opened most recently on the saved server described by
+/// `connection_options`.
+fn record_last_opened_project(
+    settings: &mut RemoteSettingsContent,
+    connection_options: &SshConnectionOptions,
+    paths: Vec<String>,
+) {
+    let server = settings
+        .ssh_connections
+        .iter_mut()
+        .flatten()
+        .find(|connection| connection.matches(connection_options));
+    if let Some(server) = server {
+        server.last_opened_project = Some(paths);
+    }
+}
+
+/// Removes `project` from the saved server, and forgets it as the server's last opened project.
+fn remove_ssh_project(server: &mut SshConnection, project: &SshProject) {
+    server.projects.remove(project);
+    if server.last_opened_project.as_ref() == Some(&project.paths) {
+        server.last_opened_project = None;
+    }
+}
+
+/// Whether `path` can be resolved on the remote without knowing its working directory.
+fn is_absolute_remote_path(path: &str) -> bool {
+    let mut chars = path.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('/'), _, _) | (Some('~'), None, _) => true,
+        (Some('~'), Some('/'), _) => true,
+        (Some(drive), Some(':'), None | Some('\\') | Some('/')) => drive.is_ascii_alphabetic(),
+        _ => false,
+    }
+}
+
 impl Focusable for ProjectPicker {
     fn focus_handle(&self, cx: &App) -> FocusHandle {
         self.picker.focus_handle(cx)
@@ -155,9 +397,7 @@ impl ProjectPicker {
         let delegate = file_finder::OpenPathDelegate::new(tx, lister, false, path_style);
 
         let picker = cx.new(|cx| {
-            let picker = Picker::uniform_list(delegate, window, cx)
-                .width(rems(34.))
-                .modal(false);
+            let picker = Picker::uniform_list(delegate, window, cx).modal(false);
             picker.set_query(home_dir.to_string(), window, cx);
             picker
         });
@@ -203,7 +443,13 @@ impl ProjectPicker {
                                     .as_mut()
                                     .and_then(|connections| connections.get_mut(ix))
                                 {
-                                    server.projects.insert(SshProject { paths });
+                                    if !server.projects.iter().any(|project| project.paths == paths)
+                                    {
+                                        server.projects.insert(SshProject {
+                                            paths,
+                                            ..SshProject::default()
+                                        });
+                                    }
                                 }
                             }
                         });
@@ -227,26 +473,49 @@ impl ProjectPicker {
                     .await
                     .log_err();
 
-                    this.update(cx, |_, cx| {
-                        cx.emit(DismissEvent);
+                    this.update(cx, |this, cx| {
+                        if this.keep_open {
+                            this.mode = Mode::default_mode(&this.ssh_config_servers, cx);
+                            cx.notify();
+                        } else {
+                            cx.emit(DismissEvent);
+                        }
                     })
                     .ok();
                     Some(())
                 }
             })
             .shared();
-        cx.new(|_| Self {
-            _path_task,
-            picker,
-            connection_string,
-            nickname,
+        cx.new(|cx| {
+            let _picker_subscription = cx.observe(&picker, |_, _, cx| cx.notify());
+            Self {
+                _path_task,
+                picker,
+                connection_string,
+                nickname,
+                _picker_subscription,
+            }
         })
     }
+
+    /// Widens the picker to fit the directory being browsed, so that deep remote paths stay
+    /// readable, while keeping it at least as wide as the rest of the modal.
+    fn width(&self, cx: &App) -> Rems {
+        // Leave room for the names of the entries listed under the typed directory.
+        const ENTRY_NAME_ALLOWANCE: usize = 20;
+        const REMS_PER_CHAR: f32 = 0.5;
+
+        let query_len = self.picker.read(cx).query(cx).chars().count();
+        let width = (query_len + ENTRY_NAME_ALLOWANCE) as f32 * REMS_PER_CHAR;
+        rems(width.clamp(MODAL_WIDTH, MAX_PROJECT_PICKER_WIDTH))
+    }
 }
 
 impl gpui::Render for ProjectPicker {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_loading = self.picker.read(cx).delegate.is_loading();
         v_flex()
+            .w(self.width(cx))
             .child(
                 SshConnectionHeader {
                     connection_string: self.connection_string.clone(),
@@ -255,6 +524,33 @@ impl gpui::Render for ProjectPicker {
                 }
                 .render(window, cx),
             )
+            .when(is_loading, |this| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .gap_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Icon::new(IconName::ArrowCircle)
+                                .size(IconSize::Small)
+                                .color(Color::Muted)
+                                .with_animation(
+                                    "remote-paths-loading",
+                                    Animation::new(Duration::from_secs(2)).repeat(),
+                                    |icon, delta| {
+                                        icon.transform(Transformation::rotate(percentage(delta)))
+                                    },
+                                ),
+                        )
+                        .child(
+                            Label::new("Loading remote directories…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+            })
             .child(
                 div()
                     .border_t_1()
@@ -283,6 +579,23 @@ impl RemoteEntry {
         matches!(self, Self::Project { .. })
     }
 
+    /// The keyboard-navigable entries of this server, in the order they are rendered.
+    fn navigable_entries(&self) -> Vec<&NavigableEntry> {
+        match self {
+            Self::Project {
+                open_folder,
+                projects,
+                configure,
+                ..
+            } => projects
+                .iter()
+                .map(|(entry, _)| entry)
+                .chain([open_folder, configure])
+                .collect(),
+            Self::SshConfig { open_folder, .. } => vec![open_folder],
+        }
+    }
+
     fn connection(&self) -> Cow<'_, SshConnection> {
         match self {
             Self::Project { connection, .. } => Cow::Borrowed(connection),
@@ -308,16 +621,20 @@ impl DefaultState {
         let add_new_server = NavigableEntry::new(&handle, cx);
 
         let ssh_settings = SshSettings::get_global(cx);
-        let read_ssh_config = ssh_settings.read_ssh_config;
 
         let mut servers: Vec<RemoteEntry> = ssh_settings
             .ssh_connections()
             .map(|connection| {
                 let open_folder = NavigableEntry::new(&handle, cx);
                 let configure = NavigableEntry::new(&handle, cx);
-                let projects = connection
-                    .projects
-                    .iter()
+                // The most recently opened project is listed first, to get back to it quickly.
+                let (mut projects, others): (Vec<_>, Vec<_>) =
+                    connection.projects.iter().partition(|project| {
+                        connection.last_opened_project.as_ref() == Some(&project.paths)
+                    });
+                projects.extend(others);
+                let projects = projects
+                    .into_iter()
                     .map(|project| (NavigableEntry::new(&handle, cx), project.clone()))
                     .collect();
                 RemoteEntry::Project {
@@ -329,20 +646,17 @@ impl DefaultState {
             })
             .collect();
 
-        if read_ssh_config {
-            let mut extra_servers_from_config = ssh_config_servers.clone();
-            for server in &servers {
-                if let RemoteEntry::Project { connection, .. } = server {
-                    extra_servers_from_config.remove(&connection.host);
-                }
-            }
-            servers.extend(extra_servers_from_config.into_iter().map(|host| {
-                RemoteEntry::SshConfig {
+        // `ssh_config_servers` is kept empty whenever ssh_config reading is disabled, be it
+        // globally or for this workspace only.
+        let extra_servers_from_config = extra_ssh_config_hosts(ssh_config_servers, &servers);
+        servers.extend(
+            extra_servers_from_config
+                .into_iter()
+                .map(|host| RemoteEntry::SshConfig {
                     open_folder: NavigableEntry::new(&handle, cx),
                     host,
-                }
-            }));
-        }
+                }),
+        );
 
         Self {
             scrollbar,
@@ -352,16 +666,85 @@ impl DefaultState {
     }
 }
 
+/// Returns the ssh_config hosts that should be listed on their own, as the ones a Zed connection
+/// already uses would be listed twice. Host names are compared case-insensitively, as ssh does.
+/// `Host` patterns never get here, as only concrete hosts are read from ssh_config.
+fn extra_ssh_config_hosts(
+    ssh_config_servers: &BTreeSet<SharedString>,
+    servers: &[RemoteEntry],
+) -> BTreeSet<SharedString> {
+    ssh_config_servers
+        .iter()
+        .filter(|ssh_config_host| {
+            !servers.iter().any(|server| match server {
+                RemoteEntry::Project { connection, .. } => {
+                    connection.host.eq_ignore_ascii_case(ssh_config_host)
+                }
+                RemoteEntry::SshConfig { .. } => false,
+            })
+        })
+        .cloned()
+        .collect()
+}
+
 #[derive(Clone)]
 struct ViewServerOptionsState {
     server_index: usize,
     connection: SshConnection,
-    entries: [NavigableEntry; 4],
+    entries: ServerOptionsEntries,
+}
+
+/// The entries of the server options view, one per option.
+#[derive(Clone)]
+struct ServerOptionsEntries {
+    edit_nickname: NavigableEntry,
+    edit_connection: NavigableEntry,
+    edit_shell: NavigableEntry,
+    open_terminal: NavigableEntry,
+    copy_server_address: NavigableEntry,
+    forget_credentials: NavigableEntry,
+    prune_missing_projects: NavigableEntry,
+    remove_server: NavigableEntry,
+    go_back: NavigableEntry,
+}
+
+impl ServerOptionsEntries {
+    fn new(cx: &App) -> Self {
+        Self {
+            edit_nickname: NavigableEntry::focusable(cx),
+            edit_connection: NavigableEntry::focusable(cx),
+            edit_shell: NavigableEntry::focusable(cx),
+            open_terminal: NavigableEntry::focusable(cx),
+            copy_server_address: NavigableEntry::focusable(cx),
+            forget_credentials: NavigableEntry::focusable(cx),
+            prune_missing_projects: NavigableEntry::focusable(cx),
+            remove_server: NavigableEntry::focusable(cx),
+            go_back: NavigableEntry::focusable(cx),
+        }
+    }
+
+    /// The entries in the order they're navigated.
+    fn into_ordered(self) -> [NavigableEntry; 9] {
+        [
+            self.edit_nickname,
+            self.edit_connection,
+            self.edit_shell,
+            self.open_terminal,
+            self.copy_server_address,
+            self.forget_credentials,
+            self.prune_missing_projects,
+            self.remove_server,
+            self.go_back,
+        ]
+    }
 }
 enum Mode {
     Default(DefaultState),
     ViewServerOptions(ViewServerOptionsState),
     EditNickname(EditNicknameState),
+    EditShell(EditShellState),
+    EditConnection(EditConnectionState),
+    OpenRemotePath(OpenRemotePathState),
     ProjectPicker(Entity<ProjectPicker>),
     CreateRemoteServer(CreateRemoteServer),
 }
@@ -380,7 +763,9 @@ impl RemoteServerProjects {
         cx: &mut Context<Self>,
     ) -> Self {
         let focus_handle = cx.focus_handle();
-        let mut read_ssh_config = SshSettings::get_global(cx).read_ssh_config;
+        let read_ssh_config_override = Self::read_ssh_config_override(&workspace, cx);
+        let mut read_ssh_config =
+            read_ssh_config_override.unwrap_or(SshSettings::get_global(cx).read_ssh_config);
         let ssh_config_updates = if read_ssh_config {
             spawn_ssh_config_watch(fs.clone(), cx)
         } else {
@@ -395,6 +780,9 @@ impl RemoteServerProjects {
 
         let _subscription =
             cx.observe_global_in::<SettingsStore>(window, move |recent_projects, _, cx| {
+                if read_ssh_config_override.is_some() {
+                    return;
+                }
                 let new_read_ssh_config = SshSettings::get_global(cx).read_ssh_config;
                 if read_ssh_config != new_read_ssh_config {
                     read_ssh_config = new_read_ssh_config;
@@ -402,6 +790,7 @@ impl RemoteServerProjects {
                         recent_projects.ssh_config_updates = spawn_ssh_config_watch(fs.clone(), cx);
                     } else {
                         recent_projects.ssh_config_servers.clear();
+                        recent_projects.ssh_config_proxies.clear();
                         recent_projects.ssh_config_updates = Task::ready(());
                     }
                 }
@@ -414,11 +803,47 @@ impl RemoteServerProjects {
             retained_connections: Vec::new(),
             ssh_config_updates,
             ssh_config_servers: BTreeSet::new(),
+            ssh_config_proxies: Vec::new(),
             create_new_window,
+            keep_open: false,
+            pending_server_focus: None,
             _subscription,
         }
     }
 
+    /// Forces ssh_config reading on or off for remote server modals opened in the given
+    /// workspace, leaving the global `read_ssh_config` setting in effect for other windows.
+    /// Passing `None` removes the override.
+    pub fn override_read_ssh_config(
+        workspace: &WeakEntity<Workspace>,
+        read_ssh_config: Option<bool>,
+        cx: &mut App,
+    ) {
+        let overrides = &mut cx.default_global::<ReadSshConfigOverrides>().0;
+        overrides.retain(|(overridden_workspace, _)| {
+            overridden_workspace != workspace && overridden_workspace.upgrade().is_some()
+        });
+        if let Some(read_ssh_config) = read_ssh_config {
+            overrides.push((workspace.clone(), read_ssh_config));
+        }
+    }
+
+    /// The hosts read from ssh_config, empty when reading it is turned off.
+    pub(crate) fn ssh_config_servers(&self) -> &BTreeSet<SharedString> {
+        &self.ssh_config_servers
+    }
+
+    pub(crate) fn read_ssh_config_override(
+        workspace: &WeakEntity<Workspace>,
+        cx: &App,
+    ) -> Option<bool> {
+        cx.try_global::<ReadSshConfigOverrides>()?
+            .0
+            .iter()
+            .find(|(overridden_workspace, _)| overridden_workspace == workspace)
+            .map(|(_, read_ssh_config)| *read_ssh_config)
+    }
+
     pub fn project_picker(
         create_new_window: bool,
         ix: usize,
@@ -460,7 +885,10 @@ impl RemoteServerProjects {
         }
 
         let connection_options = match SshConnectionOptions::parse_command_line(&input) {
-            Ok(c) => c,
+            Ok(mut c) => {
+                prefill_username(&mut c);
+                c
+            }
             Err(e) => {
                 self.mode = Mode::CreateRemoteServer(CreateRemoteServer {
                     address_editor: editor,
@@ -471,6 +899,64 @@ impl RemoteServerProjects {
                 return;
             }
         };
+
+        let Some(timeout) = SshSettings::get_global(cx).reachability_timeout() else {
+            self.connect_new_ssh_server(editor, connection_options, window, cx);
+            return;
+        };
+        let checking = cx.spawn_in(window, {
+            let editor = editor.clone();
+            async move |this, cx| {
+                let reachable = connection_options.check_reachable(timeout).await;
+                this.update_in(cx, |this, window, cx| {
+                    // The address may have been edited or the modal closed in the meantime.
+                    let Mode::CreateRemoteServer(state) = &this.mode else {
+                        return;
+                    };
+                    if state.address_editor != editor {
+                        return;
+                    }
+                    match reachable {
+                        Ok(()) => {
+                            this.connect_new_ssh_server(editor, connection_options, window, cx)
+                        }
+                        Err(e) => {
+                            editor.update(cx, |editor, _| editor.set_read_only(false));
+                            this.mode = Mode::CreateRemoteServer(CreateRemoteServer {
+                                address_editor: editor,
+                                address_error: Some(e.to_string().into()),
+                                ssh_prompt: None,
+                                _creating: None,
+                            });
+                        }
+                    }
+                    cx.notify();
+                })
+                .log_err()
+            }
+        });
+
+        editor.update(cx, |this, _| {
+            this.set_read_only(true);
+        });
+        self.mode = Mode::CreateRemoteServer(CreateRemoteServer {
+            address_editor: editor,
+            address_error: None,
+            ssh_prompt: None,
+            _creating: Some(checking),
+        });
+        cx.notify();
+    }
+
+    /// Connects to a server that was just entered in the address editor, saving it once the
+    /// connection succeeds.
+    fn connect_new_ssh_server(
+        &mut self,
+        editor: Entity<Editor>,
+        connection_options: SshConnectionOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         let ssh_prompt = cx.new(|cx| SshPrompt::new(&connection_options, window, cx));
 
         let connection = connect_over_ssh(
@@ -487,6 +973,14 @@ impl RemoteServerProjects {
             match connection.await {
                 Some(Some(client)) => this
                     .update_in(cx, |this, window, cx| {
+                        if let Some(username) = connection_options.username.clone() {
+                            remember_username_for_host(
+                                connection_options.host.clone(),
+                                username,
+                                cx,
+                            );
+                        }
+                        cx.observe(&client, |_, _, cx| cx.notify()).detach();
                         this.retained_connections.push(client);
                         this.add_ssh_server(connection_options, cx);
                         this.mode = Mode::default_mode(&this.ssh_config_servers, cx);
@@ -532,7 +1026,7 @@ impl RemoteServerProjects {
         self.mode = Mode::ViewServerOptions(ViewServerOptionsState {
             server_index,
             connection,
-            entries: std::array::from_fn(|_| NavigableEntry::focusable(cx)),
+            entries: ServerOptionsEntries::new(cx),
         });
         self.focus_handle(cx).focus(window);
         cx.notify();
@@ -542,6 +1036,7 @@ impl RemoteServerProjects {
         &mut self,
         ix: usize,
         ssh_connection: SshConnection,
+        typed_path: Option<String>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -550,7 +1045,7 @@ impl RemoteServerProjects {
         };
 
         let create_new_window = self.create_new_window;
-        let connection_options = ssh_connection.into();
+        let connection_options: SshConnectionOptions = ssh_connection.clone().into();
         workspace.update(cx, |_, cx| {
             cx.defer_in(window, move |workspace, window, cx| {
                 let app_state = workspace.app_state().clone();
@@ -606,6 +1101,95 @@ impl RemoteServerProjects {
                         )
                     })?;
 
+                    if let Some(typed_path) = typed_path {
+                        let resolved = project
+                            .read_with(cx, |project, cx| project.resolve_abs_path(&typed_path, cx))?
+                            .await;
+                        let Some(path) = resolved
+                            .filter(|path| path.is_dir())
+                            .and_then(|path| path.into_abs_path())
+                        else {
+                            return workspace.update_in(cx, |workspace, window, cx| {
+                                let weak = cx.entity().downgrade();
+                                let fs = workspace.project().read(cx).fs().clone();
+                                let error = format!("No folder found at {typed_path}").into();
+                                workspace.toggle_modal(window, cx, |window, cx| {
+                                    let mut modal = RemoteServerProjects::new(
+                                        create_new_window,
+                                        fs,
+                                        window,
+                                        weak,
+                                        cx,
+                                    );
+                                    modal.mode = Mode::OpenRemotePath(OpenRemotePathState::new(
+                                        ix,
+                                        ssh_connection,
+                                        Some(typed_path),
+                                        Some(error),
+                                        window,
+                                        cx,
+                                    ));
+                                    modal
+                                });
+                            });
+                        };
+
+                        cx.update(|_, cx| {
+                            let paths = vec![path.to_string_lossy().to_string()];
+                            update_settings_file::<SshSettings>(
+                                app_state.fs.clone(),
+                                cx,
+                                move |setting, _| {
+                                    if let Some(server) = setting
+                                        .ssh_connections
+                                        .as_mut()
+                                        .and_then(|connections| connections.get_mut(ix))
+                                    {
+                                        if !server
+                                            .projects
+                                            .iter()
+                                            .any(|project| project.paths == paths)
+                                        {
+                                            server.projects.insert(SshProject {
+                                                paths,
+                                                ..SshProject::default()
+                                            });
+                                        }
+                                    }
+                                },
+                            );
+                        })?;
+
+                        let replace_window = if create_new_window {
+                            None
+                        } else {
+                            cx.window_handle().downcast::<Workspace>()
+                        };
+                        let result = open_ssh_project(
+                            connection_options,
+                            vec![path],
+                            app_state,
+                            OpenOptions {
+                                replace_window,
+                                ..OpenOptions::default()
+                            },
+                            cx,
+                        )
+                        .await;
+                        if let Err(e) = result {
+                            log::error!("Failed to connect: {e:#}");
+                            cx.prompt(
+                                gpui::PromptLevel::Critical,
+                                "Failed to connect",
+                                Some(&e.to_string()),
+                                &["Ok"],
+                            )
+                            .await
+                            .ok();
+                        }
+                        return Ok(());
+                    }
+
                     let home_dir = project
                         .read_with(cx, |project, cx| project.resolve_abs_path("~", cx))?
                         .await
@@ -657,57 +1241,189 @@ impl RemoteServerProjects {
 
                 self.create_ssh_server(state.address_editor.clone(), window, cx);
             }
-            Mode::EditNickname(state) => {
-                let text = Some(state.editor.read(cx).text(cx)).filter(|text| !text.is_empty());
-                let index = state.index;
-                self.update_settings_file(cx, move |setting, _| {
-                    if let Some(connections) = setting.ssh_connections.as_mut() {
-                        if let Some(connection) = connections.get_mut(index) {
-                            connection.nickname = text;
-                        }
-                    }
-                });
-                self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
-                self.focus_handle.focus(window);
-            }
+            // The views with a single editor handle their own confirmation.
+            Mode::EditNickname(_)
+            | Mode::EditShell(_)
+            | Mode::EditConnection(_)
+            | Mode::OpenRemotePath(_) => {}
         }
     }
 
-    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
-        match &self.mode {
-            Mode::Default(_) => cx.emit(DismissEvent),
-            Mode::CreateRemoteServer(state) if state.ssh_prompt.is_some() => {
-                let new_state = CreateRemoteServer::new(window, cx);
-                let old_prompt = state.address_editor.read(cx).text(cx);
-                new_state.address_editor.update(cx, |this, cx| {
-                    this.set_text(old_prompt, window, cx);
-                });
-
-                self.mode = Mode::CreateRemoteServer(new_state);
-                cx.notify();
-            }
-            _ => {
-                self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
-                self.focus_handle(cx).focus(window);
-                cx.notify();
+    fn confirm_edit_nickname(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Mode::EditNickname(state) = &self.mode else {
+            return;
+        };
+        let text = Some(state.editor.read(cx).text(cx)).filter(|text| !text.is_empty());
+        let index = state.index;
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(connections) = setting.ssh_connections.as_mut() {
+                if let Some(connection) = connections.get_mut(index) {
+                    connection.nickname = text;
+                }
             }
-        }
+        });
+        self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
+        self.focus_handle.focus(window);
     }
 
-    fn render_ssh_connection(
+    fn confirm_edit_shell(
         &mut self,
-        ix: usize,
-        ssh_server: RemoteEntry,
+        _: &menu::Confirm,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> impl IntoElement {
-        let connection = ssh_server.connection().into_owned();
-        let (main_label, aux_label) = if let Some(nickname) = connection.nickname.clone() {
-            let aux_label = SharedString::from(format!("({})", connection.host));
+    ) {
+        let Mode::EditShell(state) = &self.mode else {
+            return;
+        };
+        let text =
+            Some(state.editor.read(cx).text(cx).trim().to_string()).filter(|text| !text.is_empty());
+        let index = state.index;
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(connections) = setting.ssh_connections.as_mut() {
+                if let Some(connection) = connections.get_mut(index) {
+                    connection.shell = text;
+                }
+            }
+        });
+        self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
+        self.focus_handle.focus(window);
+    }
+
+    fn confirm_edit_connection(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Mode::EditConnection(state) = &self.mode else {
+            return;
+        };
+        let input = get_text(&state.editor, cx);
+        let connection_options = match SshConnectionOptions::parse_command_line(&input) {
+            Ok(connection_options) => connection_options,
+            Err(e) => {
+                if let Mode::EditConnection(state) = &mut self.mode {
+                    state.error = Some(format!("could not parse: {:?}", e).into());
+                }
+                cx.notify();
+                return;
+            }
+        };
+        let index = state.index;
+        // Only the connection details change, so that the server's projects are kept.
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(connections) = setting.ssh_connections.as_mut() {
+                if let Some(connection) = connections.get_mut(index) {
+                    connection.host = connection_options.host.into();
+                    connection.username = connection_options.username;
+                    connection.port = connection_options.port;
+                    connection.args = connection_options.args.unwrap_or_default();
+                    connection.port_forwards = connection_options.port_forwards;
+                }
+            }
+        });
+        self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
+        self.focus_handle.focus(window);
+    }
+
+    fn confirm_open_remote_path(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Mode::OpenRemotePath(state) = &self.mode else {
+            return;
+        };
+        let index = state.index;
+        let connection = state.connection.clone();
+        let path = state.editor.read(cx).text(cx).trim().to_string();
+        if !is_absolute_remote_path(&path) {
+            let error = if path.is_empty() {
+                "Enter a path to open"
+            } else {
+                "The path must be absolute"
+            };
+            if let Mode::OpenRemotePath(state) = &mut self.mode {
+                state.error = Some(error.into());
+            }
+            cx.notify();
+            return;
+        }
+        self.create_ssh_project(index, connection, Some(path), window, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        match &self.mode {
+            Mode::Default(_) => cx.emit(DismissEvent),
+            Mode::CreateRemoteServer(state) if state.ssh_prompt.is_some() => {
+                let new_state = CreateRemoteServer::new(window, cx);
+                let old_prompt = state.address_editor.read(cx).text(cx);
+                new_state.address_editor.update(cx, |this, cx| {
+                    this.set_text(old_prompt, window, cx);
+                });
+
+                self.mode = Mode::CreateRemoteServer(new_state);
+                cx.notify();
+            }
+            _ => {
+                self.mode = Mode::default_mode(&self.ssh_config_servers, cx);
+                self.focus_handle(cx).focus(window);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Renders a dot reflecting the state of the retained connection to `host`, or a neutral
+    /// one when there is no active session.
+    fn render_connection_status(&self, host: &str, cx: &App) -> impl IntoElement {
+        let state = self
+            .retained_connections
+            .iter()
+            .find(|client| client.read(cx).connection_options().host == host)
+            .map(|client| client.read(cx).connection_state());
+        let (color, tooltip) = match state {
+            Some(ConnectionState::Connected) => (Color::Success, "Connected"),
+            Some(ConnectionState::Connecting | ConnectionState::Reconnecting) => {
+                (Color::Warning, "Connecting…")
+            }
+            Some(ConnectionState::HeartbeatMissed) => (Color::Warning, "Connection unstable"),
+            Some(ConnectionState::Disconnected) => (Color::Error, "Disconnected"),
+            None => (Color::Muted, "No active connection"),
+        };
+        div()
+            .id(SharedString::from(format!("ssh-connection-status-{host}")))
+            .child(Indicator::dot().color(color))
+            .tooltip(Tooltip::text(tooltip))
+    }
+
+    fn render_ssh_connection(
+        &mut self,
+        ix: usize,
+        ssh_server: RemoteEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut connection = ssh_server.connection().into_owned();
+        if let RemoteEntry::SshConfig { host, .. } = &ssh_server {
+            connection.args = proxy_args_for_host(host, &self.ssh_config_proxies);
+        }
+        let (main_label, aux_label) = if let Some(nickname) = connection.nickname.clone() {
+            let aux_label = SharedString::from(format!("({})", connection.host));
             (nickname.into(), Some(aux_label))
         } else {
             (connection.host.clone(), None)
         };
+        // Hosts that only come from ssh_config have no saved projects to count.
+        let project_count = match &ssh_server {
+            RemoteEntry::Project { projects, .. } => Some(projects.len()),
+            RemoteEntry::SshConfig { .. } => None,
+        };
         v_flex()
             .w_full()
             .child(ListSeparator)
@@ -719,6 +1435,7 @@ impl RemoteServerProjects {
                     .px_3()
                     .gap_1()
                     .overflow_hidden()
+                    .child(self.render_connection_status(&connection.host, cx))
                     .child(
                         div().max_w_96().overflow_hidden().text_ellipsis().child(
                             Label::new(main_label)
@@ -730,7 +1447,12 @@ impl RemoteServerProjects {
                         aux_label.map(|label| {
                             Label::new(label).size(LabelSize::Small).color(Color::Muted)
                         }),
-                    ),
+                    )
+                    .children(project_count.map(|count| {
+                        Label::new(format!("({count})"))
+                            .size(LabelSize::XSmall)
+                            .color(Color::Disabled)
+                    })),
             )
             .child(match &ssh_server {
                 RemoteEntry::Project {
@@ -758,7 +1480,19 @@ impl RemoteServerProjects {
                             .on_action(cx.listener({
                                 let ssh_connection = connection.clone();
                                 move |this, _: &menu::Confirm, window, cx| {
-                                    this.create_ssh_project(ix, ssh_connection.clone(), window, cx);
+                                    this.create_ssh_project(
+                                        ix,
+                                        ssh_connection.clone(),
+                                        None,
+                                        window,
+                                        cx,
+                                    );
+                                }
+                            }))
+                            .on_action(cx.listener({
+                                let ssh_connection = connection.clone();
+                                move |this, _: &menu::SecondaryConfirm, window, cx| {
+                                    this.open_remote_path(ix, ssh_connection.clone(), window, cx);
                                 }
                             }))
                             .child(
@@ -770,12 +1504,29 @@ impl RemoteServerProjects {
                                     .spacing(ui::ListItemSpacing::Sparse)
                                     .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
                                     .child(Label::new("Open Folder"))
+                                    .end_hover_slot(
+                                        IconButton::new(("open-remote-path", ix), IconName::Pencil)
+                                            .icon_size(IconSize::Small)
+                                            .tooltip(Tooltip::text("Open by Path"))
+                                            .on_click(cx.listener({
+                                                let ssh_connection = connection.clone();
+                                                move |this, _, window, cx| {
+                                                    this.open_remote_path(
+                                                        ix,
+                                                        ssh_connection.clone(),
+                                                        window,
+                                                        cx,
+                                                    );
+                                                }
+                                            })),
+                                    )
                                     .on_click(cx.listener({
                                         let ssh_connection = connection.clone();
                                         move |this, _, window, cx| {
                                             this.create_ssh_project(
                                                 ix,
                                                 ssh_connection.clone(),
+                                                None,
                                                 window,
                                                 cx,
                                             );
@@ -829,7 +1580,13 @@ impl RemoteServerProjects {
                             let host = host.clone();
                             move |this, _: &menu::Confirm, window, cx| {
                                 let new_ix = this.create_host_from_ssh_config(&host, cx);
-                                this.create_ssh_project(new_ix, ssh_connection.clone(), window, cx);
+                                this.create_ssh_project(
+                                    new_ix,
+                                    ssh_connection.clone(),
+                                    None,
+                                    window,
+                                    cx,
+                                );
                             }
                         }))
                         .child(
@@ -847,6 +1604,7 @@ impl RemoteServerProjects {
                                         this.create_ssh_project(
                                             new_ix,
                                             ssh_connection.clone(),
+                                            None,
                                             window,
                                             cx,
                                         );
@@ -871,54 +1629,23 @@ impl RemoteServerProjects {
         let element_id_base = SharedString::from(format!("remote-project-{server_ix}"));
         let container_element_id_base =
             SharedString::from(format!("remote-project-container-{element_id_base}"));
+        let connection = server.connection().into_owned();
+
+        let is_recent = connection.last_opened_project.as_ref() == Some(&project.paths);
 
         let callback = Rc::new({
             let project = project.clone();
             move |remote_server_projects: &mut Self,
-                  secondary_confirm: bool,
+                  open_in_new_window: bool,
                   window: &mut Window,
                   cx: &mut Context<Self>| {
-                let Some(app_state) = remote_server_projects
-                    .workspace
-                    .read_with(cx, |workspace, _| workspace.app_state().clone())
-                    .log_err()
-                else {
-                    return;
-                };
-                let project = project.clone();
-                let server = server.connection().into_owned();
-                cx.emit(DismissEvent);
-
-                let replace_window = match (create_new_window, secondary_confirm) {
-                    (true, false) | (false, true) => None,
-                    (true, true) | (false, false) => window.window_handle().downcast::<Workspace>(),
-                };
-
-                cx.spawn_in(window, async move |_, cx| {
-                    let result = open_ssh_project(
-                        server.into(),
-                        project.paths.into_iter().map(PathBuf::from).collect(),
-                        app_state,
-                        OpenOptions {
-                            replace_window,
-                            ..OpenOptions::default()
-                        },
-                        cx,
-                    )
-                    .await;
-                    if let Err(e) = result {
-                        log::error!("Failed to connect: {e:#}");
-                        cx.prompt(
-                            gpui::PromptLevel::Critical,
-                            "Failed to connect",
-                            Some(&e.to_string()),
-                            &["Ok"],
-                        )
-                        .await
-                        .ok();
-                    }
-                })
-                .detach();
+                remote_server_projects.open_saved_ssh_project(
+                    server.connection().into_owned(),
+                    project.clone(),
+                    open_in_new_window,
+                    window,
+                    cx,
+                );
             }
         });
 
@@ -929,13 +1656,13 @@ impl RemoteServerProjects {
             .on_action(cx.listener({
                 let callback = callback.clone();
                 move |this, _: &menu::Confirm, window, cx| {
-                    callback(this, false, window, cx);
+                    callback(this, create_new_window, window, cx);
                 }
             }))
             .on_action(cx.listener({
                 let callback = callback.clone();
                 move |this, _: &menu::SecondaryConfirm, window, cx| {
-                    callback(this, true, window, cx);
+                    callback(this, !create_new_window, window, cx);
                 }
             }))
             .child(
@@ -948,18 +1675,75 @@ impl RemoteServerProjects {
                             .color(Color::Muted)
                             .size(IconSize::Small),
                     )
-                    .child(Label::new(project.paths.join(", ")))
-                    .on_click(cx.listener(move |this, e: &ClickEvent, window, cx| {
-                        let secondary_confirm = e.down.modifiers.platform;
-                        callback(this, secondary_confirm, window, cx)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(project.paths.join(", ")))
+                            .when(is_recent, |this| {
+                                this.child(
+                                    Label::new("Recent")
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Accent),
+                                )
+                            }),
+                    )
+                    .on_click(cx.listener({
+                        let callback = callback.clone();
+                        move |this, e: &ClickEvent, window, cx| {
+                            let secondary_confirm = e.down.modifiers.platform;
+                            callback(this, create_new_window != secondary_confirm, window, cx)
+                        }
                     }))
-                    .when(is_from_zed, |server_list_item| {
-                        server_list_item.end_hover_slot::<AnyElement>(Some(
-                            div()
-                                .mr_2()
-                                .child({
-                                    let project = project.clone();
-                                    // Right-margin to offset it from the Scrollbar
+                    .end_hover_slot::<AnyElement>(Some(
+                        // Right-margin to offset it from the Scrollbar
+                        h_flex()
+                            .mr_2()
+                            .gap_1()
+                            .child(
+                                IconButton::new("open-remote-project-here", IconName::FolderOpen)
+                                    .icon_size(IconSize::Small)
+                                    .shape(IconButtonShape::Square)
+                                    .size(ButtonSize::Large)
+                                    .tooltip(Tooltip::text("Open Here"))
+                                    .on_click(cx.listener({
+                                        let callback = callback.clone();
+                                        move |this, _, window, cx| callback(this, false, window, cx)
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(
+                                    "open-remote-project-in-new-window",
+                                    IconName::ArrowUpRight,
+                                )
+                                .icon_size(IconSize::Small)
+                                .shape(IconButtonShape::Square)
+                                .size(ButtonSize::Large)
+                                .tooltip(Tooltip::text("Open in New Window"))
+                                .on_click(cx.listener(
+                                    move |this, _, window, cx| callback(this, true, window, cx),
+                                )),
+                            )
+                            .child(
+                                IconButton::new("open-remote-project-terminal", IconName::Terminal)
+                                    .icon_size(IconSize::Small)
+                                    .shape(IconButtonShape::Square)
+                                    .size(ButtonSize::Large)
+                                    .tooltip(Tooltip::text("Open in Terminal"))
+                                    .on_click(cx.listener({
+                                        let path = project.paths.first().map(PathBuf::from);
+                                        move |this, _, window, cx| {
+                                            this.open_remote_terminal(
+                                                connection.clone(),
+                                                path.clone(),
+                                                window,
+                                                cx,
+                                            )
+                                        }
+                                    })),
+                            )
+                            .when(is_from_zed, |buttons| {
+                                let project = project.clone();
+                                buttons.child(
                                     IconButton::new("remove-remote-project", IconName::TrashAlt)
                                         .icon_size(IconSize::Small)
                                         .shape(IconButtonShape::Square)
@@ -967,14 +1751,122 @@ impl RemoteServerProjects {
                                         .tooltip(Tooltip::text("Delete Remote Project"))
                                         .on_click(cx.listener(move |this, _, _, cx| {
                                             this.delete_ssh_project(server_ix, &project, cx)
-                                        }))
-                                })
-                                .into_any_element(),
-                        ))
-                    }),
+                                        })),
+                                )
+                            })
+                            .into_any_element(),
+                    )),
             )
     }
 
+    /// Opens a saved project of the server, remembering it as the server's most recently opened
+    /// one once connected.
+    fn open_saved_ssh_project(
+        &mut self,
+        server: SshConnection,
+        project: SshProject,
+        open_in_new_window: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(app_state) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.app_state().clone())
+            .log_err()
+        else {
+            return;
+        };
+        if !self.keep_open {
+            cx.emit(DismissEvent);
+        }
+
+        let replace_window = if open_in_new_window {
+            None
+        } else {
+            window.window_handle().downcast::<Workspace>()
+        };
+        let is_from_zed = SshSettings::get_global(cx)
+            .ssh_connections()
+            .any(|connection| connection == server);
+
+        cx.spawn_in(window, async move |_, cx| {
+            let connection_options = SshConnectionOptions::from(server.clone());
+            let result = open_ssh_project(
+                connection_options.clone(),
+                project.paths.iter().map(PathBuf::from).collect(),
+                app_state.clone(),
+                OpenOptions {
+                    replace_window,
+                    ..OpenOptions::default()
+                },
+                cx,
+            )
+            .await;
+            match result {
+                Ok(()) if is_from_zed => {
+                    cx.update(|_, cx| {
+                        update_settings_file::<SshSettings>(
+                            app_state.fs.clone(),
+                            cx,
+                            move |setting, _| {
+                                record_last_opened_project(
+                                    setting,
+                                    &connection_options,
+                                    project.paths,
+                                )
+                            },
+                        );
+                    })
+                    .log_err();
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    log::error!("Failed to connect: {e:#}");
+                    cx.prompt(
+                        gpui::PromptLevel::Critical,
+                        "Failed to connect",
+                        Some(&e.to_string()),
+                        &["Ok"],
+                    )
+                    .await
+                    .ok();
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Opens the most recently opened project of the server that contains the focused entry,
+    /// without having to select it in the list first.
+    fn reopen_last_ssh_project(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Mode::Default(state) = &self.mode else {
+            return;
+        };
+        let project = state.servers.iter().find_map(|server| {
+            let RemoteEntry::Project {
+                connection,
+                projects,
+                ..
+            } = server
+            else {
+                return None;
+            };
+            server
+                .navigable_entries()
+                .iter()
+                .any(|entry| entry.focus_handle.contains_focused(window, cx))
+                .then_some(())?;
+            let last_opened = connection.last_opened_project.as_ref()?;
+            let (_, project) = projects
+                .iter()
+                .find(|(_, project)| &project.paths == last_opened)?;
+            Some((connection.clone(), project.clone()))
+        });
+        if let Some((connection, project)) = project {
+            self.open_saved_ssh_project(connection, project, self.create_new_window, window, cx);
+        }
+    }
+
     fn update_settings_file(
         &mut self,
         cx: &mut Context<Self>,
@@ -990,6 +1882,156 @@ impl RemoteServerProjects {
         update_settings_file::<SshSettings>(fs, cx, move |setting, cx| f(setting, cx));
     }
 
+    /// Opens a terminal tab with a login shell in `path` on the server, or in its home
+    /// directory, reusing an already established connection to it if there is one.
+    fn open_remote_terminal(
+        &mut self,
+        ssh_connection: SshConnection,
+        path: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        // Task terminals of remote projects run on that project's host, so our local ssh
+        // control socket would not be reachable from there.
+        if !workspace.read(cx).project().read(cx).is_local() {
+            struct RemoteTerminalUnavailable;
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<RemoteTerminalUnavailable>(),
+                        "Remote terminals can only be opened from a local project",
+                    )
+                    .autohide(),
+                    cx,
+                );
+            });
+            return;
+        }
+
+        let retained_client = self
+            .retained_connections
+            .iter()
+            .find(|client| ssh_connection.matches(&client.read(cx).connection_options()))
+            .cloned();
+        let connection_options: SshConnectionOptions = ssh_connection.into();
+        cx.emit(DismissEvent);
+
+        workspace.update(cx, |_, cx| {
+            cx.defer_in(window, move |workspace, window, cx| {
+                let connect = match retained_client {
+                    Some(client) => Task::ready(Some(Some(client))),
+                    None => {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            SshConnectionModal::new(&connection_options, Vec::new(), window, cx)
+                        });
+                        let prompt = workspace
+                            .active_modal::<SshConnectionModal>(cx)
+                            .unwrap()
+                            .read(cx)
+                            .prompt
+                            .clone();
+                        connect_over_ssh(
+                            ConnectionIdentifier::setup(),
+                            connection_options.clone(),
+                            prompt,
+                            window,
+                            cx,
+                        )
+                        .prompt_err(
+                            "Failed to connect",
+                            window,
+                            cx,
+                            |_, _, _| None,
+                        )
+                    }
+                };
+
+                cx.spawn_in(window, async move |workspace, cx| {
+                    let client = connect.await;
+                    workspace.update(cx, |workspace, cx| {
+                        if let Some(prompt) = workspace.active_modal::<SshConnectionModal>(cx) {
+                            prompt.update(cx, |prompt, cx| prompt.finished(cx))
+                        }
+                    })?;
+                    let Some(Some(client)) = client else {
+                        return Ok(());
+                    };
+
+                    let terminal = workspace.update_in(cx, |workspace, window, cx| {
+                        let client = client.read(cx);
+                        let (SshArgs { arguments, envs }, path_style) = client.ssh_info()?;
+                        let shell = client.connection_options().shell;
+                        let shell_args = vec!["-l".to_string()];
+                        let (program, args) = wrap_for_ssh(
+                            &SshCommand { arguments },
+                            shell.as_ref().map(|shell| (shell, &shell_args)),
+                            path.as_deref(),
+                            Default::default(),
+                            None,
+                            path_style,
+                        );
+                        let label = format!("ssh {}", connection_options.connection_string());
+                        Some(workspace.spawn_in_terminal(
+                            SpawnInTerminal {
+                                id: TaskId(format!("remote-terminal-{}", connection_options.host)),
+                                full_label: label.clone(),
+                                label: label.clone(),
+                                command: Some(program),
+                                args,
+                                command_label: label,
+                                env: envs.into_iter().flatten().collect(),
+                                use_new_terminal: true,
+                                allow_concurrent_runs: true,
+                                ..SpawnInTerminal::default()
+                            },
+                            window,
+                            cx,
+                        ))
+                    })?;
+
+                    // Keep the connection, and with it the ssh control socket the terminal
+                    // goes through, alive until the terminal exits.
+                    if let Some(terminal) = terminal {
+                        terminal.await;
+                    }
+                    drop(client);
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+            })
+        });
+    }
+
+    /// Drops the connections kept alive for the given host, so that connecting to it again
+    /// prompts for credentials instead of reusing an authenticated session.
+    fn forget_credentials(&mut self, host: &str, cx: &mut Context<Self>) {
+        self.retained_connections
+            .retain(|client| client.read(cx).connection_options().host != host);
+        SshRemoteClient::forget_connections_to_host(host, cx);
+
+        struct SshServerCredentialsForgotten;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::composite::<SshServerCredentialsForgotten>(
+                            SharedString::from(host.to_string()),
+                        ),
+                        format!(
+                            "New connections to {host} will authenticate again; \
+                            windows connected to it stay connected"
+                        ),
+                    )
+                    .autohide(),
+                    cx,
+                );
+            })
+            .ok();
+    }
+
     fn delete_ssh_server(&mut self, server: usize, cx: &mut Context<Self>) {
         self.update_settings_file(cx, move |setting, _| {
             if let Some(connections) = setting.ssh_connections.as_mut() {
@@ -998,16 +2040,191 @@ impl RemoteServerProjects {
         });
     }
 
-    fn delete_ssh_project(&mut self, server: usize, project: &SshProject, cx: &mut Context<Self>) {
-        let project = project.clone();
-        self.update_settings_file(cx, move |setting, _| {
-            if let Some(server) = setting
-                .ssh_connections
-                .as_mut()
-                .and_then(|connections| connections.get_mut(server))
-            {
-                server.projects.remove(&project);
-            }
+    /// Swaps the saved server that contains the focused entry with its neighbor. Hosts that only
+    /// come from ssh_config aren't persisted, so they can neither be moved nor swapped with.
+    fn move_ssh_server(&mut self, offset: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let Mode::Default(state) = &self.mode else {
+            return;
+        };
+        let Some((server_ix, connection, entry_ix)) =
+            state
+                .servers
+                .iter()
+                .enumerate()
+                .find_map(|(server_ix, server)| {
+                    let RemoteEntry::Project { connection, .. } = server else {
+                        return None;
+                    };
+                    let entry_ix = server
+                        .navigable_entries()
+                        .iter()
+                        .position(|entry| entry.focus_handle.contains_focused(window, cx))?;
+                    Some((server_ix, connection.clone(), entry_ix))
+                })
+        else {
+            return;
+        };
+        let Some(target_ix) = server_ix.checked_add_signed(offset) else {
+            return;
+        };
+        if !matches!(
+            state.servers.get(target_ix),
+            Some(RemoteEntry::Project { .. })
+        ) {
+            return;
+        }
+
+        self.pending_server_focus = Some((connection, entry_ix));
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(connections) = setting.ssh_connections.as_mut() {
+                if server_ix < connections.len() && target_ix < connections.len() {
+                    connections.swap(server_ix, target_ix);
+                }
+            }
+        });
+    }
+
+    fn delete_ssh_project(&mut self, server: usize, project: &SshProject, cx: &mut Context<Self>) {
+        let project = project.clone();
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(server) = setting
+                .ssh_connections
+                .as_mut()
+                .and_then(|connections| connections.get_mut(server))
+            {
+                remove_ssh_project(server, &project);
+            }
+        });
+    }
+
+    /// Connects to the server and, after confirmation, removes its saved projects with paths
+    /// that no longer exist there.
+    fn prune_missing_projects(
+        &mut self,
+        server_index: usize,
+        ssh_connection: SshConnection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let create_new_window = self.create_new_window;
+        let connection_options: SshConnectionOptions = ssh_connection.clone().into();
+        workspace.update(cx, |_, cx| {
+            cx.defer_in(window, move |workspace, window, cx| {
+                let app_state = workspace.app_state().clone();
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    SshConnectionModal::new(&connection_options, Vec::new(), window, cx)
+                });
+                let prompt = workspace
+                    .active_modal::<SshConnectionModal>(cx)
+                    .unwrap()
+                    .read(cx)
+                    .prompt
+                    .clone();
+
+                let connect = connect_over_ssh(
+                    ConnectionIdentifier::setup(),
+                    connection_options.clone(),
+                    prompt,
+                    window,
+                    cx,
+                )
+                .prompt_err("Failed to connect", window, cx, |_, _, _| None);
+
+                cx.spawn_in(window, async move |workspace, cx| {
+                    let session = connect.await;
+
+                    workspace.update(cx, |workspace, cx| {
+                        if let Some(prompt) = workspace.active_modal::<SshConnectionModal>(cx) {
+                            prompt.update(cx, |prompt, cx| prompt.finished(cx))
+                        }
+                    })?;
+
+                    if let Some(Some(session)) = session {
+                        let mut missing_projects = Vec::new();
+                        for ssh_project in &ssh_connection.projects {
+                            for path in &ssh_project.paths {
+                                let exists = session
+                                    .read_with(cx, |session, _| session.path_exists(path))?
+                                    .await;
+                                // Paths that couldn't be checked are kept.
+                                if exists.log_err() == Some(false) {
+                                    missing_projects.push(ssh_project.clone());
+                                    break;
+                                }
+                            }
+                        }
+
+                        let host = ssh_connection.host.clone();
+                        if missing_projects.is_empty() {
+                            workspace.update(cx, |workspace, cx| {
+                                struct NoMissingSshProjects;
+                                workspace.show_toast(
+                                    Toast::new(
+                                        NotificationId::composite::<NoMissingSshProjects>(
+                                            host.clone(),
+                                        ),
+                                        format!("All saved projects on {host} still exist"),
+                                    )
+                                    .autohide(),
+                                    cx,
+                                );
+                            })?;
+                        } else {
+                            let message = if missing_projects.len() == 1 {
+                                format!("Remove 1 missing project from {host}?")
+                            } else {
+                                format!(
+                                    "Remove {} missing projects from {host}?",
+                                    missing_projects.len()
+                                )
+                            };
+                            let detail = missing_projects
+                                .iter()
+                                .map(|project| project.paths.join(", "))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            let confirmation = cx.prompt(
+                                PromptLevel::Warning,
+                                &message,
+                                Some(&detail),
+                                &["Remove", "Cancel"],
+                            );
+                            if confirmation.await.ok() == Some(0) {
+                                cx.update(|_, cx| {
+                                    update_settings_file::<SshSettings>(
+                                        app_state.fs.clone(),
+                                        cx,
+                                        move |setting, _| {
+                                            if let Some(server) =
+                                                setting.ssh_connections.as_mut().and_then(
+                                                    |connections| connections.get_mut(server_index),
+                                                )
+                                            {
+                                                for project in &missing_projects {
+                                                    remove_ssh_project(server, project);
+                                                }
+                                            }
+                                        },
+                                    );
+                                })?;
+                            }
+                        }
+                    }
+
+                    workspace.update_in(cx, |workspace, window, cx| {
+                        let weak = cx.entity().downgrade();
+                        let fs = workspace.project().read(cx).fs().clone();
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            RemoteServerProjects::new(create_new_window, fs, window, weak, cx)
+                        });
+                    })
+                })
+                .detach_and_log_err(cx);
+            })
         });
     }
 
@@ -1029,6 +2246,11 @@ impl RemoteServerProjects {
                     args: connection_options.args.unwrap_or_default(),
                     upload_binary_over_ssh: None,
                     port_forwards: connection_options.port_forwards,
+                    shell: connection_options.shell,
+                    allow_startup_commands: None,
+                    resolve_symlinks: None,
+                    host_key_checking: connection_options.host_key_checking,
+                    last_opened_project: None,
                 })
         });
     }
@@ -1046,6 +2268,14 @@ impl RemoteServerProjects {
             }
         });
 
+        // Show how the command line is interpreted before it gets saved.
+        let address = get_text(&state.address_editor, cx);
+        let parsed_options = if address.is_empty() {
+            None
+        } else {
+            SshConnectionOptions::parse_command_line(&address).ok()
+        };
+
         let theme = cx.theme();
 
         v_flex()
@@ -1069,6 +2299,14 @@ impl RemoteServerProjects {
                     .map(|this| {
                         if let Some(ssh_prompt) = ssh_prompt {
                             this.child(h_flex().w_full().child(ssh_prompt))
+                        } else if state._creating.is_some() {
+                            this.child(
+                                h_flex().p_2().w_full().gap_2().child(
+                                    Label::new("Checking that the server is reachable…")
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            )
                         } else if let Some(address_error) = &state.address_error {
                             this.child(
                                 h_flex().p_2().w_full().gap_2().child(
@@ -1077,6 +2315,8 @@ impl RemoteServerProjects {
                                         .color(Color::Error),
                                 ),
                             )
+                        } else if let Some(options) = &parsed_options {
+                            this.child(render_connection_preview(options))
                         } else {
                             this.child(
                                 h_flex()
@@ -1144,7 +2384,7 @@ impl RemoteServerProjects {
                             };
                             div()
                                 .id("ssh-options-add-nickname")
-                                .track_focus(&entries[0].focus_handle)
+                                .track_focus(&entries.edit_nickname.focus_handle)
                                 .on_action(cx.listener(
                                     move |this, _: &menu::Confirm, window, cx| {
                                         this.mode = Mode::EditNickname(EditNicknameState::new(
@@ -1158,7 +2398,10 @@ impl RemoteServerProjects {
                                 .child(
                                     ListItem::new("add-nickname")
                                         .toggle_state(
-                                            entries[0].focus_handle.contains_focused(window, cx),
+                                            entries
+                                                .edit_nickname
+                                                .focus_handle
+                                                .contains_focused(window, cx),
                                         )
                                         .inset(true)
                                         .spacing(ui::ListItemSpacing::Sparse)
@@ -1174,6 +2417,132 @@ impl RemoteServerProjects {
                                         })),
                                 )
                         })
+                        .child({
+                            div()
+                                .id("ssh-options-edit-connection")
+                                .track_focus(&entries.edit_connection.focus_handle)
+                                .on_action(cx.listener(
+                                    move |this, _: &menu::Confirm, window, cx| {
+                                        this.mode = Mode::EditConnection(EditConnectionState::new(
+                                            server_index,
+                                            window,
+                                            cx,
+                                        ));
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(
+                                    ListItem::new("edit-connection")
+                                        .toggle_state(
+                                            entries
+                                                .edit_connection
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::Settings).color(Color::Muted),
+                                        )
+                                        .child(Label::new("Edit Connection"))
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.mode = Mode::EditConnection(
+                                                EditConnectionState::new(server_index, window, cx),
+                                            );
+                                            cx.notify();
+                                        })),
+                                )
+                        })
+                        .child({
+                            let label = if connection.shell.is_some() {
+                                "Edit Terminal Shell"
+                            } else {
+                                "Set Terminal Shell"
+                            };
+                            div()
+                                .id("ssh-options-edit-shell")
+                                .track_focus(&entries.edit_shell.focus_handle)
+                                .on_action(cx.listener(
+                                    move |this, _: &menu::Confirm, window, cx| {
+                                        this.mode = Mode::EditShell(EditShellState::new(
+                                            server_index,
+                                            window,
+                                            cx,
+                                        ));
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(
+                                    ListItem::new("edit-shell")
+                                        .toggle_state(
+                                            entries
+                                                .edit_shell
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::Terminal).color(Color::Muted),
+                                        )
+                                        .child(Label::new(label))
+                                        .when_some(connection.shell.clone(), |item, shell| {
+                                            item.end_hover_slot(
+                                                Label::new(shell).color(Color::Muted),
+                                            )
+                                        })
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.mode = Mode::EditShell(EditShellState::new(
+                                                server_index,
+                                                window,
+                                                cx,
+                                            ));
+                                            cx.notify();
+                                        })),
+                                )
+                        })
+                        .child({
+                            div()
+                                .id("ssh-options-open-terminal")
+                                .track_focus(&entries.open_terminal.focus_handle)
+                                .on_action(cx.listener({
+                                    let connection = connection.clone();
+                                    move |this, _: &menu::Confirm, window, cx| {
+                                        this.open_remote_terminal(
+                                            connection.clone(),
+                                            None,
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }))
+                                .child(
+                                    ListItem::new("open-terminal")
+                                        .toggle_state(
+                                            entries
+                                                .open_terminal
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::TerminalAlt).color(Color::Muted),
+                                        )
+                                        .child(Label::new("Open Remote Terminal"))
+                                        .on_click(cx.listener({
+                                            let connection = connection.clone();
+                                            move |this, _, window, cx| {
+                                                this.open_remote_terminal(
+                                                    connection.clone(),
+                                                    None,
+                                                    window,
+                                                    cx,
+                                                );
+                                            }
+                                        })),
+                                )
+                        })
                         .child({
                             let workspace = self.workspace.clone();
                             fn callback(
@@ -1209,7 +2578,7 @@ impl RemoteServerProjects {
                             }
                             div()
                                 .id("ssh-options-copy-server-address")
-                                .track_focus(&entries[1].focus_handle)
+                                .track_focus(&entries.copy_server_address.focus_handle)
                                 .on_action({
                                     let connection_string = connection_string.clone();
                                     let workspace = self.workspace.clone();
@@ -1220,7 +2589,10 @@ impl RemoteServerProjects {
                                 .child(
                                     ListItem::new("copy-server-address")
                                         .toggle_state(
-                                            entries[1].focus_handle.contains_focused(window, cx),
+                                            entries
+                                                .copy_server_address
+                                                .focus_handle
+                                                .contains_focused(window, cx),
                                         )
                                         .inset(true)
                                         .spacing(ui::ListItemSpacing::Sparse)
@@ -1242,6 +2614,80 @@ impl RemoteServerProjects {
                                         }),
                                 )
                         })
+                        .child({
+                            div()
+                                .id("ssh-options-forget-credentials")
+                                .track_focus(&entries.forget_credentials.focus_handle)
+                                .on_action(cx.listener({
+                                    let host = connection.host.clone();
+                                    move |this, _: &menu::Confirm, _, cx| {
+                                        this.forget_credentials(&host, cx);
+                                    }
+                                }))
+                                .child(
+                                    ListItem::new("forget-credentials")
+                                        .toggle_state(
+                                            entries
+                                                .forget_credentials
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::LockOutlined).color(Color::Muted),
+                                        )
+                                        .child(Label::new("Sign Out and Forget Credentials"))
+                                        .on_click(cx.listener({
+                                            let host = connection.host.clone();
+                                            move |this, _, _, cx| {
+                                                this.forget_credentials(&host, cx);
+                                            }
+                                        })),
+                                )
+                        })
+                        .child({
+                            div()
+                                .id("ssh-options-prune-missing-projects")
+                                .track_focus(&entries.prune_missing_projects.focus_handle)
+                                .on_action(cx.listener({
+                                    let connection = connection.clone();
+                                    move |this, _: &menu::Confirm, window, cx| {
+                                        this.prune_missing_projects(
+                                            server_index,
+                                            connection.clone(),
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }))
+                                .child(
+                                    ListItem::new("prune-missing-projects")
+                                        .toggle_state(
+                                            entries
+                                                .prune_missing_projects
+                                                .focus_handle
+                                                .contains_focused(window, cx),
+                                        )
+                                        .inset(true)
+                                        .spacing(ui::ListItemSpacing::Sparse)
+                                        .start_slot(
+                                            Icon::new(IconName::FolderX).color(Color::Muted),
+                                        )
+                                        .child(Label::new("Prune Missing Projects"))
+                                        .on_click(cx.listener({
+                                            let connection = connection.clone();
+                                            move |this, _, window, cx| {
+                                                this.prune_missing_projects(
+                                                    server_index,
+                                                    connection.clone(),
+                                                    window,
+                                                    cx,
+                                                );
+                                            }
+                                        })),
+                                )
+                        })
                         .child({
                             fn remove_ssh_server(
                                 remote_servers: Entity<RemoteServerProjects>,
@@ -1283,8 +2729,8 @@ impl RemoteServerProjects {
                                 .detach_and_log_err(cx);
                             }
                             div()
-                                .id("ssh-options-copy-server-address")
-                                .track_focus(&entries[2].focus_handle)
+                                .id("ssh-options-remove-server")
+                                .track_focus(&entries.remove_server.focus_handle)
                                 .on_action(cx.listener({
                                     let connection_string = connection_string.clone();
                                     move |_, _: &menu::Confirm, window, cx| {
@@ -1301,7 +2747,10 @@ impl RemoteServerProjects {
                                 .child(
                                     ListItem::new("remove-server")
                                         .toggle_state(
-                                            entries[2].focus_handle.contains_focused(window, cx),
+                                            entries
+                                                .remove_server
+                                                .focus_handle
+                                                .contains_focused(window, cx),
                                         )
                                         .inset(true)
                                         .spacing(ui::ListItemSpacing::Sparse)
@@ -1322,8 +2771,8 @@ impl RemoteServerProjects {
                         .child(ListSeparator)
                         .child({
                             div()
-                                .id("ssh-options-copy-server-address")
-                                .track_focus(&entries[3].focus_handle)
+                                .id("ssh-options-go-back")
+                                .track_focus(&entries.go_back.focus_handle)
                                 .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
                                     this.mode = Mode::default_mode(&this.ssh_config_servers, cx);
                                     cx.focus_self(window);
@@ -1332,7 +2781,10 @@ impl RemoteServerProjects {
                                 .child(
                                     ListItem::new("go-back")
                                         .toggle_state(
-                                            entries[3].focus_handle.contains_focused(window, cx),
+                                            entries
+                                                .go_back
+                                                .focus_handle
+                                                .contains_focused(window, cx),
                                         )
                                         .inset(true)
                                         .spacing(ui::ListItemSpacing::Sparse)
@@ -1351,7 +2803,7 @@ impl RemoteServerProjects {
                 )
                 .into_any_element(),
         );
-        for entry in entries {
+        for entry in entries.into_ordered() {
             view = view.entry(entry);
         }
 
@@ -1364,26 +2816,100 @@ impl RemoteServerProjects {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let Some(connection) = SshSettings::get_global(cx)
+        let connection = SshSettings::get_global(cx)
             .ssh_connections()
-            .nth(state.index)
-        else {
-            return v_flex()
-                .id("ssh-edit-nickname")
-                .track_focus(&self.focus_handle(cx));
-        };
+            .nth(state.index);
+        self.render_single_editor(
+            "ssh-edit-nickname",
+            connection.as_ref(),
+            &state.editor,
+            None,
+            Self::confirm_edit_nickname,
+            window,
+            cx,
+        )
+    }
 
-        let connection_string = connection.host.clone();
-        let nickname = connection.nickname.clone().map(|s| s.into());
+    fn render_edit_shell(
+        &self,
+        state: &EditShellState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let connection = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(state.index);
+        self.render_single_editor(
+            "ssh-edit-shell",
+            connection.as_ref(),
+            &state.editor,
+            None,
+            Self::confirm_edit_shell,
+            window,
+            cx,
+        )
+    }
 
-        v_flex()
-            .id("ssh-edit-nickname")
-            .track_focus(&self.focus_handle(cx))
+    fn render_edit_connection(
+        &self,
+        state: &EditConnectionState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let connection = SshSettings::get_global(cx)
+            .ssh_connections()
+            .nth(state.index);
+        self.render_single_editor(
+            "ssh-edit-connection",
+            connection.as_ref(),
+            &state.editor,
+            state.error.clone(),
+            Self::confirm_edit_connection,
+            window,
+            cx,
+        )
+    }
+
+    fn render_open_remote_path(
+        &self,
+        state: &OpenRemotePathState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_single_editor(
+            "ssh-open-remote-path",
+            Some(&state.connection),
+            &state.editor,
+            state.error.clone(),
+            Self::confirm_open_remote_path,
+            window,
+            cx,
+        )
+    }
+
+    /// Renders a single editor under the header of the given server, along with the error of its
+    /// last confirmation, if any. Renders nothing once the server is gone from the settings.
+    fn render_single_editor(
+        &self,
+        id: &'static str,
+        connection: Option<&SshConnection>,
+        editor: &Entity<Editor>,
+        error: Option<SharedString>,
+        on_confirm: fn(&mut Self, &menu::Confirm, &mut Window, &mut Context<Self>),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement + use<> {
+        let view = v_flex().id(id).track_focus(&self.focus_handle(cx));
+        let Some(connection) = connection else {
+            return view;
+        };
+
+        view.on_action(cx.listener(on_confirm))
             .child(
                 SshConnectionHeader {
-                    connection_string,
+                    connection_string: connection.host.clone(),
                     paths: Default::default(),
-                    nickname,
+                    nickname: connection.nickname.clone().map(|s| s.into()),
                 }
                 .render(window, cx),
             )
@@ -1392,8 +2918,29 @@ impl RemoteServerProjects {
                     .p_2()
                     .border_t_1()
                     .border_color(cx.theme().colors().border_variant)
-                    .child(state.editor.clone()),
+                    .child(editor.clone()),
             )
+            .when_some(error, |this, error| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .pb_2()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+    }
+
+    fn open_remote_path(
+        &mut self,
+        ix: usize,
+        connection: SshConnection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.mode = Mode::OpenRemotePath(OpenRemotePathState::new(
+            ix, connection, None, None, window, cx,
+        ));
+        cx.notify();
     }
 
     fn render_default(
@@ -1422,7 +2969,7 @@ impl RemoteServerProjects {
             should_rebuild = true;
         };
 
-        if !should_rebuild && ssh_settings.read_ssh_config {
+        if !should_rebuild {
             let current_ssh_hosts: BTreeSet<SharedString> = state
                 .servers
                 .iter()
@@ -1431,12 +2978,8 @@ impl RemoteServerProjects {
                     _ => None,
                 })
                 .collect();
-            let mut expected_ssh_hosts = self.ssh_config_servers.clone();
-            for server in &state.servers {
-                if let RemoteEntry::Project { connection, .. } = server {
-                    expected_ssh_hosts.remove(&connection.host);
-                }
-            }
+            let expected_ssh_hosts =
+                extra_ssh_config_hosts(&self.ssh_config_servers, &state.servers);
             should_rebuild = current_ssh_hosts != expected_ssh_hosts;
         }
 
@@ -1445,6 +2988,22 @@ impl RemoteServerProjects {
             if let Mode::Default(new_state) = &self.mode {
                 state = new_state.clone();
             }
+            if let Some((moved_connection, entry_ix)) = self.pending_server_focus.take() {
+                let focus_handle = state
+                    .servers
+                    .iter()
+                    .find(|server| *server.connection() == moved_connection)
+                    .and_then(|server| {
+                        let entries = server.navigable_entries();
+                        entries
+                            .get(entry_ix)
+                            .or(entries.last())
+                            .map(|entry| entry.focus_handle.clone())
+                    });
+                if let Some(focus_handle) = focus_handle {
+                    window.defer(cx, move |window, _| focus_handle.focus(window));
+                }
+            }
         }
 
         let scroll_state = state.scrollbar.parent_entity(&cx.entity());
@@ -1487,6 +3046,17 @@ impl RemoteServerProjects {
             v_flex()
                 .track_focus(&self.focus_handle(cx))
                 .id("ssh-server-list")
+                .on_action(cx.listener(|this, _: &MoveRemoteServerUp, window, cx| {
+                    this.move_ssh_server(-1, window, cx)
+                }))
+                .on_action(cx.listener(|this, _: &MoveRemoteServerDown, window, cx| {
+                    this.move_ssh_server(1, window, cx)
+                }))
+                .on_action(
+                    cx.listener(|this, _: &ReopenLastRemoteProject, window, cx| {
+                        this.reopen_last_ssh_project(window, cx)
+                    }),
+                )
                 .overflow_y_scroll()
                 .track_scroll(&scroll_handle)
                 .size_full()
@@ -1534,30 +3104,60 @@ impl RemoteServerProjects {
         }
         let mut modal_section = modal_section.render(window, cx).into_any_element();
 
-        let (create_window, reuse_window) = if self.create_new_window {
-            (
-                window.keystroke_text_for(&menu::Confirm),
-                window.keystroke_text_for(&menu::SecondaryConfirm),
-            )
-        } else {
-            (
-                window.keystroke_text_for(&menu::SecondaryConfirm),
-                window.keystroke_text_for(&menu::Confirm),
-            )
+        let (create_window_action, reuse_window_action): (&dyn Action, &dyn Action) =
+            if self.create_new_window {
+                (&menu::Confirm, &menu::SecondaryConfirm)
+            } else {
+                (&menu::SecondaryConfirm, &menu::Confirm)
+            };
+        let hint_label =
+            |text: &'static str| Label::new(text).color(Color::Muted).size(LabelSize::XSmall);
+        let keybinding_hint = match (
+            KeyBinding::for_action_in(reuse_window_action, &self.focus_handle, window, cx),
+            KeyBinding::for_action_in(create_window_action, &self.focus_handle, window, cx),
+        ) {
+            (Some(reuse_window), Some(create_window)) => h_flex()
+                .gap_1()
+                .child(reuse_window.size(rems_from_px(12.)))
+                .child(hint_label("reuses this window,"))
+                .child(create_window.size(rems_from_px(12.)))
+                .child(hint_label("opens a new one"))
+                .into_any_element(),
+            _ => Label::new(format!(
+                "{} reuses this window, {} opens a new one",
+                window.keystroke_text_for(reuse_window_action),
+                window.keystroke_text_for(create_window_action),
+            ))
+            .color(Color::Muted)
+            .size(LabelSize::XSmall)
+            .into_any_element(),
         };
-        let placeholder_text = Arc::from(format!(
-            "{reuse_window} reuses this window, {create_window} opens a new one",
-        ));
 
         Modal::new("remote-projects", None)
             .header(
                 ModalHeader::new()
-                    .child(Headline::new("Remote Projects").size(HeadlineSize::XSmall))
                     .child(
-                        Label::new(placeholder_text)
-                            .color(Color::Muted)
-                            .size(LabelSize::XSmall),
-                    ),
+                        h_flex()
+                            .justify_between()
+                            .child(Headline::new("Remote Projects").size(HeadlineSize::XSmall))
+                            .child(
+                                IconButton::new("keep-remote-projects-open", IconName::Pin)
+                                    .icon_size(IconSize::Small)
+                                    .shape(IconButtonShape::Square)
+                                    .toggle_state(self.keep_open)
+                                    .selected_icon_color(Color::Accent)
+                                    .tooltip(Tooltip::text(if self.keep_open {
+                                        "Close After Opening a Project"
+                                    } else {
+                                        "Keep Open After Opening a Project"
+                                    }))
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.keep_open = !this.keep_open;
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                    .child(keybinding_hint),
             )
             .section(
                 Section::new().padded(false).child(
@@ -1617,6 +3217,8 @@ impl RemoteServerProjects {
             );
         });
 
+        // Only the host is saved: ssh reads the rest of its configuration, including any proxy,
+        // from ssh_config on every connection, so copying it here would go stale.
         self.add_ssh_server(
             SshConnectionOptions {
                 host: ssh_config_host.to_string(),
@@ -1634,13 +3236,20 @@ fn spawn_ssh_config_watch(fs: Arc<dyn Fs>, cx: &Context<RemoteServerProjects>) -
         watch_config_file(cx.background_executor(), fs.clone(), user_ssh_config_file());
     let mut global_ssh_config_watcher = watch_config_file(
         cx.background_executor(),
-        fs,
+        fs.clone(),
         global_ssh_config_file().to_owned(),
     );
+    let user_ssh_config_dir = user_ssh_config_file()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let global_ssh_config_dir = global_ssh_config_file().parent().unwrap_or(Path::new("/"));
 
     cx.spawn(async move |remote_server_projects, cx| {
         let mut global_hosts = BTreeSet::default();
         let mut user_hosts = BTreeSet::default();
+        let mut global_proxies = Vec::new();
+        let mut user_proxies = Vec::new();
         let mut running_receivers = 2;
 
         loop {
@@ -1648,9 +3257,12 @@ fn spawn_ssh_config_watch(fs: Arc<dyn Fs>, cx: &Context<RemoteServerProjects>) -
                 new_global_file_contents = global_ssh_config_watcher.next().fuse() => {
                     match new_global_file_contents {
                         Some(new_global_file_contents) => {
-                            global_hosts = parse_ssh_config_hosts(&new_global_file_contents);
+                            let configs = load_ssh_config_with_includes(&new_global_file_contents, global_ssh_config_dir, fs.as_ref()).await;
+                            global_hosts = configs.iter().flat_map(|config| parse_ssh_config_hosts(config)).collect();
+                            global_proxies = configs.iter().flat_map(|config| parse_ssh_config_proxies(config)).collect();
                             if remote_server_projects.update(cx, |remote_server_projects, cx| {
                                 remote_server_projects.ssh_config_servers = global_hosts.iter().chain(user_hosts.iter()).map(SharedString::from).collect();
+                                remote_server_projects.ssh_config_proxies = user_proxies.iter().chain(global_proxies.iter()).cloned().collect();
                                 cx.notify();
                             }).is_err() {
                                 return;
@@ -1667,9 +3279,12 @@ fn spawn_ssh_config_watch(fs: Arc<dyn Fs>, cx: &Context<RemoteServerProjects>) -
                 new_user_file_contents = user_ssh_config_watcher.next().fuse() => {
                     match new_user_file_contents {
                         Some(new_user_file_contents) => {
-                            user_hosts = parse_ssh_config_hosts(&new_user_file_contents);
+                            let configs = load_ssh_config_with_includes(&new_user_file_contents, &user_ssh_config_dir, fs.as_ref()).await;
+                            user_hosts = configs.iter().flat_map(|config| parse_ssh_config_hosts(config)).collect();
+                            user_proxies = configs.iter().flat_map(|config| parse_ssh_config_proxies(config)).collect();
                             if remote_server_projects.update(cx, |remote_server_projects, cx| {
                                 remote_server_projects.ssh_config_servers = global_hosts.iter().chain(user_hosts.iter()).map(SharedString::from).collect();
+                                remote_server_projects.ssh_config_proxies = user_proxies.iter().chain(global_proxies.iter()).cloned().collect();
                                 cx.notify();
                             }).is_err() {
                                 return;
@@ -1709,7 +3324,9 @@ impl Render for RemoteServerProjects {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .elevation_3(cx)
-            .w(rems(34.))
+            .when(!matches!(self.mode, Mode::ProjectPicker(_)), |this| {
+                this.w(rems(MODAL_WIDTH))
+            })
             .key_context("RemoteServerModal")
             .on_action(cx.listener(Self::cancel))
             .on_action(cx.listener(Self::confirm))
@@ -1735,6 +3352,146 @@ impl Render for RemoteServerProjects {
                 Mode::EditNickname(state) => self
                     .render_edit_nickname(state, window, cx)
                     .into_any_element(),
+                Mode::EditShell(state) => {
+                    self.render_edit_shell(state, window, cx).into_any_element()
+                }
+                Mode::EditConnection(state) => self
+                    .render_edit_connection(state, window, cx)
+                    .into_any_element(),
+                Mode::OpenRemotePath(state) => self
+                    .render_open_remote_path(state, window, cx)
+                    .into_any_element(),
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_absolute_remote_path() {
+        for path in [
+            "/",
+            "/home/me/project",
+            "~",
+            "~/project",
+            "C:",
+            "C:\\repo",
+            "d:/repo",
+        ] {
+            assert!(is_absolute_remote_path(path), "{path:?} should be absolute");
+        }
+        for path in [
+            "",
+            "project",
+            "./project",
+            "~me/project",
+            "1:/repo",
+            "C:repo",
+        ] {
+            assert!(
+                !is_absolute_remote_path(path),
+                "{path:?} should be relative"
+            );
+        }
+    }
+
+    #[test]
+    fn test_record_last_opened_project() {
+        let connection = |host: &str, port: Option<u16>| SshConnection {
+            host: host.to_owned().into(),
+            port,
+            ..SshConnection::default()
+        };
+        let mut settings = RemoteSettingsContent {
+            ssh_connections: Some(vec![
+                connection("example.com", None),
+                connection("example.com", Some(2222)),
+            ]),
+            ..RemoteSettingsContent::default()
+        };
+        let options = SshConnectionOptions {
+            host: "example.com".to_owned(),
+            port: Some(2222),
+            ..SshConnectionOptions::default()
+        };
+
+        record_last_opened_project(&mut settings, &options, vec!["/srv/app".to_owned()]);
+        let last_opened_projects = |settings: &RemoteSettingsContent| {
+            settings
+                .ssh_connections
+                .iter()
+                .flatten()
+                .map(|connection| connection.last_opened_project.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            last_opened_projects(&settings),
+            [None, Some(vec!["/srv/app".to_owned()])]
+        );
+
+        record_last_opened_project(&mut settings, &options, vec!["/srv/other".to_owned()]);
+        assert_eq!(
+            last_opened_projects(&settings),
+            [None, Some(vec!["/srv/other".to_owned()])]
+        );
+
+        let unknown = SshConnectionOptions {
+            host: "elsewhere.com".to_owned(),
+            ..SshConnectionOptions::default()
+        };
+        record_last_opened_project(&mut settings, &unknown, vec!["/tmp".to_owned()]);
+        assert_eq!(
+            last_opened_projects(&settings),
+            [None, Some(vec!["/srv/other".to_owned()])]
+        );
+    }
+
+    #[test]
+    fn test_remove_ssh_project() {
+        let project = |path: &str| SshProject {
+            paths: vec![path.to_owned()],
+            ..SshProject::default()
+        };
+        let mut server = SshConnection {
+            host: "example.com".to_owned().into(),
+            projects: BTreeSet::from_iter([project("/srv/app"), project("/srv/other")]),
+            last_opened_project: Some(vec!["/srv/app".to_owned()]),
+            ..SshConnection::default()
+        };
+
+        remove_ssh_project(&mut server, &project("/srv/other"));
+        assert_eq!(server.projects.len(), 1);
+        assert_eq!(
+            server.last_opened_project,
+            Some(vec!["/srv/app".to_owned()])
+        );
+
+        remove_ssh_project(&mut server, &project("/srv/app"));
+        assert!(server.projects.is_empty());
+        assert_eq!(server.last_opened_project, None);
+    }
+
+    #[test]
+    fn test_prefill_username() {
+        smol::block_on(KEY_VALUE_STORE.write_kvp(
+            last_username_key("prefill.example.com"),
+            "deploy".to_owned(),
+        ))
+        .unwrap();
+
+        let mut options = SshConnectionOptions::parse_command_line("prefill.example.com").unwrap();
+        prefill_username(&mut options);
+        assert_eq!(options.username.as_deref(), Some("deploy"));
+
+        let mut options =
+            SshConnectionOptions::parse_command_line("admin@prefill.example.com").unwrap();
+        prefill_username(&mut options);
+        assert_eq!(options.username.as_deref(), Some("admin"));
+
+        let mut options = SshConnectionOptions::parse_command_line("unknown.example.com").unwrap();
+        prefill_username(&mut options);
+        assert_eq!(options.username, None);
+    }
+}
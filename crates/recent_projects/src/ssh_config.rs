@@ -1,4 +1,13 @@
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
+
+use futures::StreamExt as _;
+use project::Fs;
+
+/// Matches the nesting limit ssh itself applies to `Include` directives.
+const MAX_INCLUDE_DEPTH: usize = 16;
 
 pub fn parse_ssh_config_hosts(config: &str) -> BTreeSet<String> {
     let mut hosts = BTreeSet::new();
@@ -38,19 +47,282 @@ pub fn parse_ssh_config_hosts(config: &str) -> BTreeSet<String> {
     hosts
 }
 
+/// Like [`parse_ssh_config_hosts`], but also collects the hosts of every file pulled in through
+/// `Include` directives, recursively. Relative include paths are resolved against the directory
+/// of the file including them, which is `config_dir` for `config` itself.
+pub async fn parse_ssh_config_hosts_with_includes(
+    config: &str,
+    config_dir: &Path,
+    fs: &dyn Fs,
+) -> BTreeSet<String> {
+    load_ssh_config_with_includes(config, config_dir, fs)
+        .await
+        .iter()
+        .flat_map(|config| parse_ssh_config_hosts(config))
+        .collect()
+}
+
+/// Returns the contents of `config` followed by those of every file it includes, recursively.
+/// See [`parse_ssh_config_hosts_with_includes`] for how include paths are resolved.
+pub async fn load_ssh_config_with_includes(
+    config: &str,
+    config_dir: &Path,
+    fs: &dyn Fs,
+) -> Vec<String> {
+    let mut configs = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![(config.to_owned(), config_dir.to_path_buf(), 0)];
+    while let Some((config, directory, depth)) = pending.pop() {
+        if depth >= MAX_INCLUDE_DEPTH {
+            log::debug!("Not following ssh config includes nested deeper than {MAX_INCLUDE_DEPTH}");
+        } else {
+            for include in include_patterns(&config) {
+                for path in resolve_include(include, &directory, fs).await {
+                    if !visited.insert(path.clone()) {
+                        continue;
+                    }
+                    match fs.load(&path).await {
+                        Ok(contents) => {
+                            let directory = path.parent().unwrap_or(&directory).to_path_buf();
+                            pending.push((contents, directory, depth + 1))
+                        }
+                        Err(e) => {
+                            log::debug!("Failed to read included ssh config {path:?}: {e:#}")
+                        }
+                    }
+                }
+            }
+        }
+        configs.push(config);
+    }
+    configs
+}
+
+/// The proxy a `Host` block of an ssh_config connects through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SshConfigProxy {
+    patterns: Vec<String>,
+    proxy: Proxy,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Proxy {
+    Jump(String),
+    Command(String),
+}
+
+/// Collects the `ProxyJump` and `ProxyCommand` settings of every `Host` block in a config, in
+/// the order they appear. Settings in `Match` blocks are skipped, as their criteria can't be
+/// evaluated without connecting.
+pub fn parse_ssh_config_proxies(config: &str) -> Vec<SshConfigProxy> {
+    let mut proxies = Vec::new();
+    // Settings before the first `Host` line apply to every host.
+    let mut patterns = Some(vec!["*".to_owned()]);
+    for line in config.lines() {
+        let line = line.trim();
+        let Some((keyword, value)) = line.split_once(|c: char| c.is_whitespace() || c == '=')
+        else {
+            continue;
+        };
+        let value = value
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+            .trim();
+        let proxy = if keyword.eq_ignore_ascii_case("Host") {
+            patterns = Some(value.split_whitespace().map(str::to_owned).collect());
+            continue;
+        } else if keyword.eq_ignore_ascii_case("Match") {
+            patterns = None;
+            continue;
+        } else if keyword.eq_ignore_ascii_case("ProxyJump") {
+            Proxy::Jump(value.to_owned())
+        } else if keyword.eq_ignore_ascii_case("ProxyCommand") {
+            Proxy::Command(value.to_owned())
+        } else {
+            continue;
+        };
+        if let Some(patterns) = &patterns {
+            proxies.push(SshConfigProxy {
+                patterns: patterns.clone(),
+                proxy,
+            });
+        }
+    }
+    proxies
+}
+
+/// Returns the ssh arguments that make a connection to `host` go through the proxy configured
+/// for it. As in ssh, the first matching `ProxyJump` or `ProxyCommand` wins, and `none`
+/// disables proxying.
+pub fn proxy_args_for_host(host: &str, proxies: &[SshConfigProxy]) -> Vec<String> {
+    let proxy = proxies
+        .iter()
+        .find(|proxy| host_matches_patterns(host, proxy.patterns.iter().map(String::as_str)));
+    match proxy.map(|proxy| &proxy.proxy) {
+        Some(Proxy::Jump(jump)) if !jump.eq_ignore_ascii_case("none") => {
+            vec!["-J".to_owned(), jump.clone()]
+        }
+        Some(Proxy::Command(command)) if !command.eq_ignore_ascii_case("none") => {
+            vec!["-o".to_owned(), format!("ProxyCommand={command}")]
+        }
+        Some(_) | None => Vec::new(),
+    }
+}
+
+/// Lists the paths of all `Include` directives in a config, in the order they appear.
+fn include_patterns(config: &str) -> Vec<&str> {
+    config
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (keyword, arguments) = line.split_once(|c: char| c.is_whitespace() || c == '=')?;
+            keyword
+                .eq_ignore_ascii_case("Include")
+                .then(|| arguments.trim_start_matches(|c: char| c.is_whitespace() || c == '='))
+        })
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+/// Expands a single `Include` argument of a file in `directory` into the files it refers to. A
+/// leading `~` stands for the home directory, and `*`/`?` wildcards are supported in any path
+/// component, with matches sorted lexically as ssh does.
+async fn resolve_include(include: &str, directory: &Path, fs: &dyn Fs) -> Vec<PathBuf> {
+    let path = if include == "~" {
+        paths::home_dir().clone()
+    } else if let Some(relative_to_home) = include.strip_prefix("~/") {
+        paths::home_dir().join(relative_to_home)
+    } else {
+        directory.join(include)
+    };
+
+    let mut candidates = vec![PathBuf::new()];
+    let mut components = path.components().peekable();
+    while let Some(component) = components.next() {
+        let is_last = components.peek().is_none();
+        let pattern = component
+            .as_os_str()
+            .to_str()
+            .filter(|component| component.contains(['*', '?']));
+        let Some(pattern) = pattern else {
+            for candidate in &mut candidates {
+                candidate.push(component);
+            }
+            continue;
+        };
+
+        let mut matches = Vec::new();
+        for directory in candidates {
+            let Ok(mut entries) = fs.read_dir(&directory).await else {
+                continue;
+            };
+            while let Some(entry) = entries.next().await {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                let matches_pattern = entry
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| wildcard_matches(name, pattern));
+                // Intermediate components only need to be traversable, the final file is
+                // checked below.
+                if matches_pattern && (is_last || fs.is_dir(&entry).await) {
+                    matches.push(entry);
+                }
+            }
+        }
+        matches.sort();
+        candidates = matches;
+    }
+
+    let mut files = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        if fs.is_file(&candidate).await {
+            files.push(candidate);
+        }
+    }
+    files
+}
+
 fn parse_hosts_from(line: &str, hosts: &mut BTreeSet<String>) {
     hosts.extend(
         line.split_whitespace()
-            .filter(|field| !field.starts_with("!"))
-            .filter(|field| !field.contains("*"))
-            .filter(|field| !field.is_empty())
+            .filter(|field| is_concrete_host(field))
             .map(|field| field.to_owned()),
     );
 }
 
+/// Whether a `Host` token names a host that can be connected to directly, as opposed to a
+/// wildcard or negated pattern, or a stray line continuation.
+fn is_concrete_host(host: &str) -> bool {
+    !host.is_empty() && host != "\\" && !is_host_pattern(host)
+}
+
+/// Whether a `Host` entry is a pattern rather than a concrete, connectable host.
+fn is_host_pattern(host: &str) -> bool {
+    host.starts_with('!') || host.contains(['*', '?'])
+}
+
+/// Checks a concrete host against the patterns of a single `Host` line, following ssh_config
+/// semantics: any matching negated pattern rejects the host, otherwise at least one of the
+/// positive patterns has to match.
+pub fn host_matches_patterns<'a>(host: &str, patterns: impl IntoIterator<Item = &'a str>) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if host_matches_pattern(host, negated) {
+                return false;
+            }
+        } else if host_matches_pattern(host, pattern) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Matches a host against a single glob pattern, where `*` matches any run of characters and
+/// `?` matches exactly one. Host names are compared case-insensitively.
+fn host_matches_pattern(host: &str, pattern: &str) -> bool {
+    wildcard_matches(&host.to_lowercase(), &pattern.to_lowercase())
+}
+
+/// Glob matching where `*` matches any run of characters and `?` matches exactly one.
+fn wildcard_matches(text: &str, pattern: &str) -> bool {
+    let host = text.chars().collect::<Vec<_>>();
+    let pattern = pattern.chars().collect::<Vec<_>>();
+
+    let (mut host_ix, mut pattern_ix) = (0, 0);
+    let mut last_star = None;
+    while host_ix < host.len() {
+        match pattern.get(pattern_ix) {
+            Some('*') => {
+                last_star = Some((pattern_ix, host_ix));
+                pattern_ix += 1;
+            }
+            Some(&c) if c == '?' || c == host[host_ix] => {
+                host_ix += 1;
+                pattern_ix += 1;
+            }
+            _ => match last_star {
+                Some((star_ix, star_host_ix)) => {
+                    pattern_ix = star_ix + 1;
+                    host_ix = star_host_ix + 1;
+                    last_star = Some((star_ix, host_ix));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[pattern_ix..].iter().all(|&c| c == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use remote::SshConnectionOptions;
+    use serde_json::json;
+    use util::path;
 
     #[test]
     fn test_thank_you_bjorn3() {
@@ -79,7 +351,10 @@ mod tests {
         Host \
                somehost \
         anotherhost
-        Hostname 192.168.3.3";
+        Hostname 192.168.3.3
+
+        Host db? cache-*.internal
+          User ops";
 
         let expected_hosts = BTreeSet::from_iter([
             "something".to_owned(),
@@ -93,4 +368,161 @@ mod tests {
 
         assert_eq!(expected_hosts, parse_ssh_config_hosts(hosts));
     }
+
+    #[test]
+    fn test_concrete_hosts() {
+        let config = "
+            Host *.internal db? !bastion
+              User ops
+
+            Host jump *.corp
+              HostName jump.corp
+
+            Match host foo exec \"true\"
+              User matched
+
+            Match originalhost bar
+            Host dev
+              HostName dev.example.com";
+        assert_eq!(
+            parse_ssh_config_hosts(config),
+            BTreeSet::from_iter(["jump", "dev"].map(str::to_owned))
+        );
+
+        assert!(is_concrete_host("dev"));
+        assert!(is_concrete_host("192.168.3.3"));
+        assert!(!is_concrete_host("*"));
+        assert!(!is_concrete_host("*.internal"));
+        assert!(!is_concrete_host("db?"));
+        assert!(!is_concrete_host("!bastion"));
+        assert!(!is_concrete_host(""));
+        assert!(!is_concrete_host("\\"));
+    }
+
+    #[test]
+    fn test_proxy_args() {
+        let config = "
+            Host web
+              ProxyJump jump
+
+            Host db
+              HostName db.internal
+              ProxyCommand ssh -W %h:%p bastion
+
+            Host direct
+              ProxyJump none
+
+            Match host direct
+              ProxyJump ignored
+
+            Host *.internal
+              ProxyJump=bastion";
+        let proxies = parse_ssh_config_proxies(config);
+
+        let options = SshConnectionOptions {
+            host: "web".to_owned(),
+            args: Some(proxy_args_for_host("web", &proxies)),
+            ..SshConnectionOptions::default()
+        };
+        assert_eq!(options.additional_args(), ["-J", "jump"]);
+
+        assert_eq!(
+            proxy_args_for_host("db", &proxies),
+            ["-o", "ProxyCommand=ssh -W %h:%p bastion"]
+        );
+        assert_eq!(
+            proxy_args_for_host("cache.internal", &proxies),
+            ["-J", "bastion"]
+        );
+        assert!(proxy_args_for_host("direct", &proxies).is_empty());
+        assert!(proxy_args_for_host("elsewhere", &proxies).is_empty());
+    }
+
+    #[test]
+    fn test_host_pattern_matching() {
+        assert!(host_matches_patterns("db.internal", ["*.internal"]));
+        assert!(host_matches_patterns("DB.Internal", ["*.internal"]));
+        assert!(host_matches_patterns("anything", ["*"]));
+        assert!(!host_matches_patterns("internal", ["*.internal"]));
+        assert!(!host_matches_patterns(
+            "db.internal.example.com",
+            ["*.internal"]
+        ));
+
+        assert!(host_matches_patterns("db1", ["db?"]));
+        assert!(!host_matches_patterns("db", ["db?"]));
+        assert!(!host_matches_patterns("db12", ["db?"]));
+        assert!(host_matches_patterns("db12", ["db?*"]));
+
+        assert!(host_matches_patterns(
+            "web.internal",
+            ["*.internal", "!db.internal"]
+        ));
+        assert!(!host_matches_patterns(
+            "db.internal",
+            ["*.internal", "!db.internal"]
+        ));
+        assert!(!host_matches_patterns("db.internal", ["!db.*"]));
+        assert!(!host_matches_patterns("web.internal", ["!db.*"]));
+
+        assert!(host_matches_patterns("rpi", ["rpi"]));
+        assert!(!host_matches_patterns("rpi2", ["rpi"]));
+    }
+
+    #[gpui::test]
+    async fn test_ssh_config_includes(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/ssh"),
+            json!({
+                "config": "",
+                "config.d": {
+                    "10-work": "Host work-box\n  HostName work.example.com\n\nInclude nested/extra",
+                    "20-home": "Host nas rpi\n  User pi",
+                    "README": "Host not-included",
+                    "nested": {
+                        "extra": "Host build-server",
+                    },
+                },
+                "nested": {
+                    "extra": "Host not-relative-to-the-including-file",
+                },
+                "projects": {
+                    "alpha": { "ssh_config": "Host alpha-dev" },
+                    "beta": { "ssh_config": "Host beta-dev" },
+                    "gamma": { "README": "Host not-a-config" },
+                },
+                "other": "Host other\nInclude config",
+            }),
+        )
+        .await;
+
+        let config = "
+            Host direct
+              User me
+
+            Include config.d/*-*
+            include=other
+            Include projects/*/ssh_config";
+        let hosts =
+            parse_ssh_config_hosts_with_includes(config, Path::new(path!("/ssh")), fs.as_ref())
+                .await;
+
+        assert_eq!(
+            hosts,
+            BTreeSet::from_iter(
+                [
+                    "direct",
+                    "work-box",
+                    "build-server",
+                    "nas",
+                    "rpi",
+                    "other",
+                    "alpha-dev",
+                    "beta-dev",
+                ]
+                .map(str::to_owned)
+            )
+        );
+    }
 }
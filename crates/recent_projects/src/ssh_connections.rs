@@ -1,4 +1,5 @@
-use std::collections::BTreeSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::{Context as _, Result};
@@ -13,19 +14,25 @@ use gpui::{
 
 use language::CursorShape;
 use markdown::{Markdown, MarkdownElement, MarkdownStyle};
+use project::Fs;
+use project_panel::ProjectPanel;
 use release_channel::ReleaseChannel;
 use remote::ssh_session::{ConnectionIdentifier, SshPortForwardOption};
-use remote::{SshConnectionOptions, SshPlatform, SshRemoteClient};
+use remote::{
+    HostKeyChecking, SshConnectionOptions, SshPlatform, SshRemoteClient, SshRemoteEvent,
+    is_host_key_changed_error,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsSources};
+use settings::{Settings, SettingsSources, update_settings_file};
+use task::{SpawnInTerminal, TaskId, TaskVariables, VariableName};
 use theme::ThemeSettings;
 use ui::{
     ActiveTheme, Color, Context, Icon, IconName, IconSize, InteractiveElement, IntoElement, Label,
     LabelCommon, Styled, Window, prelude::*,
 };
-use util::serde::default_true;
-use workspace::{AppState, ModalView, Workspace};
+use util::{ResultExt, serde::default_true};
+use workspace::{AppState, ModalView, Toast, Workspace, notifications::NotificationId};
 
 #[derive(Deserialize)]
 pub struct SshSettings {
@@ -33,6 +40,23 @@ pub struct SshSettings {
     /// Whether to read ~/.ssh/config for ssh connection sources.
     #[serde(default = "default_true")]
     pub read_ssh_config: bool,
+    /// How long to wait for a new server to accept a TCP connection on its SSH port before
+    /// reporting it as unreachable, in milliseconds. `0` skips the check.
+    #[serde(default = "default_reachability_timeout_ms")]
+    pub reachability_timeout_ms: u64,
+    #[serde(default)]
+    pub remote: RemoteProjectSettings,
+}
+
+fn default_reachability_timeout_ms() -> u64 {
+    3000
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct RemoteProjectSettings {
+    /// Whether to reveal the project panel once a remote project has connected.
+    #[serde(default)]
+    pub open_project_panel_on_connect: bool,
 }
 
 impl SshSettings {
@@ -40,6 +64,30 @@ impl SshSettings {
         self.ssh_connections.clone().into_iter().flatten()
     }
 
+    /// The timeout of the reachability check done before connecting to a new server, if enabled.
+    pub fn reachability_timeout(&self) -> Option<Duration> {
+        (self.reachability_timeout_ms > 0)
+            .then(|| Duration::from_millis(self.reachability_timeout_ms))
+    }
+
+    /// Probes every configured connection concurrently, reporting which of them currently
+    /// answer within `timeout`.
+    pub fn probe_connections(
+        &self,
+        timeout: Duration,
+        cx: &App,
+    ) -> Task<Vec<(SshConnection, bool)>> {
+        let connections = self.ssh_connections().collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let probes = connections.iter().map(|connection| {
+                let options = SshConnectionOptions::from(connection.clone());
+                async move { options.probe(timeout).await }
+            });
+            let reachable = futures::future::join_all(probes).await;
+            connections.into_iter().zip(reachable).collect()
+        })
+    }
+
     pub fn connection_options_for(
         &self,
         host: String,
@@ -57,6 +105,8 @@ impl SshSettings {
                     username,
                     port_forwards: conn.port_forwards,
                     password: None,
+                    shell: conn.shell,
+                    host_key_checking: conn.host_key_checking,
                 };
             }
         }
@@ -93,6 +143,39 @@ pub struct SshConnection {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port_forwards: Option<Vec<SshPortForwardOption>>,
+
+    /// The shell to start for terminals opened on this server, e.g. `/usr/bin/zsh`.
+    /// Falls back to the remote user's login shell when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+
+    /// Whether the `on_open_command` of this server's projects may be run when they are opened.
+    /// Off by default, as it runs commands on the server without asking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_startup_commands: Option<bool>,
+
+    /// Whether to replace the saved paths of this server's projects with their canonical ones
+    /// once opened, so that projects saved at a symlink are listed at its target instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_symlinks: Option<bool>,
+
+    /// How to verify the server's host key: `"strict"`, `"accept_new"` or `"off"`.
+    /// Uses the `StrictHostKeyChecking` setting from your ssh_config when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_key_checking: Option<HostKeyChecking>,
+
+    /// The paths of the project on this server that was opened most recently.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_opened_project: Option<Vec<String>>,
+}
+
+impl SshConnection {
+    /// Whether this is the saved connection to the server described by `connection_options`.
+    pub fn matches(&self, connection_options: &SshConnectionOptions) -> bool {
+        *self.host == connection_options.host
+            && self.port == connection_options.port
+            && self.username == connection_options.username
+    }
 }
 
 impl From<SshConnection> for SshConnectionOptions {
@@ -106,6 +189,8 @@ impl From<SshConnection> for SshConnectionOptions {
             nickname: val.nickname,
             upload_binary_over_ssh: val.upload_binary_over_ssh.unwrap_or_default(),
             port_forwards: val.port_forwards,
+            shell: val.shell,
+            host_key_checking: val.host_key_checking,
         }
     }
 }
@@ -113,12 +198,103 @@ impl From<SshConnection> for SshConnectionOptions {
 #[derive(Clone, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema)]
 pub struct SshProject {
     pub paths: Vec<String>,
+    /// Task variables to use for this project when it's opened on the server, taking
+    /// precedence over the ones computed by the language context providers.
+    /// They are available in tasks as `$ZED_CUSTOM_<NAME>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub task_variables: BTreeMap<String, String>,
+    /// A command to run in a terminal on the server once this project has been opened,
+    /// e.g. to activate a virtual environment or start a file watcher.
+    /// Only run when the server has `allow_startup_commands` enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_open_command: Option<String>,
+}
+
+impl SshSettings {
+    /// The saved project with the given paths on the server described by `connection_options`,
+    /// along with the connection it belongs to.
+    fn saved_project(
+        &self,
+        connection_options: &SshConnectionOptions,
+        paths: &[PathBuf],
+    ) -> Option<(SshConnection, SshProject)> {
+        self.ssh_connections()
+            .filter(|connection| connection.matches(connection_options))
+            .find_map(|connection| {
+                let project = connection
+                    .projects
+                    .iter()
+                    .find(|project| {
+                        project
+                            .paths
+                            .iter()
+                            .map(PathBuf::from)
+                            .eq(paths.iter().cloned())
+                    })?
+                    .clone();
+                Some((connection, project))
+            })
+    }
+
+    /// The task variable overrides of the saved project with the given paths on the server
+    /// described by `connection_options`.
+    fn task_variable_overrides(
+        &self,
+        connection_options: &SshConnectionOptions,
+        paths: &[PathBuf],
+    ) -> Option<TaskVariables> {
+        let (_, project) = self.saved_project(connection_options, paths)?;
+        Some(TaskVariables::from_iter(
+            project
+                .task_variables
+                .into_iter()
+                .map(|(name, value)| (VariableName::Custom(Cow::Owned(name)), value)),
+        ))
+    }
+
+    /// Whether the saved projects of the server described by `connection_options` should be
+    /// updated to their canonical paths once opened.
+    fn resolves_symlinks(&self, connection_options: &SshConnectionOptions) -> bool {
+        self.ssh_connections().any(|connection| {
+            connection.matches(connection_options) && connection.resolve_symlinks.unwrap_or(false)
+        })
+    }
+
+    /// The command to run once the saved project with the given paths has been opened, if it has
+    /// one and its server allows startup commands.
+    fn startup_command(
+        &self,
+        connection_options: &SshConnectionOptions,
+        paths: &[PathBuf],
+    ) -> Option<String> {
+        let (connection, project) = self.saved_project(connection_options, paths)?;
+        if !connection.allow_startup_commands.unwrap_or(false) {
+            return None;
+        }
+        project
+            .on_open_command
+            .filter(|command| !command.trim().is_empty())
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub read_ssh_config: Option<bool>,
+    /// How long to wait for a new server to accept a TCP connection on its SSH port before
+    /// reporting it as unreachable, in milliseconds. `0` skips the check.
+    ///
+    /// Default: 3000
+    pub reachability_timeout_ms: Option<u64>,
+    pub remote: Option<RemoteProjectSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteProjectSettingsContent {
+    /// Whether to reveal the project panel once a remote project has connected.
+    ///
+    /// Default: false
+    pub open_project_panel_on_connect: Option<bool>,
 }
 
 impl Settings for SshSettings {
@@ -536,8 +712,92 @@ pub fn connect_over_ssh(
     )
 }
 
+/// Replaces the saved project at `paths` on the server described by `connection_options` with
+/// one at `canonical_paths`, keeping the rest of its settings.
+fn save_canonical_project_paths(
+    connection_options: &SshConnectionOptions,
+    paths: &[PathBuf],
+    canonical_paths: Vec<PathBuf>,
+    fs: Arc<dyn Fs>,
+    cx: &mut App,
+) {
+    let connection_options = connection_options.clone();
+    let paths = paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let canonical_paths = canonical_paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    update_settings_file::<SshSettings>(fs, cx, move |setting, _| {
+        let Some(connection) = setting
+            .ssh_connections
+            .iter_mut()
+            .flatten()
+            .find(|connection| connection.matches(&connection_options))
+        else {
+            return;
+        };
+        let Some(mut project) = connection
+            .projects
+            .iter()
+            .find(|project| project.paths == paths)
+            .cloned()
+        else {
+            return;
+        };
+        connection.projects.remove(&project);
+        if !connection
+            .projects
+            .iter()
+            .any(|existing| existing.paths == canonical_paths)
+        {
+            project.paths = canonical_paths;
+            connection.projects.insert(project);
+        }
+    });
+}
+
+/// Tells which port forwards came back after reconnecting to the server.
+fn show_port_forwards_toast(
+    workspace: &mut Workspace,
+    restored: &[SshPortForwardOption],
+    failed: &[(SshPortForwardOption, SharedString)],
+    cx: &mut Context<Workspace>,
+) {
+    struct PortForwardsReestablished;
+
+    let mut lines = Vec::new();
+    if !restored.is_empty() {
+        let restored = restored
+            .iter()
+            .map(|forward| forward.spec())
+            .collect::<Vec<_>>();
+        lines.push(format!("Restored port forwards: {}", restored.join(", ")));
+    }
+    for (forward, error) in failed {
+        lines.push(format!(
+            "Could not restore port forward {}: {error}",
+            forward.spec()
+        ));
+    }
+    let toast = Toast::new(
+        NotificationId::unique::<PortForwardsReestablished>(),
+        lines.join("\n"),
+    );
+    workspace.show_toast(
+        if failed.is_empty() {
+            toast.autohide()
+        } else {
+            toast
+        },
+        cx,
+    );
+}
+
 pub async fn open_ssh_project(
-    connection_options: SshConnectionOptions,
+    mut connection_options: SshConnectionOptions,
     paths: Vec<PathBuf>,
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
@@ -633,7 +893,32 @@ pub async fn open_ssh_project(
             })
             .ok();
 
-        if let Err(e) = did_open_ssh_project {
+        let opened = did_open_ssh_project.is_ok();
+        if let Err(e) = &did_open_ssh_project
+            && is_host_key_changed_error(e)
+        {
+            log::error!("Failed to open project: {e:?}");
+            let response = window
+                .update(cx, |_, window, cx| {
+                    window.prompt(
+                        PromptLevel::Critical,
+                        "The host key of the server has changed",
+                        Some(&format!(
+                            "This happens when the server has been rebuilt, but could also mean \
+                            that someone is impersonating it.\n\n{e}"
+                        )),
+                        &["Forget Old Key and Connect", "Cancel"],
+                        cx,
+                    )
+                })?
+                .await;
+
+            if response == Ok(0) {
+                connection_options.forget_host_key().await.log_err();
+                connection_options.host_key_checking = Some(HostKeyChecking::AcceptNew);
+                continue;
+            }
+        } else if let Err(e) = did_open_ssh_project {
             log::error!("Failed to open project: {e:?}");
             let response = window
                 .update(cx, |_, window, cx| {
@@ -653,11 +938,83 @@ pub async fn open_ssh_project(
         }
 
         window
-            .update(cx, |workspace, _, cx| {
+            .update(cx, |workspace, window, cx| {
                 if let Some(client) = workspace.project().read(cx).ssh_client().clone() {
+                    if opened {
+                        cx.subscribe_in(&client, window, |workspace, _, event, _, cx| {
+                            if let SshRemoteEvent::PortForwardsReestablished { restored, failed } =
+                                event
+                            {
+                                show_port_forwards_toast(workspace, restored, failed, cx);
+                            }
+                        })
+                        .detach();
+                    }
                     ExtensionStore::global(cx)
                         .update(cx, |store, cx| store.register_ssh_client(client, cx));
                 }
+                if opened
+                    && SshSettings::get_global(cx)
+                        .remote
+                        .open_project_panel_on_connect
+                {
+                    workspace.focus_panel::<ProjectPanel>(window, cx);
+                }
+                if opened
+                    && let Some(overrides) = SshSettings::get_global(cx)
+                        .task_variable_overrides(&connection_options, &paths)
+                {
+                    workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .update(cx, |task_store, _| {
+                            task_store.set_variable_overrides(overrides);
+                        });
+                }
+                if opened && SshSettings::get_global(cx).resolves_symlinks(&connection_options) {
+                    let canonical_paths = workspace
+                        .project()
+                        .read(cx)
+                        .visible_worktrees(cx)
+                        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                        .collect::<Vec<_>>();
+                    if canonical_paths.len() == paths.len() && canonical_paths != paths {
+                        save_canonical_project_paths(
+                            &connection_options,
+                            &paths,
+                            canonical_paths,
+                            app_state.fs.clone(),
+                            cx,
+                        );
+                    }
+                }
+                if opened
+                    && let Some(command) =
+                        SshSettings::get_global(cx).startup_command(&connection_options, &paths)
+                {
+                    let label = format!("Startup: {command}");
+                    workspace
+                        .spawn_in_terminal(
+                            SpawnInTerminal {
+                                id: TaskId(format!(
+                                    "remote-startup-{}",
+                                    connection_options.connection_string()
+                                )),
+                                full_label: label.clone(),
+                                label,
+                                command: Some(command.clone()),
+                                command_label: command,
+                                cwd: paths.first().cloned(),
+                                use_new_terminal: true,
+                                allow_concurrent_runs: true,
+                                ..SpawnInTerminal::default()
+                            },
+                            window,
+                            cx,
+                        )
+                        .detach();
+                }
             })
             .ok();
 
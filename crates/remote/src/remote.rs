@@ -4,6 +4,6 @@ pub mod proxy;
 pub mod ssh_session;
 
 pub use ssh_session::{
-    ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshRemoteClient,
-    SshRemoteEvent,
+    ConnectionState, HostKeyChecking, SshClientDelegate, SshConnectionOptions, SshPlatform,
+    SshRemoteClient, SshRemoteEvent, is_host_key_changed_error,
 };
@@ -19,7 +19,7 @@ use futures::{
 };
 use gpui::{
     App, AppContext as _, AsyncApp, BackgroundExecutor, BorrowAppContext, Context, Entity,
-    EventEmitter, Global, SemanticVersion, Task, WeakEntity,
+    EventEmitter, Global, SemanticVersion, SharedString, Task, WeakEntity,
 };
 use itertools::Itertools;
 use parking_lot::Mutex;
@@ -38,6 +38,7 @@ use smol::{
 };
 use std::{
     any::TypeId,
+    borrow::Cow,
     collections::VecDeque,
     fmt, iter,
     ops::ControlFlow,
@@ -78,6 +79,93 @@ pub struct SshPortForwardOption {
     pub remote_port: u16,
 }
 
+impl SshPortForwardOption {
+    /// The forward in the `[bind_address:]port:host:hostport` form taken by `ssh -L`.
+    pub fn spec(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.local_host.as_deref().unwrap_or("localhost"),
+            self.local_port,
+            self.remote_host.as_deref().unwrap_or("localhost"),
+            self.remote_port
+        )
+    }
+}
+
+/// How ssh should verify the key of the host it connects to, passed as `StrictHostKeyChecking`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HostKeyChecking {
+    /// Refuse to connect to unknown hosts and hosts whose key has changed.
+    Strict,
+    /// Add the keys of unknown hosts to `known_hosts`, but refuse hosts whose key has changed.
+    AcceptNew,
+    /// Connect even if the host's key is unknown or has changed.
+    Off,
+}
+
+impl HostKeyChecking {
+    fn ssh_option(self) -> &'static str {
+        match self {
+            Self::Strict => "StrictHostKeyChecking=yes",
+            Self::AcceptNew => "StrictHostKeyChecking=accept-new",
+            Self::Off => "StrictHostKeyChecking=no",
+        }
+    }
+}
+
+/// The banner OpenSSH prints, untranslated, when the host's key no longer matches the one in
+/// `known_hosts`. Connection errors are shared as strings between the callers awaiting them, so
+/// the error of such a connection keeps it in its message.
+const HOST_KEY_CHANGED_MARKER: &str = "REMOTE HOST IDENTIFICATION HAS CHANGED";
+
+/// The exit code of ssh when it fails to connect, as opposed to the exit code of the remote
+/// command.
+const SSH_CONNECTION_FAILED_EXIT_CODE: i32 = 255;
+
+/// Whether connecting failed because the host's key no longer matches the one in `known_hosts`,
+/// e.g. after the server was rebuilt.
+pub fn is_host_key_changed_error(error: &anyhow::Error) -> bool {
+    format!("{error:#}").contains(HOST_KEY_CHANGED_MARKER)
+}
+
+/// The error of an ssh process that exited with `exit_code` before the connection was
+/// established, reporting `stderr`.
+fn connection_failed_error(exit_code: Option<i32>, stderr: &str) -> anyhow::Error {
+    let stderr = stderr.trim();
+    if exit_code == Some(SSH_CONNECTION_FAILED_EXIT_CODE)
+        && stderr.contains(HOST_KEY_CHANGED_MARKER)
+    {
+        anyhow!("host key has changed: {stderr}")
+    } else {
+        // Keep the marker out of other errors, so that they aren't taken for a changed host key.
+        anyhow!(
+            "failed to connect: {}",
+            stderr.replace(
+                HOST_KEY_CHANGED_MARKER,
+                "remote host identification has changed"
+            )
+        )
+    }
+}
+
+/// Where ssh connects to for a host, once its ssh_config has been applied.
+#[derive(Debug, PartialEq)]
+enum ResolvedDestination {
+    Direct {
+        host: String,
+        port: u16,
+    },
+    /// The host is reached through a `ProxyJump` or a `ProxyCommand`.
+    Proxied,
+}
+
+/// Whether a probe reached the host, given how its `ssh` command exited. A host that rejects
+/// the attempt for lack of credentials still counts as reachable.
+fn probe_answered(success: bool, stderr: &str) -> bool {
+    success || stderr.contains("Permission denied")
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct SshConnectionOptions {
     pub host: String,
@@ -89,6 +177,10 @@ pub struct SshConnectionOptions {
 
     pub nickname: Option<String>,
     pub upload_binary_over_ssh: bool,
+    /// The shell to start for terminals opened on this host, instead of the user's login shell.
+    pub shell: Option<String>,
+    /// Overrides the `StrictHostKeyChecking` behavior from the user's ssh_config.
+    pub host_key_checking: Option<HostKeyChecking>,
 }
 
 pub struct SshArgs {
@@ -195,6 +287,7 @@ impl SshConnectionOptions {
                 } else {
                     anyhow::bail!("Missing port forward format");
                 }
+                continue;
             }
 
             for a in ALLOWED_ARGS {
@@ -243,9 +336,34 @@ impl SshConnectionOptions {
             password: None,
             nickname: None,
             upload_binary_over_ssh: false,
+            shell: None,
+            host_key_checking: None,
         })
     }
 
+    /// The `ssh` command line that [`Self::parse_command_line`] parses back into these options.
+    pub fn command_line(&self) -> String {
+        let mut command = String::from("ssh");
+        for arg in self.args.iter().flatten() {
+            command.push(' ');
+            command.push_str(&shlex::try_quote(arg).unwrap_or(Cow::Borrowed(arg)));
+        }
+        for forward in self.port_forwards.iter().flatten() {
+            command.push_str(" -L ");
+            command.push_str(&forward.spec());
+        }
+        command.push(' ');
+        if let Some(username) = &self.username {
+            command.push_str(username);
+            command.push('@');
+        }
+        command.push_str(&self.host);
+        if let Some(port) = self.port {
+            command.push_str(&format!(" -p {port}"));
+        }
+        command
+    }
+
     pub fn ssh_url(&self) -> String {
         let mut result = String::from("ssh://");
         if let Some(username) = &self.username {
@@ -265,27 +383,154 @@ impl SshConnectionOptions {
     pub fn additional_args(&self) -> Vec<String> {
         let mut args = self.args.iter().flatten().cloned().collect::<Vec<String>>();
 
-        if let Some(forwards) = &self.port_forwards {
-            args.extend(forwards.iter().map(|pf| {
-                let local_host = match &pf.local_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
-                let remote_host = match &pf.remote_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
+        if let Some(host_key_checking) = self.host_key_checking {
+            args.push("-o".into());
+            args.push(host_key_checking.ssh_option().into());
+        }
 
-                format!(
-                    "-L{}:{}:{}:{}",
-                    local_host, pf.local_port, remote_host, pf.remote_port
-                )
-            }));
+        if let Some(forwards) = &self.port_forwards {
+            args.extend(forwards.iter().map(|pf| format!("-L{}", pf.spec())));
         }
 
         args
     }
 
+    /// The arguments of the `ssh` command run by [`Self::probe`].
+    fn probe_args(&self, timeout: Duration) -> Vec<String> {
+        let mut args = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", timeout.as_secs().max(1)),
+        ];
+        // Skip port forwards, which would fail if the ports are already taken.
+        args.extend(self.args.iter().flatten().cloned());
+        args.push(self.ssh_url());
+        args.push("exit".to_string());
+        args
+    }
+
+    /// The arguments of the `ssh -G` command that prints the configuration ssh would use to
+    /// connect to the host.
+    fn resolve_config_args(&self) -> Vec<String> {
+        let mut args = self.args.iter().flatten().cloned().collect::<Vec<_>>();
+        args.extend(self.port.map(|port| format!("-p{port}")));
+        args.push("-G".to_string());
+        args.push(self.host.clone());
+        args
+    }
+
+    /// Reads the destination ssh would connect to from the output of `ssh -G`, falling back to
+    /// the host and port of these options for the values it leaves out.
+    fn parse_resolved_destination(&self, ssh_config: &str) -> ResolvedDestination {
+        let mut host = self.host.clone();
+        let mut port = self.port.unwrap_or(22);
+        for line in ssh_config.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            match key {
+                "hostname" => host = value.to_string(),
+                "port" => port = value.parse().unwrap_or(port),
+                "proxyjump" | "proxycommand" if value != "none" => {
+                    return ResolvedDestination::Proxied;
+                }
+                _ => {}
+            }
+        }
+        ResolvedDestination::Direct { host, port }
+    }
+
+    /// Checks whether the host answers an SSH connection attempt within `timeout`, without
+    /// prompting for credentials. A host that rejects the attempt for lack of credentials
+    /// still counts as reachable.
+    pub async fn probe(&self, timeout: Duration) -> bool {
+        let mut command = util::command::new_smol_command("ssh");
+        command
+            .args(self.probe_args(timeout))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let output = command.output().fuse();
+        let timer = smol::Timer::after(timeout).fuse();
+        futures::pin_mut!(output, timer);
+        select! {
+            output = output => match output {
+                Ok(output) => probe_answered(
+                    output.status.success(),
+                    &String::from_utf8_lossy(&output.stderr),
+                ),
+                Err(error) => {
+                    log::error!("failed to probe {}: {error}", self.connection_string());
+                    false
+                }
+            },
+            _ = timer => false,
+        }
+    }
+
+    /// Checks that a TCP connection to the host's SSH port can be opened within `timeout`,
+    /// which is much quicker to fail than a full connection attempt for mistyped addresses.
+    /// The host name and port are resolved through ssh_config first, and hosts reached through
+    /// a proxy are not checked at all.
+    pub async fn check_reachable(&self, timeout: Duration) -> Result<()> {
+        let output = util::command::new_smol_command("ssh")
+            .args(self.resolve_config_args())
+            .stdin(Stdio::null())
+            .output()
+            .await;
+        let (host, port) = match output {
+            Ok(output) if output.status.success() => {
+                match self.parse_resolved_destination(&String::from_utf8_lossy(&output.stdout)) {
+                    ResolvedDestination::Direct { host, port } => (host, port),
+                    ResolvedDestination::Proxied => return Ok(()),
+                }
+            }
+            Ok(_) => (self.host.clone(), self.port.unwrap_or(22)),
+            Err(error) => {
+                log::debug!("failed to resolve ssh config for {}: {error}", self.host);
+                (self.host.clone(), self.port.unwrap_or(22))
+            }
+        };
+
+        let connect = smol::net::TcpStream::connect((host.as_str(), port)).fuse();
+        let timer = smol::Timer::after(timeout).fuse();
+        futures::pin_mut!(connect, timer);
+        select! {
+            result = connect => match result {
+                Ok(_) => Ok(()),
+                Err(error) if error.kind() == std::io::ErrorKind::ConnectionRefused => {
+                    Err(anyhow!("host {host} refused the connection on port {port}"))
+                }
+                Err(error) => Err(anyhow!("host {host} unreachable on port {port}: {error}")),
+            },
+            _ = timer => Err(anyhow!("host {host} unreachable on port {port}: timed out")),
+        }
+    }
+
+    /// Removes the host's entries from the user's `known_hosts`, so that a host whose key has
+    /// changed can be connected to again with [`HostKeyChecking::AcceptNew`].
+    pub async fn forget_host_key(&self) -> Result<()> {
+        let host = match self.port {
+            Some(port) if port != 22 => format!("[{}]:{port}", self.host),
+            _ => self.host.clone(),
+        };
+        let output = util::command::new_smol_command("ssh-keygen")
+            .arg("-R")
+            .arg(&host)
+            .output()
+            .await
+            .context("running ssh-keygen")?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to remove the host key of {host}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+
     fn scp_url(&self) -> String {
         if let Some(username) = &self.username {
             format!("{}@{}", username, self.host)
@@ -384,6 +629,30 @@ impl SshSocket {
         command
     }
 
+    /// Asks the master process to add a port forward. Forwards it already has are left as is.
+    #[cfg(not(target_os = "windows"))]
+    async fn forward_port(&self, forward: &SshPortForwardOption) -> Result<()> {
+        let mut command = util::command::new_smol_command("ssh");
+        let output = self
+            .ssh_options(&mut command)
+            .args(["-O", "forward"])
+            .arg(format!("-L{}", forward.spec()))
+            .arg(self.connection_options.ssh_url())
+            .output()
+            .await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    async fn forward_port(&self, _: &SshPortForwardOption) -> Result<()> {
+        anyhow::bail!("port forwards can't be added to an established connection on Windows")
+    }
+
     async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
         let output = self.ssh_command(program, args).output().await?;
         anyhow::ensure!(
@@ -475,6 +744,46 @@ const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
 
 const MAX_RECONNECT_ATTEMPTS: usize = 3;
 
+const PORT_FORWARD_ATTEMPTS: u32 = 3;
+const PORT_FORWARD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Sets up each of `forwards` with `forward_port`, retrying the ones that fail up to
+/// [`PORT_FORWARD_ATTEMPTS`] times in total, and waiting with `wait` for an exponentially
+/// growing delay before each retry. Returns the restored forwards and the ones that still failed.
+async fn retry_port_forwards<F, W>(
+    forwards: Vec<SshPortForwardOption>,
+    mut forward_port: impl FnMut(SshPortForwardOption) -> F,
+    mut wait: impl FnMut(Duration) -> W,
+) -> (
+    Vec<SshPortForwardOption>,
+    Vec<(SshPortForwardOption, anyhow::Error)>,
+)
+where
+    F: Future<Output = Result<()>>,
+    W: Future<Output = ()>,
+{
+    let mut restored = Vec::new();
+    let mut pending = forwards;
+    let mut failed = Vec::new();
+    for attempt in 0..PORT_FORWARD_ATTEMPTS {
+        if attempt > 0 {
+            wait(PORT_FORWARD_RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+        }
+        failed.clear();
+        for forward in pending.drain(..) {
+            match forward_port(forward.clone()).await {
+                Ok(()) => restored.push(forward),
+                Err(error) => failed.push((forward, error)),
+            }
+        }
+        if failed.is_empty() {
+            break;
+        }
+        pending = failed.iter().map(|(forward, _)| forward.clone()).collect();
+    }
+    (restored, failed)
+}
+
 enum State {
     Connecting,
     Connected {
@@ -557,6 +866,10 @@ impl State {
         matches!(self, Self::Reconnecting { .. })
     }
 
+    fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
     fn heartbeat_recovered(self) -> Self {
         match self {
             Self::HeartbeatMissed {
@@ -641,6 +954,11 @@ pub struct SshRemoteClient {
 #[derive(Debug)]
 pub enum SshRemoteEvent {
     Disconnected,
+    /// The configured port forwards were requested again after reconnecting.
+    PortForwardsReestablished {
+        restored: Vec<SshPortForwardOption>,
+        failed: Vec<(SshPortForwardOption, SharedString)>,
+    },
 }
 
 impl EventEmitter<SshRemoteEvent> for SshRemoteClient {}
@@ -963,6 +1281,9 @@ impl SshRemoteClient {
                     this.reconnect(cx)
                 } else if this.state_is(State::is_reconnect_exhausted) {
                     Ok(())
+                } else if this.state_is(State::is_connected) {
+                    this.reestablish_port_forwards(cx).detach();
+                    Ok(())
                 } else {
                     log::debug!("State has transition from Reconnecting into new state while attempting reconnect.");
                     Ok(())
@@ -974,6 +1295,56 @@ impl SshRemoteClient {
         Ok(())
     }
 
+    /// Asks the connection to forward the configured ports again, as ssh only warns about
+    /// forwards it fails to set up when connecting, e.g. because a port was still in use.
+    /// Failed forwards are retried a few times, and each one that can't be restored is logged.
+    pub fn reestablish_port_forwards(&self, cx: &mut Context<Self>) -> Task<()> {
+        let forwards = self
+            .connection_options
+            .port_forwards
+            .clone()
+            .unwrap_or_default();
+        let Some(ssh_connection) = self.state.lock().as_ref().and_then(|state| match state {
+            State::Connected { ssh_connection, .. } => Some(ssh_connection.clone()),
+            _ => None,
+        }) else {
+            return Task::ready(());
+        };
+        if forwards.is_empty() {
+            return Task::ready(());
+        }
+
+        cx.spawn(async move |this, cx| {
+            let executor = cx.background_executor().clone();
+            let (restored, failed) = retry_port_forwards(
+                forwards,
+                |forward| {
+                    let ssh_connection = ssh_connection.clone();
+                    async move { ssh_connection.forward_port(&forward).await }
+                },
+                |delay| executor.timer(delay),
+            )
+            .await;
+
+            for (forward, error) in &failed {
+                log::error!(
+                    "failed to restore port forward {}: {error:#}",
+                    forward.spec()
+                );
+            }
+            this.update(cx, |_, cx| {
+                cx.emit(SshRemoteEvent::PortForwardsReestablished {
+                    restored,
+                    failed: failed
+                        .into_iter()
+                        .map(|(forward, error)| (forward, error.to_string().into()))
+                        .collect(),
+                });
+            })
+            .ok();
+        })
+    }
+
     fn heartbeat(
         this: WeakEntity<Self>,
         mut connection_activity_rx: mpsc::Receiver<()>,
@@ -1188,10 +1559,30 @@ impl SshRemoteClient {
         self.connection_state() == ConnectionState::Disconnected
     }
 
+    /// Forgets the established connections to the given host, so that the next connection to
+    /// it authenticates from scratch instead of reusing an existing SSH session. Clients that
+    /// are already connected keep their connection.
+    pub fn forget_connections_to_host(host: &str, cx: &mut App) {
+        cx.update_default_global(|pool: &mut ConnectionPool, _| {
+            pool.connections.retain(|opts, entry| {
+                opts.host != host || matches!(entry, ConnectionPoolEntry::Connecting(_))
+            });
+        });
+    }
+
     pub fn path_style(&self) -> PathStyle {
         self.path_style
     }
 
+    /// Checks whether `path` exists on the server, without opening a project there.
+    pub fn path_exists(&self, path: &str) -> impl Future<Output = Result<bool>> + use<> {
+        let request = self.proto_client().request(proto::GetPathMetadata {
+            project_id: proto::SSH_PROJECT_ID,
+            path: RemotePathBuf::from_str(path, self.path_style).to_proto(),
+        });
+        async move { Ok(request.await?.exists) }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn simulate_disconnect(&self, client_cx: &mut App) -> Task<()> {
         let opts = self.connection_options();
@@ -1381,6 +1772,8 @@ trait RemoteConnection: Send + Sync {
     /// On Windows, we need to use `SSH_ASKPASS` to provide the password to ssh.
     /// On Linux, we use the `ControlPath` option to create a socket file that ssh can use to
     fn ssh_args(&self) -> SshArgs;
+    /// Forwards the given port over the established connection, if it isn't already.
+    async fn forward_port(&self, forward: &SshPortForwardOption) -> Result<()>;
     fn connection_options(&self) -> SshConnectionOptions;
     fn path_style(&self) -> PathStyle;
 
@@ -1416,6 +1809,10 @@ impl RemoteConnection for SshRemoteConnection {
         self.socket.ssh_args()
     }
 
+    async fn forward_port(&self, forward: &SshPortForwardOption) -> Result<()> {
+        self.socket.forward_port(forward).await
+    }
+
     fn connection_options(&self) -> SshConnectionOptions {
         self.socket.connection_options.clone()
     }
@@ -1613,16 +2010,13 @@ impl SshRemoteConnection {
             return Err(e.context("Failed to connect to host"));
         }
 
-        if master_process.try_status()?.is_some() {
+        if let Some(status) = master_process.try_status()? {
             output.clear();
             let mut stderr = master_process.stderr.take().unwrap();
             stderr.read_to_end(&mut output).await?;
 
-            let error_message = format!(
-                "failed to connect: {}",
-                String::from_utf8_lossy(&output).trim()
-            );
-            anyhow::bail!(error_message);
+            let output = String::from_utf8_lossy(&output);
+            return Err(connection_failed_error(status.code(), &output));
         }
 
         #[cfg(not(target_os = "windows"))]
@@ -2586,7 +2980,7 @@ mod fake {
 
     use super::{
         ChannelClient, RemoteConnection, SshArgs, SshClientDelegate, SshConnectionOptions,
-        SshPlatform,
+        SshPlatform, SshPortForwardOption,
     };
 
     pub(super) struct FakeRemoteConnection {
@@ -2629,6 +3023,10 @@ mod fake {
             }
         }
 
+        async fn forward_port(&self, _: &SshPortForwardOption) -> Result<()> {
+            Ok(())
+        }
+
         fn upload_directory(
             &self,
             _src_path: PathBuf,
@@ -2724,3 +3122,215 @@ mod fake {
         fn set_status(&self, _: Option<&str>, _: &mut AsyncApp) {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_round_trip() {
+        let options = SshConnectionOptions {
+            host: "example.com".to_string(),
+            username: Some("me".to_string()),
+            port: Some(2222),
+            args: Some(vec!["-i".to_string(), "~/.ssh/my key".to_string()]),
+            port_forwards: Some(vec![
+                SshPortForwardOption {
+                    local_host: Some("localhost".to_string()),
+                    local_port: 8080,
+                    remote_host: Some("localhost".to_string()),
+                    remote_port: 80,
+                },
+                SshPortForwardOption {
+                    local_host: Some("127.0.0.1".to_string()),
+                    local_port: 5432,
+                    remote_host: Some("db.internal".to_string()),
+                    remote_port: 5432,
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let parsed = SshConnectionOptions::parse_command_line(&options.command_line()).unwrap();
+        assert_eq!(parsed, options);
+    }
+
+    #[test]
+    fn test_probe_args() {
+        let options = SshConnectionOptions {
+            host: "example.com".to_string(),
+            username: Some("me".to_string()),
+            port: Some(2222),
+            args: Some(vec!["-i".to_string(), "~/.ssh/id_test".to_string()]),
+            port_forwards: Some(vec![SshPortForwardOption {
+                local_host: None,
+                local_port: 8080,
+                remote_host: None,
+                remote_port: 80,
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.probe_args(Duration::from_millis(500)),
+            [
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                "ConnectTimeout=1",
+                "-i",
+                "~/.ssh/id_test",
+                "ssh://me@example.com:2222",
+                "exit",
+            ]
+        );
+        assert_eq!(
+            options.resolve_config_args(),
+            ["-i", "~/.ssh/id_test", "-p2222", "-G", "example.com"]
+        );
+
+        assert!(probe_answered(true, ""));
+        assert!(probe_answered(
+            false,
+            "me@example.com: Permission denied (publickey)."
+        ));
+        assert!(!probe_answered(
+            false,
+            "ssh: connect to host example.com port 2222: Connection refused"
+        ));
+    }
+
+    #[test]
+    fn test_parse_resolved_destination() {
+        let options = SshConnectionOptions {
+            host: "web".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.parse_resolved_destination(
+                "user me\nhostname web.example.com\nport 2200\nproxyjump none\n"
+            ),
+            ResolvedDestination::Direct {
+                host: "web.example.com".to_string(),
+                port: 2200,
+            }
+        );
+        assert_eq!(
+            options.parse_resolved_destination("hostname web.example.com\nproxyjump bastion\n"),
+            ResolvedDestination::Proxied
+        );
+        assert_eq!(
+            options.parse_resolved_destination(
+                "hostname web.example.com\nproxycommand ssh -W %h:%p bastion\n"
+            ),
+            ResolvedDestination::Proxied
+        );
+        assert_eq!(
+            options.parse_resolved_destination(""),
+            ResolvedDestination::Direct {
+                host: "web".to_string(),
+                port: 22,
+            }
+        );
+
+        let options = SshConnectionOptions {
+            port: Some(2222),
+            ..options
+        };
+        assert_eq!(
+            options.parse_resolved_destination("port not-a-port\n"),
+            ResolvedDestination::Direct {
+                host: "web".to_string(),
+                port: 2222,
+            }
+        );
+    }
+
+    #[test]
+    fn test_retry_port_forwards() {
+        let forward = |local_port| SshPortForwardOption {
+            local_host: None,
+            local_port,
+            remote_host: None,
+            remote_port: 80,
+        };
+        let attempts = std::cell::RefCell::new(HashMap::<u16, u32>::default());
+        let waits = std::cell::RefCell::new(Vec::new());
+
+        // 8080 succeeds right away, 8081 on its second attempt, and 8082 never does.
+        let (restored, failed) = smol::block_on(retry_port_forwards(
+            vec![forward(8080), forward(8081), forward(8082)],
+            |forward| {
+                let mut attempts = attempts.borrow_mut();
+                let attempt = attempts.entry(forward.local_port).or_default();
+                *attempt += 1;
+                let result = match (forward.local_port, *attempt) {
+                    (8080, _) | (8081, 2..) => Ok(()),
+                    _ => Err(anyhow!("address already in use")),
+                };
+                async move { result }
+            },
+            |delay| {
+                waits.borrow_mut().push(delay);
+                async {}
+            },
+        ));
+
+        assert_eq!(restored, [forward(8080), forward(8081)]);
+        assert_eq!(
+            failed
+                .iter()
+                .map(|(forward, _)| forward.clone())
+                .collect::<Vec<_>>(),
+            [forward(8082)]
+        );
+        assert_eq!(attempts.borrow()[&8080], 1);
+        assert_eq!(attempts.borrow()[&8081], 2);
+        assert_eq!(attempts.borrow()[&8082], PORT_FORWARD_ATTEMPTS);
+        assert_eq!(
+            *waits.borrow(),
+            [PORT_FORWARD_RETRY_DELAY, PORT_FORWARD_RETRY_DELAY * 2]
+        );
+    }
+
+    /// What OpenSSH 9 prints when connecting to a host whose key changed with
+    /// `StrictHostKeyChecking` enabled, before exiting with 255.
+    const HOST_KEY_CHANGED_STDERR: &str = "\
+@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+@    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @
+@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@
+IT IS POSSIBLE THAT SOMEONE IS DOING SOMETHING NASTY!
+Someone could be eavesdropping on you right now (man-in-the-middle attack)!
+It is also possible that a host key has just been changed.
+The fingerprint for the ED25519 key sent by the remote host is
+SHA256:3wGiIWLRYtnO2+JVlZqzGGV0M8PgK4WmyQxCT2bqbIE.
+Please contact your system administrator.
+Add correct host key in /home/me/.ssh/known_hosts to get rid of this message.
+Offending ED25519 key in /home/me/.ssh/known_hosts:12
+  remove with:
+  ssh-keygen -f '/home/me/.ssh/known_hosts' -R 'example.com'
+Host key for example.com has changed and you have requested strict checking.
+Host key verification failed.
+";
+
+    #[test]
+    fn test_host_key_changed_error() {
+        let error = connection_failed_error(Some(255), HOST_KEY_CHANGED_STDERR);
+        assert!(is_host_key_changed_error(&error));
+        assert!(is_host_key_changed_error(
+            &error.context("Failed to connect to host")
+        ));
+
+        assert!(!is_host_key_changed_error(&connection_failed_error(
+            Some(1),
+            HOST_KEY_CHANGED_STDERR
+        )));
+        assert!(!is_host_key_changed_error(&connection_failed_error(
+            None,
+            HOST_KEY_CHANGED_STDERR
+        )));
+        assert!(!is_host_key_changed_error(&connection_failed_error(
+            Some(255),
+            "me@example.com: Permission denied (publickey,password)."
+        )));
+    }
+}
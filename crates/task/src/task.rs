@@ -282,6 +282,10 @@ impl TaskVariables {
     pub fn iter(&self) -> impl Iterator<Item = (&VariableName, &String)> {
         self.0.iter()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl FromIterator<(VariableName, String)> for TaskVariables {
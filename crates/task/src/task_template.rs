@@ -63,6 +63,10 @@ pub struct TaskTemplate {
     #[serde(default, deserialize_with = "non_empty_string_vec")]
     #[schemars(length(min = 1))]
     pub tags: Vec<String>,
+    /// The group to list this task under in the task picker, e.g. `test` or `build`.
+    /// Tasks sharing a group are shown together.
+    #[serde(default)]
+    pub group: Option<String>,
     /// Which shell to use when spawning the task.
     #[serde(default)]
     pub shell: Shell,
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use crate::TaskContexts;
+use collections::HashMap;
 use editor::Editor;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -45,6 +46,23 @@ pub struct TaskOverrides {
 }
 
 impl TasksModalDelegate {
+    /// The group of the task listed at `ix`, if that task starts a section of its group.
+    /// Sections are only shown while the query is empty, below the recently used tasks.
+    fn group_label_at(&self, ix: usize) -> Option<&str> {
+        if !self.prompt.is_empty() || Some(ix) <= self.divider_index {
+            return None;
+        }
+        let candidates = self.candidates.as_ref()?;
+        let group_at = |ix: usize| {
+            let (_, task) = candidates.get(self.matches.get(ix)?.candidate_id)?;
+            task.original_task().group.as_deref()
+        };
+        let group = group_at(ix)?;
+        let starts_section =
+            ix == 0 || Some(ix - 1) <= self.divider_index || group_at(ix - 1) != Some(group);
+        starts_section.then_some(group)
+    }
+
     fn new(
         task_store: Entity<TaskStore>,
         task_contexts: Arc<TaskContexts>,
@@ -370,6 +388,16 @@ impl PickerDelegate for TasksModalDelegate {
                     if let Some(index) = delegate.last_used_candidate_index {
                         delegate.matches.sort_by_key(|m| m.candidate_id > index);
                     }
+                    if query.is_empty()
+                        && let Some(candidates) = &delegate.candidates
+                    {
+                        let history_len = delegate.last_used_candidate_index.map_or(0, |index| {
+                            delegate
+                                .matches
+                                .partition_point(|m| m.candidate_id <= index)
+                        });
+                        group_matches(&mut delegate.matches[history_len..], candidates);
+                    }
 
                     delegate.prompt = query;
                     delegate.divider_index = delegate.last_used_candidate_index.and_then(|index| {
@@ -509,6 +537,11 @@ impl PickerDelegate for TasksModalDelegate {
             IconWithIndicator::new(icon, indicator)
                 .indicator_border_color(Some(cx.theme().colors().border_transparent))
         });
+        let group_label = self.group_label_at(ix).map(|group| {
+            Label::new(group.to_owned())
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+        });
         let history_run_icon = if Some(ix) <= self.divider_index {
             Some(
                 Icon::new(IconName::HistoryRerun)
@@ -532,6 +565,7 @@ impl PickerDelegate for TasksModalDelegate {
                 .end_slot::<AnyElement>(
                     h_flex()
                         .gap_1()
+                        .children(group_label)
                         .child(Label::new(truncate_and_trailoff(
                             &template
                                 .tags
@@ -630,11 +664,17 @@ impl PickerDelegate for TasksModalDelegate {
     }
 
     fn separators_after_indices(&self) -> Vec<usize> {
+        let mut separators = Vec::new();
         if let Some(i) = self.divider_index {
-            vec![i]
-        } else {
-            Vec::new()
+            separators.push(i);
         }
+        separators.extend(
+            (1..self.matches.len())
+                .filter(|&ix| self.group_label_at(ix).is_some())
+                .map(|ix| ix - 1)
+                .filter(|&ix| Some(ix) != self.divider_index),
+        );
+        separators
     }
 
     fn render_footer(
@@ -737,6 +777,30 @@ impl PickerDelegate for TasksModalDelegate {
     }
 }
 
+/// Moves the tasks of each group right after the first task listed in that group, leaving the
+/// order of everything else unchanged.
+fn group_matches(matches: &mut [StringMatch], candidates: &[(TaskSourceKind, ResolvedTask)]) {
+    let mut group_positions = HashMap::default();
+    let sort_keys = matches
+        .iter()
+        .enumerate()
+        .map(|(ix, m)| {
+            let group = candidates
+                .get(m.candidate_id)
+                .and_then(|(_, task)| task.original_task().group.as_deref());
+            match group {
+                Some(group) => *group_positions.entry(group).or_insert(ix),
+                None => ix,
+            }
+        })
+        .collect::<Vec<_>>();
+    let grouped = (0..matches.len())
+        .sorted_by_key(|&ix| (sort_keys[ix], ix))
+        .map(|ix| matches[ix].clone())
+        .collect::<Vec<_>>();
+    matches.clone_from_slice(&grouped);
+}
+
 fn string_match_candidates<'a>(
     candidates: impl IntoIterator<Item = &'a (TaskSourceKind, ResolvedTask)> + 'a,
 ) -> Vec<StringMatchCandidate> {
@@ -1253,6 +1317,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_group_matches() {
+        let candidates = [
+            ("build", Some("build")),
+            ("test file", Some("test")),
+            ("execute selection", None),
+            ("package.json > lint", Some("scripts")),
+            ("test symbol", Some("test")),
+            ("build release", Some("build")),
+        ]
+        .into_iter()
+        .map(|(label, group)| {
+            let task = TaskTemplate {
+                label: label.to_owned(),
+                command: "echo".to_owned(),
+                group: group.map(ToOwned::to_owned),
+                ..TaskTemplate::default()
+            }
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+            (TaskSourceKind::UserInput, task)
+        })
+        .collect::<Vec<_>>();
+        let mut matches = candidates
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, (_, task))| StringMatch {
+                candidate_id,
+                score: 0.,
+                positions: Vec::new(),
+                string: task.resolved_label.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        group_matches(&mut matches, &candidates);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|hit| hit.string.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "build",
+                "build release",
+                "test file",
+                "test symbol",
+                "execute selection",
+                "package.json > lint",
+            ]
+        );
+    }
+
     fn emulate_task_schedule(
         tasks_picker: Entity<Picker<TasksModalDelegate>>,
         project: &Entity<Project>,
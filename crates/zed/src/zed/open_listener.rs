@@ -621,6 +621,8 @@ mod tests {
                 port_forwards: None,
                 nickname: None,
                 upload_binary_over_ssh: false,
+                shell: None,
+                host_key_checking: None,
             }
         );
         assert_eq!(request.open_paths, vec!["/"]);
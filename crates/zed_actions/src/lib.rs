@@ -140,7 +140,9 @@ pub mod dev {
         dev,
         [
             /// Toggles the developer inspector for debugging UI elements.
-            ToggleInspector
+            ToggleInspector,
+            /// Opens the language server protocol logs viewer.
+            OpenLanguageServerLogs
         ]
     );
 }